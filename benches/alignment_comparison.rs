@@ -1,6 +1,6 @@
 use const_secret::{
     ByteArray, Encrypted,
-    align::{Aligned8, Aligned16},
+    align::{Aligned8, Aligned16, Aligned32, Aligned64},
     drop_strategy::NoOp,
     rc4::Rc4,
     xor::Xor,
@@ -38,6 +38,22 @@ fn xor_alignment_size_23(c: &mut Criterion) {
         });
     });
 
+    group.bench_function("aligned32", |b| {
+        b.iter(|| {
+            let e: Aligned32<Encrypted<Xor<0xAA, NoOp>, ByteArray, 23>> =
+                Aligned32(Encrypted::<Xor<0xAA, NoOp>, ByteArray, 23>::new([0u8; 23]));
+            black_box(&**e);
+        });
+    });
+
+    group.bench_function("aligned64", |b| {
+        b.iter(|| {
+            let e: Aligned64<Encrypted<Xor<0xAA, NoOp>, ByteArray, 23>> =
+                Aligned64(Encrypted::<Xor<0xAA, NoOp>, ByteArray, 23>::new([0u8; 23]));
+            black_box(&**e);
+        });
+    });
+
     group.finish();
 }
 
@@ -69,6 +85,22 @@ fn xor_alignment_size_53(c: &mut Criterion) {
         });
     });
 
+    group.bench_function("aligned32", |b| {
+        b.iter(|| {
+            let e: Aligned32<Encrypted<Xor<0xAA, NoOp>, ByteArray, 53>> =
+                Aligned32(Encrypted::<Xor<0xAA, NoOp>, ByteArray, 53>::new([0u8; 53]));
+            black_box(&**e);
+        });
+    });
+
+    group.bench_function("aligned64", |b| {
+        b.iter(|| {
+            let e: Aligned64<Encrypted<Xor<0xAA, NoOp>, ByteArray, 53>> =
+                Aligned64(Encrypted::<Xor<0xAA, NoOp>, ByteArray, 53>::new([0u8; 53]));
+            black_box(&**e);
+        });
+    });
+
     group.finish();
 }
 
@@ -100,6 +132,22 @@ fn xor_alignment_size_89(c: &mut Criterion) {
         });
     });
 
+    group.bench_function("aligned32", |b| {
+        b.iter(|| {
+            let e: Aligned32<Encrypted<Xor<0xAA, NoOp>, ByteArray, 89>> =
+                Aligned32(Encrypted::<Xor<0xAA, NoOp>, ByteArray, 89>::new([0u8; 89]));
+            black_box(&**e);
+        });
+    });
+
+    group.bench_function("aligned64", |b| {
+        b.iter(|| {
+            let e: Aligned64<Encrypted<Xor<0xAA, NoOp>, ByteArray, 89>> =
+                Aligned64(Encrypted::<Xor<0xAA, NoOp>, ByteArray, 89>::new([0u8; 89]));
+            black_box(&**e);
+        });
+    });
+
     group.finish();
 }
 
@@ -1,3 +1,6 @@
+// Secrets are declared as `const` items on purpose: every use yields a fresh, still-encrypted copy.
+#![allow(clippy::declare_interior_mutable_const)]
+
 use const_secret::{ByteArray, Encrypted, drop_strategy::NoOp, rc4::Rc4, xor::Xor};
 use criterion::{Criterion, criterion_group, criterion_main};
 use std::{hint::black_box, sync::Arc, thread};
@@ -1,5 +1,5 @@
 use const_secret::{ByteArray, Encrypted, drop_strategy::NoOp, rc4::Rc4};
-use criterion::{Criterion, criterion_group, criterion_main};
+use criterion::{BatchSize, Criterion, Throughput, criterion_group, criterion_main};
 use std::hint::black_box;
 
 // RC4 keys of different realistic sizes
@@ -134,6 +134,24 @@ fn rc4_cached_access_size_89(c: &mut Criterion) {
     });
 }
 
+// First-decrypt latency of a large blob, as documented on `rc4::Rc4Large`.
+// The secret is sealed in the setup closure so only decryption is measured.
+fn rc4_key16_first_decrypt_size_64k(c: &mut Criterion) {
+    const N: usize = 64 * 1024;
+    let mut group = c.benchmark_group("rc4_key16_first_decrypt_size_64k");
+    group.throughput(Throughput::Bytes(N as u64));
+    group.bench_function("rc4", |b| {
+        b.iter_batched(
+            || Box::new(Encrypted::<Rc4<16, NoOp<[u8; 16]>>, ByteArray, N>::new([0u8; N], KEY_16)),
+            |e| {
+                black_box(&**e);
+            },
+            BatchSize::LargeInput,
+        );
+    });
+    group.finish();
+}
+
 criterion_group!(
     benches,
     rc4_key5_first_decrypt_size_7,
@@ -148,5 +166,6 @@ criterion_group!(
     rc4_cached_access_size_7,
     rc4_cached_access_size_23,
     rc4_cached_access_size_89,
+    rc4_key16_first_decrypt_size_64k,
 );
 criterion_main!(benches);
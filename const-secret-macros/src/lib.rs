@@ -0,0 +1,150 @@
+//! Procedural macros for [`const-secret`](https://docs.rs/const-secret).
+//!
+//! Use them through the `macros` feature of `const-secret`, which re-exports them as
+//! `const_secret::macros`, rather than depending on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::Literal;
+use quote::quote;
+use syn::{
+    Error, Expr, Lit, Result, Token, Type,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+};
+
+mod kw {
+    syn::custom_keyword!(algorithm);
+    syn::custom_keyword!(mode);
+}
+
+/// Builds an `Encrypted` value from a literal, inferring its length `N`.
+///
+/// ```text
+/// secret!(algorithm: <Algorithm>, mode: <Mode>, <literal> [, <constructor args>...])
+/// ```
+///
+/// Expands to `::const_secret::Encrypted::<Algorithm, Mode, N>::new(*b"...", args...)`.
+/// The literal may be a string (`"..."`, which must be ASCII), a byte string
+/// (`b"..."`) or a C string (`c"..."`, which includes its NUL terminator). Arguments
+/// after the literal are passed on to the algorithm's constructor, e.g. the RC4 key.
+///
+/// The literal is checked against the mode: a `StringLiteral` secret must be UTF-8,
+/// and a `CStrLiteral` one must end with its only NUL byte.
+#[proc_macro]
+pub fn secret(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as SecretInput);
+    input.expand().unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// The parsed arguments of [`secret!`].
+struct SecretInput {
+    algorithm: Type,
+    mode: Type,
+    literal: Lit,
+    args: Vec<Expr>,
+}
+
+impl Parse for SecretInput {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        input.parse::<kw::algorithm>()?;
+        input.parse::<Token![:]>()?;
+        let algorithm = input.parse()?;
+        input.parse::<Token![,]>()?;
+
+        input.parse::<kw::mode>()?;
+        input.parse::<Token![:]>()?;
+        let mode = input.parse()?;
+        input.parse::<Token![,]>()?;
+
+        let literal = input.parse()?;
+        let args = if input.is_empty() {
+            Vec::new()
+        } else {
+            input.parse::<Token![,]>()?;
+            Punctuated::<Expr, Token![,]>::parse_terminated(input)?.into_iter().collect()
+        };
+
+        Ok(Self {
+            algorithm,
+            mode,
+            literal,
+            args,
+        })
+    }
+}
+
+impl SecretInput {
+    fn expand(self) -> Result<proc_macro2::TokenStream> {
+        let bytes = self.plaintext()?;
+        self.check_mode(&bytes)?;
+
+        let Self {
+            algorithm,
+            mode,
+            args,
+            ..
+        } = &self;
+        let len = Literal::usize_unsuffixed(bytes.len());
+        let mut literal = Literal::byte_string(&bytes);
+        literal.set_span(self.literal.span());
+
+        Ok(quote! {
+            ::const_secret::Encrypted::<#algorithm, #mode, #len>::new(*#literal #(, #args)*)
+        })
+    }
+
+    /// Returns the bytes of the literal.
+    fn plaintext(&self) -> Result<Vec<u8>> {
+        match &self.literal {
+            Lit::Str(lit) => {
+                let value = lit.value();
+                if !value.is_ascii() {
+                    return Err(Error::new(
+                        lit.span(),
+                        "string literal secrets must be ASCII; spell other bytes out in a \
+                         byte string literal (`b\"...\"`)",
+                    ));
+                }
+                Ok(value.into_bytes())
+            }
+            Lit::ByteStr(lit) => Ok(lit.value()),
+            Lit::CStr(lit) => Ok(lit.value().into_bytes_with_nul()),
+            other => {
+                Err(Error::new(other.span(), "expected a string, byte string or C string literal"))
+            }
+        }
+    }
+
+    /// Rejects plaintext the mode cannot represent.
+    fn check_mode(&self, bytes: &[u8]) -> Result<()> {
+        let span = self.literal.span();
+        match mode_name(&self.mode).as_deref() {
+            Some("StringLiteral") if core::str::from_utf8(bytes).is_err() => {
+                Err(Error::new(span, "a `StringLiteral` secret must be valid UTF-8"))
+            }
+            Some("CStrLiteral") if !is_c_string(bytes) => Err(Error::new(
+                span,
+                "a `CStrLiteral` secret must end with `\\0` and contain no other NUL byte; \
+                 a `c\"...\"` literal adds the terminator",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Returns the last path segment of `mode`, such as `StringLiteral`.
+///
+/// Modes the macro does not recognise (e.g. behind a type alias) are left for the
+/// type checker.
+fn mode_name(mode: &Type) -> Option<String> {
+    match mode {
+        Type::Path(path) => path.path.segments.last().map(|segment| segment.ident.to_string()),
+        _ => None,
+    }
+}
+
+/// Returns `true` if the only NUL byte in `bytes` is the last one.
+fn is_c_string(bytes: &[u8]) -> bool {
+    matches!(bytes.split_last(), Some((&0, rest)) if !rest.contains(&0))
+}
@@ -1,3 +1,6 @@
+// Secrets are declared as `const` items on purpose: every use yields a fresh, still-encrypted copy.
+#![allow(clippy::declare_interior_mutable_const, clippy::explicit_auto_deref)]
+
 use const_secret::{
     ByteArray, Encrypted, StringLiteral,
     drop_strategy::{NoOp, Zeroize},
@@ -0,0 +1,148 @@
+//! Additive (Caesar-style) shift algorithm implementation.
+//!
+//! This module provides an algorithm that adds a single-byte key to every byte of the
+//! plaintext, wrapping on overflow, and subtracts it again to decrypt. Like
+//! [`Xor`](crate::xor::Xor) it is not cryptographically secure, only an obfuscation.
+//!
+//! # Algorithm
+//!
+//! [`AddShift`] computes `buffer[i].wrapping_add(SHIFT)` to encrypt and
+//! `buffer[i].wrapping_sub(SHIFT)` to decrypt. Unlike XOR, a shift moves every byte by
+//! the same distance, so it preserves the ordering of byte values and does not flip
+//! low bits in the way XOR does. ASCII text shifted by a small amount (such as `1`
+//! to `5`) mostly stays printable, which some scanners flag less than the control
+//! bytes an XOR key often produces.
+//!
+//! # Types
+//!
+//! - [`AddShift<SHIFT, D>`]: The algorithm type with const generic shift and drop strategy
+//! - [`ReEncrypt<SHIFT>`]: A drop strategy that shifts the data again on drop
+//!
+//! # Example
+//!
+//! ```rust
+//! use const_secret::{
+//!     Encrypted, StringLiteral,
+//!     add_shift::{AddShift, ReEncrypt},
+//!     drop_strategy::Zeroize,
+//! };
+//!
+//! const SECRET: Encrypted<AddShift<3, Zeroize>, StringLiteral, 5> =
+//!     Encrypted::<AddShift<3, Zeroize>, StringLiteral, 5>::new(*b"hello");
+//!
+//! const SECRET2: Encrypted<AddShift<1, ReEncrypt<1>>, StringLiteral, 6> =
+//!     Encrypted::<AddShift<1, ReEncrypt<1>>, StringLiteral, 6>::new(*b"secret");
+//!
+//! fn main() {
+//!     assert_eq!(&*SECRET, "hello");
+//!     assert_eq!(&*SECRET2, "secret");
+//! }
+//! ```
+
+use core::{cell::UnsafeCell, marker::PhantomData, sync::atomic::AtomicU8};
+
+use crate::{
+    Algorithm, Encrypted, Mode, STATE_UNENCRYPTED, check_plaintext,
+    drop_strategy::{DropStrategy, Residue, Zeroize},
+};
+
+/// Shifts [`AddShift`] data forward again on drop, restoring the ciphertext.
+pub struct ReEncrypt<const SHIFT: u8>;
+
+impl<const SHIFT: u8> DropStrategy for ReEncrypt<SHIFT> {
+    type Extra = ();
+    const RESIDUE: Residue = Residue::Ciphertext;
+
+    fn drop(data: &mut [u8], _extra: &()) {
+        shift(data, SHIFT);
+    }
+}
+
+/// Adds `amount` to every byte of `data`, wrapping on overflow.
+const fn shift(data: &mut [u8], amount: u8) {
+    // We use a while loop because const contexts do not allow for-loops.
+    let mut i = 0;
+    while i < data.len() {
+        data[i] = data[i].wrapping_add(amount);
+        i += 1;
+    }
+}
+
+/// An algorithm that adds a constant to each byte to encrypt and subtracts it to
+/// decrypt. This algorithm is generic over drop strategy.
+pub struct AddShift<const SHIFT: u8, D: DropStrategy = Zeroize>(PhantomData<D>);
+
+impl<const SHIFT: u8, D: DropStrategy<Extra = ()>> Algorithm for AddShift<SHIFT, D> {
+    type Drop = D;
+    type Extra = ();
+
+    fn encrypt(data: &mut [u8], _extra: &()) {
+        shift(data, SHIFT);
+    }
+
+    fn decrypt(data: &mut [u8], _extra: &()) {
+        shift(data, SHIFT.wrapping_neg());
+    }
+}
+
+impl<const SHIFT: u8, D: DropStrategy<Extra = ()>, M: Mode, const N: usize>
+    Encrypted<AddShift<SHIFT, D>, M, N>
+{
+    /// Creates a new encrypted buffer by shifting every byte by `SHIFT`.
+    pub const fn new(mut buffer: [u8; N]) -> Self {
+        check_plaintext::<M, N>(&buffer);
+        shift(&mut buffer, SHIFT);
+
+        Encrypted {
+            buffer: UnsafeCell::new(buffer),
+            decryption_state: AtomicU8::new(STATE_UNENCRYPTED),
+            extra: (),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::explicit_auto_deref)]
+mod tests {
+    use super::*;
+    use crate::{ByteArray, StringLiteral, builder::SealingBuilder};
+
+    #[test]
+    fn test_buffer_is_shifted_before_deref() {
+        let encrypted = Encrypted::<AddShift<3, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        assert_eq!(unsafe { *encrypted.buffer.get() }, *b"khoor");
+        assert_eq!(&*encrypted, "hello");
+    }
+
+    #[test]
+    fn test_wraps_around() {
+        let encrypted = Encrypted::<AddShift<2, Zeroize>, ByteArray, 3>::new([0xFE, 0xFF, 0x00]);
+        assert_eq!(unsafe { *encrypted.buffer.get() }, [0x00, 0x01, 0x02]);
+        assert_eq!(&*encrypted, &[0xFE, 0xFF, 0x00]);
+    }
+
+    #[test]
+    fn test_reencrypt_restores_ciphertext() {
+        let mut encrypted =
+            Encrypted::<AddShift<7, ReEncrypt<7>>, StringLiteral, 6>::new(*b"secret");
+        let sealed = unsafe { *encrypted.buffer.get() };
+        assert_eq!(&*encrypted, "secret");
+
+        encrypted.reset();
+        assert_eq!(unsafe { *encrypted.buffer.get() }, sealed);
+        assert_eq!(&*encrypted, "secret");
+    }
+
+    #[test]
+    fn test_runtime_sealing_matches_const_new() {
+        let mut builder: SealingBuilder<AddShift<200, Zeroize>, ByteArray, 4> =
+            Encrypted::with_capacity_sealed();
+        builder.push_segment(b"data").unwrap();
+        let sealed = builder.finish(()).unwrap();
+
+        let expected = Encrypted::<AddShift<200, Zeroize>, ByteArray, 4>::new(*b"data");
+        assert_eq!(unsafe { *sealed.buffer.get() }, unsafe { *expected.buffer.get() });
+        assert_eq!(&*sealed, b"data");
+    }
+}
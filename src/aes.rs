@@ -0,0 +1,413 @@
+//! AES-128 in CTR mode.
+//!
+//! This module provides AES-128 ([FIPS 197](https://csrc.nist.gov/pubs/fips/197/final))
+//! in counter mode ([NIST SP 800-38A](https://csrc.nist.gov/pubs/sp/800/38/a/final)).
+//! CTR mode turns the block cipher into a stream cipher, so it works with any buffer
+//! length `N` and preserves byte values for [`StringLiteral`](crate::StringLiteral)
+//! round-trips, just like [`rc4`](crate::rc4).
+//!
+//! # Security Note
+//!
+//! The key is stored alongside the ciphertext in the binary, so this is still
+//! obfuscation: it resists analysis of the ciphertext, not a reverse engineer who
+//! finds the key. This implementation uses table lookups and is not hardened against
+//! cache-timing side channels.
+//!
+//! # Algorithm
+//!
+//! 1. **Key expansion**: the 16-byte key is expanded into 11 round keys
+//! 2. **Keystream**: the 16-byte counter block (initially the IV) is encrypted with
+//!    10 AES rounds, then incremented as a 128-bit big-endian integer
+//! 3. The keystream is XOR'd with the plaintext
+//!
+//! # Types
+//!
+//! - [`Aes128Ctr<D>`](Aes128Ctr): The main algorithm type, generic over drop strategy
+//! - [`ReEncrypt`]: A drop strategy that re-encrypts data on drop
+//!
+//! # Example
+//!
+//! ```rust
+//! use const_secret::{
+//!     Encrypted, StringLiteral,
+//!     aes::{Aes128Ctr, Iv, Key, ReEncrypt},
+//!     drop_strategy::Zeroize,
+//! };
+//!
+//! const KEY: [u8; 16] = *b"sixteen byte key";
+//! const IV: [u8; 16] = *b"initial counter!";
+//!
+//! // Zeroize on drop (default)
+//! const SECRET: Encrypted<Aes128Ctr<Zeroize<(Key, Iv)>>, StringLiteral, 5> =
+//!     Encrypted::<Aes128Ctr<Zeroize<(Key, Iv)>>, StringLiteral, 5>::new(*b"hello", KEY, IV);
+//!
+//! // Re-encrypt on drop
+//! const SECRET2: Encrypted<Aes128Ctr<ReEncrypt>, StringLiteral, 6> =
+//!     Encrypted::<Aes128Ctr<ReEncrypt>, StringLiteral, 6>::new(*b"secret", KEY, IV);
+//!
+//! fn main() {
+//!     let s1: &str = &*SECRET;
+//!     assert_eq!(s1, "hello");
+//!
+//!     let s2: &str = &*SECRET2;
+//!     assert_eq!(s2, "secret");
+//! }
+//! ```
+
+use core::{cell::UnsafeCell, marker::PhantomData, sync::atomic::AtomicU8};
+
+use crate::{
+    Algorithm, Encrypted, Mode, STATE_UNENCRYPTED, check_plaintext,
+    drop_strategy::{DropStrategy, Residue, Zeroize},
+};
+
+/// An AES-128 key.
+pub type Key = [u8; 16];
+/// The initial CTR counter block.
+pub type Iv = [u8; 16];
+
+/// Number of rounds for AES-128.
+const ROUNDS: usize = 10;
+
+/// The AES S-box (FIPS 197, figure 7).
+#[rustfmt::skip]
+const SBOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,];
+
+/// Round constants for key expansion.
+const RCON: [u8; ROUNDS] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+/// Expands `key` into the 11 round keys.
+const fn expand_key(key: &Key) -> [[u8; 16]; ROUNDS + 1] {
+    let mut round_keys = [[0u8; 16]; ROUNDS + 1];
+    round_keys[0] = *key;
+
+    let mut round = 1;
+    while round <= ROUNDS {
+        let prev = round_keys[round - 1];
+        // RotWord, SubWord and Rcon on the last word of the previous round key
+        let mut temp = [
+            SBOX[prev[13] as usize] ^ RCON[round - 1],
+            SBOX[prev[14] as usize],
+            SBOX[prev[15] as usize],
+            SBOX[prev[12] as usize],
+        ];
+
+        let mut next = [0u8; 16];
+        let mut word = 0;
+        while word < 4 {
+            let mut i = 0;
+            while i < 4 {
+                next[word * 4 + i] = prev[word * 4 + i] ^ temp[i];
+                temp[i] = next[word * 4 + i];
+                i += 1;
+            }
+            word += 1;
+        }
+        round_keys[round] = next;
+        round += 1;
+    }
+
+    round_keys
+}
+
+/// Multiplies by `x` in GF(2^8).
+const fn xtime(b: u8) -> u8 {
+    (b << 1) ^ (((b >> 7) & 1) * 0x1b)
+}
+
+/// XORs a round key into the state.
+const fn add_round_key(state: &mut [u8; 16], round_key: &[u8; 16]) {
+    let mut i = 0;
+    while i < 16 {
+        state[i] ^= round_key[i];
+        i += 1;
+    }
+}
+
+/// `SubBytes` followed by `ShiftRows`. The state is column-major.
+const fn sub_shift(state: &mut [u8; 16]) {
+    let s = *state;
+    let mut col = 0;
+    while col < 4 {
+        let mut row = 0;
+        while row < 4 {
+            state[col * 4 + row] = SBOX[s[((col + row) % 4) * 4 + row] as usize];
+            row += 1;
+        }
+        col += 1;
+    }
+}
+
+/// `MixColumns`.
+const fn mix_columns(state: &mut [u8; 16]) {
+    let mut col = 0;
+    while col < 4 {
+        let a = [state[col * 4], state[col * 4 + 1], state[col * 4 + 2], state[col * 4 + 3]];
+        let all = a[0] ^ a[1] ^ a[2] ^ a[3];
+        let mut row = 0;
+        while row < 4 {
+            state[col * 4 + row] = a[row] ^ all ^ xtime(a[row] ^ a[(row + 1) % 4]);
+            row += 1;
+        }
+        col += 1;
+    }
+}
+
+/// Encrypts a single block with the expanded key.
+const fn encrypt_block(block: &[u8; 16], round_keys: &[[u8; 16]; ROUNDS + 1]) -> [u8; 16] {
+    let mut state = *block;
+    add_round_key(&mut state, &round_keys[0]);
+
+    let mut round = 1;
+    while round < ROUNDS {
+        sub_shift(&mut state);
+        mix_columns(&mut state);
+        add_round_key(&mut state, &round_keys[round]);
+        round += 1;
+    }
+
+    sub_shift(&mut state);
+    add_round_key(&mut state, &round_keys[ROUNDS]);
+    state
+}
+
+/// Increments the counter block as a 128-bit big-endian integer.
+const fn increment(counter: &mut [u8; 16]) {
+    let mut i = 16;
+    while i > 0 {
+        i -= 1;
+        counter[i] = counter[i].wrapping_add(1);
+        if counter[i] != 0 {
+            break;
+        }
+    }
+}
+
+/// Applies the AES-128-CTR keystream for `key` and `iv` to `data` in place.
+///
+/// CTR mode XORs the keystream into the buffer, so the same routine encrypts and
+/// decrypts. The final block is truncated when `data.len()` is not a multiple of 16.
+pub(crate) const fn apply_keystream(data: &mut [u8], key: &Key, iv: &Iv) {
+    let round_keys = expand_key(key);
+    let mut counter = *iv;
+    let mut offset = 0usize;
+    while offset < data.len() {
+        let keystream = encrypt_block(&counter, &round_keys);
+        let mut i = 0;
+        while i < 16 && offset + i < data.len() {
+            data[offset + i] ^= keystream[i];
+            i += 1;
+        }
+        offset += 16;
+        increment(&mut counter);
+    }
+}
+
+/// Re-encrypts the buffer using AES-128-CTR on drop.
+/// This ensures the plaintext never remains in memory after the value is dropped.
+pub struct ReEncrypt;
+
+impl DropStrategy for ReEncrypt {
+    type Extra = (Key, Iv);
+    const RESIDUE: Residue = Residue::Ciphertext;
+
+    fn drop(data: &mut [u8], extra: &(Key, Iv)) {
+        // Re-run AES-128-CTR to re-encrypt the buffer
+        apply_keystream(data, &extra.0, &extra.1);
+    }
+}
+
+/// An algorithm that performs AES-128-CTR encryption and decryption.
+/// This algorithm is generic over drop strategy.
+///
+/// The 16-byte key and 16-byte initial counter block are stored alongside the
+/// encrypted data and are used to reproduce the keystream for decryption at runtime.
+pub struct Aes128Ctr<D: DropStrategy = Zeroize<(Key, Iv)>>(PhantomData<D>);
+
+impl<D: DropStrategy<Extra = (Key, Iv)>> Algorithm for Aes128Ctr<D> {
+    type Drop = D;
+    type Extra = (Key, Iv);
+
+    fn encrypt(data: &mut [u8], extra: &(Key, Iv)) {
+        apply_keystream(data, &extra.0, &extra.1);
+    }
+}
+
+impl<D: DropStrategy<Extra = (Key, Iv)>, M: Mode, const N: usize> Encrypted<Aes128Ctr<D>, M, N> {
+    /// Creates a new encrypted buffer using AES-128-CTR.
+    ///
+    /// # Arguments
+    /// * `buffer` - The plaintext data to encrypt (must be an array of length N)
+    /// * `key` - The AES-128 key
+    /// * `iv` - The initial counter block
+    ///
+    /// This function performs AES-128-CTR encryption at compile time:
+    /// 1. Expands the key into the round keys
+    /// 2. Encrypts successive counter blocks to generate the keystream
+    /// 3. XORs the keystream with the plaintext
+    pub const fn new(mut buffer: [u8; N], key: Key, iv: Iv) -> Self {
+        check_plaintext::<M, N>(&buffer);
+        apply_keystream(&mut buffer, &key, &iv);
+
+        Encrypted {
+            buffer: UnsafeCell::new(buffer),
+            decryption_state: AtomicU8::new(STATE_UNENCRYPTED),
+            extra: (key, iv),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::borrow_interior_mutable_const,
+    clippy::declare_interior_mutable_const,
+    clippy::explicit_auto_deref
+)]
+mod tests {
+    use super::*;
+    use crate::{ByteArray, StringLiteral, drop_strategy::Zeroize};
+
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use std::sync::Arc;
+    use std::thread;
+
+    /// NIST SP 800-38A, F.5.1 CTR-AES128.Encrypt.
+    const NIST_KEY: Key = [
+        0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f,
+        0x3c,
+    ];
+    const NIST_IV: Iv = [
+        0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd, 0xfe,
+        0xff,
+    ];
+    #[rustfmt::skip]
+    const NIST_PLAINTEXT: [u8; 64] = [
+        0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96, 0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93, 0x17, 0x2a,
+        0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c, 0x9e, 0xb7, 0x6f, 0xac, 0x45, 0xaf, 0x8e, 0x51,
+        0x30, 0xc8, 0x1c, 0x46, 0xa3, 0x5c, 0xe4, 0x11, 0xe5, 0xfb, 0xc1, 0x19, 0x1a, 0x0a, 0x52, 0xef,
+        0xf6, 0x9f, 0x24, 0x45, 0xdf, 0x4f, 0x9b, 0x17, 0xad, 0x2b, 0x41, 0x7b, 0xe6, 0x6c, 0x37, 0x10,
+    ];
+    #[rustfmt::skip]
+    const NIST_CIPHERTEXT: [u8; 64] = [
+        0x87, 0x4d, 0x61, 0x91, 0xb6, 0x20, 0xe3, 0x26, 0x1b, 0xef, 0x68, 0x64, 0x99, 0x0d, 0xb6, 0xce,
+        0x98, 0x06, 0xf6, 0x6b, 0x79, 0x70, 0xfd, 0xff, 0x86, 0x17, 0x18, 0x7b, 0xb9, 0xff, 0xfd, 0xff,
+        0x5a, 0xe4, 0xdf, 0x3e, 0xdb, 0xd5, 0xd3, 0x5e, 0x5b, 0x4f, 0x09, 0x02, 0x0d, 0xb0, 0x3e, 0xab,
+        0x1e, 0x03, 0x1d, 0xda, 0x2f, 0xbe, 0x03, 0xd1, 0x79, 0x21, 0x70, 0xa0, 0xf3, 0x00, 0x9c, 0xee,
+    ];
+
+    type ZeroizeAes = Aes128Ctr<Zeroize<(Key, Iv)>>;
+
+    const CONST_ENCRYPTED: Encrypted<ZeroizeAes, ByteArray, 64> =
+        Encrypted::<ZeroizeAes, ByteArray, 64>::new(NIST_PLAINTEXT, NIST_KEY, NIST_IV);
+
+    const CONST_ENCRYPTED_STR: Encrypted<ZeroizeAes, StringLiteral, 5> =
+        Encrypted::<ZeroizeAes, StringLiteral, 5>::new(*b"hello", NIST_KEY, NIST_IV);
+
+    #[test]
+    fn test_block_matches_fips_197() {
+        // FIPS 197, appendix C.1
+        let key: Key = core::array::from_fn(|i| i as u8);
+        let plaintext: [u8; 16] = core::array::from_fn(|i| (i as u8) * 0x11);
+        let expected = [
+            0x69, 0xc4, 0xe0, 0xd8, 0x6a, 0x7b, 0x04, 0x30, 0xd8, 0xcd, 0xb7, 0x80, 0x70, 0xb4,
+            0xc5, 0x5a,
+        ];
+        assert_eq!(encrypt_block(&plaintext, &expand_key(&key)), expected);
+    }
+
+    #[test]
+    fn test_ciphertext_matches_sp_800_38a() {
+        let encrypted = CONST_ENCRYPTED;
+        let raw = unsafe { &*encrypted.buffer.get() };
+        assert_eq!(raw, &NIST_CIPHERTEXT);
+    }
+
+    #[test]
+    fn test_aes_bytearray_deref_decrypts() {
+        let encrypted = CONST_ENCRYPTED;
+        let decrypted: &[u8; 64] = &*encrypted;
+        assert_eq!(decrypted, &NIST_PLAINTEXT);
+    }
+
+    #[test]
+    fn test_aes_string_deref_decrypts() {
+        let encrypted = CONST_ENCRYPTED_STR;
+        let raw = unsafe { &*encrypted.buffer.get() };
+        assert_ne!(raw, b"hello", "buffer must NOT be plaintext before deref");
+
+        let decrypted: &str = &*encrypted;
+        assert_eq!(decrypted, "hello");
+    }
+
+    #[test]
+    fn test_counter_carries_across_bytes() {
+        let mut counter = [0xff; 16];
+        counter[0] = 0x00;
+        increment(&mut counter);
+        let mut expected = [0u8; 16];
+        expected[0] = 0x01;
+        assert_eq!(counter, expected);
+    }
+
+    #[test]
+    fn test_round_trip_partial_blocks() {
+        for len in [0, 1, 15, 16, 17, 33, 100] {
+            let plain: Vec<u8> = (0..len).map(|i| (i * 13) as u8).collect();
+            let mut data = plain.clone();
+            apply_keystream(&mut data, &NIST_KEY, &NIST_IV);
+            // A truncated final block must use a prefix of the full keystream.
+            assert_eq!(&data[..len.min(64)], &encrypt_prefix(&plain[..len.min(64)])[..]);
+            apply_keystream(&mut data, &NIST_KEY, &NIST_IV);
+            assert_eq!(data, plain, "length {len}");
+        }
+    }
+
+    /// XORs `plain` with the start of the NIST test keystream.
+    fn encrypt_prefix(plain: &[u8]) -> Vec<u8> {
+        plain.iter().enumerate().map(|(i, b)| b ^ NIST_PLAINTEXT[i] ^ NIST_CIPHERTEXT[i]).collect()
+    }
+
+    #[test]
+    fn test_aes_reencrypt_drop() {
+        let mut data = NIST_PLAINTEXT;
+        ReEncrypt::drop(&mut data, &(NIST_KEY, NIST_IV));
+        assert_eq!(data, NIST_CIPHERTEXT);
+    }
+
+    #[test]
+    fn test_aes_concurrent_deref_same_value() {
+        let shared = Arc::new(CONST_ENCRYPTED);
+        let mut handles: Vec<thread::JoinHandle<()>> = vec![];
+
+        for _ in 0..10 {
+            let shared_clone = Arc::clone(&shared);
+            let handle = thread::spawn(move || {
+                let decrypted: &[u8; 64] = &*shared_clone;
+                assert_eq!(decrypted, &NIST_PLAINTEXT);
+            });
+            handles.push(handle);
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+}
@@ -8,6 +8,14 @@
 //!
 //! - [`Aligned8`]: Forces 8-byte alignment
 //! - [`Aligned16`]: Forces 16-byte alignment
+//! - [`Aligned32`]: Forces 32-byte alignment (e.g. for AVX2 loads)
+//! - [`Aligned64`]: Forces 64-byte alignment (a cache line on most `x86_64` and ARM cores)
+//! - [`Aligned128`]: Forces 128-byte alignment (a cache line pair, or a line on Apple
+//!   silicon), to keep a secret from sharing a line with unrelated data
+//!
+//! Every wrapper dereferences to the inner value, so methods can be called on it
+//! without going through `.0`. Wrap a value with `From`/`Into` and unwrap it with
+//! `Aligned16::into_inner(wrapper)`.
 //!
 //! # Example
 //!
@@ -26,9 +34,15 @@
 //! fn main() {
 //!     // Access the inner encrypted data
 //!     let _inner: &Encrypted<Xor<0xAA, Zeroize>, ByteArray, 16> = &SECRET.0;
+//!
+//!     // Or call its methods directly
+//!     let secret = SECRET;
+//!     assert_eq!(secret.len(), 16);
 //! }
 //! ```
 
+use core::ops::{Deref, DerefMut};
+
 #[repr(align(8))]
 #[derive(Debug)]
 pub struct Aligned8<E>(pub E);
@@ -36,3 +50,93 @@ pub struct Aligned8<E>(pub E);
 #[repr(align(16))]
 #[derive(Debug)]
 pub struct Aligned16<E>(pub E);
+
+#[repr(align(32))]
+#[derive(Debug)]
+pub struct Aligned32<E>(pub E);
+
+#[repr(align(64))]
+#[derive(Debug)]
+pub struct Aligned64<E>(pub E);
+
+#[repr(align(128))]
+#[derive(Debug)]
+pub struct Aligned128<E>(pub E);
+
+macro_rules! impl_wrapper {
+    ($($wrapper:ident),*) => {$(
+        impl<E> $wrapper<E> {
+            /// Returns the inner value.
+            ///
+            /// An associated function rather than a method, so that it does not
+            /// shadow an `into_inner` method of the inner value reached through
+            /// `Deref`.
+            pub fn into_inner(this: Self) -> E {
+                this.0
+            }
+        }
+
+        impl<E> From<E> for $wrapper<E> {
+            fn from(inner: E) -> Self {
+                Self(inner)
+            }
+        }
+
+        impl<E> Deref for $wrapper<E> {
+            type Target = E;
+
+            fn deref(&self) -> &E {
+                &self.0
+            }
+        }
+
+        impl<E> DerefMut for $wrapper<E> {
+            fn deref_mut(&mut self) -> &mut E {
+                &mut self.0
+            }
+        }
+    )*};
+}
+
+impl_wrapper!(Aligned8, Aligned16, Aligned32, Aligned64, Aligned128);
+
+#[cfg(test)]
+#[allow(clippy::explicit_auto_deref)]
+mod tests {
+    use super::*;
+    use crate::{ByteArray, Encrypted, drop_strategy::Zeroize, xor::Xor};
+    use core::mem::align_of;
+
+    type Secret = Encrypted<Xor<0xAA, Zeroize>, ByteArray, 4>;
+
+    #[test]
+    fn test_alignments() {
+        assert_eq!(align_of::<Aligned8<Secret>>(), 8);
+        assert_eq!(align_of::<Aligned16<Secret>>(), 16);
+        assert_eq!(align_of::<Aligned32<Secret>>(), 32);
+        assert_eq!(align_of::<Aligned64<Secret>>(), 64);
+        assert_eq!(align_of::<Aligned128<Secret>>(), 128);
+    }
+
+    #[test]
+    fn test_deref_to_inner() {
+        let secret = Aligned32(Secret::new([1, 2, 3, 4]));
+        assert_eq!(secret.len(), 4);
+        assert_eq!(&**secret, &[1, 2, 3, 4]);
+
+        let mut secret = Aligned128(Secret::new([1, 2, 3, 4]));
+        secret.zeroize_now();
+        assert!(secret.is_wiped());
+    }
+
+    #[test]
+    fn test_from_and_into_inner() {
+        let secret: Aligned8<Secret> = Secret::new([1, 2, 3, 4]).into();
+        assert_eq!(&**secret, &[1, 2, 3, 4]);
+
+        let inner: Secret = Aligned8::into_inner(secret);
+        assert!(inner.is_decrypted());
+        assert_eq!(&*inner, &[1, 2, 3, 4]);
+        assert_eq!(&**Aligned16::from(inner), &[1, 2, 3, 4]);
+    }
+}
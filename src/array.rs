@@ -0,0 +1,231 @@
+//! Fixed-size groups of secrets sharing an algorithm, mode and length.
+//!
+//! A service holding several API keys of the same shape would otherwise declare one
+//! `const` per key. [`EncryptedArray`] declares them together: it is an array of
+//! [`Encrypted`] values built in one `const fn` call, indexed with `[i]` and iterated
+//! like any array. Each element keeps its own state, so dereferencing one secret
+//! decrypts only that secret.
+//!
+//! # Example
+//!
+//! ```rust
+//! use const_secret::{
+//!     ByteArray, Encrypted, array::EncryptedArray, drop_strategy::Zeroize, xor::Xor,
+//! };
+//!
+//! static API_KEYS: EncryptedArray<Xor<0xAA, Zeroize>, ByteArray, 4, 3> =
+//!     EncryptedArray::<Xor<0xAA, Zeroize>, ByteArray, 4, 3>::new([*b"key1", *b"key2", *b"key3"]);
+//!
+//! assert_eq!(&*API_KEYS[1], b"key2");
+//! assert!(!API_KEYS[0].is_decrypted());
+//! assert_eq!(API_KEYS.iter().filter(|key| key.is_decrypted()).count(), 1);
+//! ```
+
+use core::{
+    fmt,
+    mem::MaybeUninit,
+    ops::{Deref, Index},
+    ptr,
+};
+
+use crate::{Algorithm, Encrypted, Mode, drop_strategy::DropStrategy, rc4::Rc4, xor::Xor};
+
+/// `COUNT` secrets of `N` bytes each, sealed with the same algorithm.
+///
+/// Dereferences to `[Encrypted<A, M, N>; COUNT]`, so slice methods and iteration work
+/// as on a plain array.
+pub struct EncryptedArray<A: Algorithm, M, const N: usize, const COUNT: usize>(
+    [Encrypted<A, M, N>; COUNT],
+);
+
+/// Moves the elements out of an array whose elements have all been written.
+///
+/// # Safety
+///
+/// Every element of `items` must be initialized, and `items` must not be read again.
+const unsafe fn assume_init<T, const COUNT: usize>(items: &[MaybeUninit<T>; COUNT]) -> [T; COUNT] {
+    // SAFETY: `MaybeUninit<T>` has the layout of `T`, the caller guarantees that every
+    // element is initialized, and `MaybeUninit` never drops its contents, so the
+    // values are moved out exactly once.
+    unsafe { ptr::from_ref(items).cast::<[T; COUNT]>().read() }
+}
+
+impl<A: Algorithm, M, const N: usize, const COUNT: usize> EncryptedArray<A, M, N, COUNT> {
+    /// Returns the secrets.
+    pub fn into_inner(self) -> [Encrypted<A, M, N>; COUNT] {
+        self.0
+    }
+}
+
+impl<const KEY: u8, D: DropStrategy<Extra = ()>, M: Mode, const N: usize, const COUNT: usize>
+    EncryptedArray<Xor<KEY, D>, M, N, COUNT>
+{
+    /// Encrypts each plaintext by XOR-ing every byte with `KEY`.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`Encrypted::new`] for XOR, which fails the
+    /// build in a `const` initializer.
+    pub const fn new(plaintexts: [[u8; N]; COUNT]) -> Self {
+        let mut secrets = [const { MaybeUninit::uninit() }; COUNT];
+        let mut i = 0;
+        while i < COUNT {
+            secrets[i].write(Encrypted::<Xor<KEY, D>, M, N>::new(plaintexts[i]));
+            i += 1;
+        }
+        // SAFETY: the loop wrote every element.
+        Self(unsafe { assume_init(&secrets) })
+    }
+}
+
+impl<
+    const KEY_LEN: usize,
+    D: DropStrategy<Extra = [u8; KEY_LEN]>,
+    M: Mode,
+    const N: usize,
+    const COUNT: usize,
+> EncryptedArray<Rc4<KEY_LEN, D>, M, N, COUNT>
+{
+    /// Encrypts each plaintext with RC4 under the shared `key`.
+    ///
+    /// Each secret stores its own copy of the key.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`Encrypted::new`] for RC4, which fails the
+    /// build in a `const` initializer.
+    pub const fn new(plaintexts: [[u8; N]; COUNT], key: [u8; KEY_LEN]) -> Self {
+        let mut secrets = [const { MaybeUninit::uninit() }; COUNT];
+        let mut i = 0;
+        while i < COUNT {
+            secrets[i].write(Encrypted::<Rc4<KEY_LEN, D>, M, N>::new(plaintexts[i], key));
+            i += 1;
+        }
+        // SAFETY: the loop wrote every element.
+        Self(unsafe { assume_init(&secrets) })
+    }
+}
+
+impl<A: Algorithm, M, const N: usize, const COUNT: usize> From<[Encrypted<A, M, N>; COUNT]>
+    for EncryptedArray<A, M, N, COUNT>
+{
+    /// Groups secrets built with any algorithm.
+    fn from(secrets: [Encrypted<A, M, N>; COUNT]) -> Self {
+        Self(secrets)
+    }
+}
+
+impl<A: Algorithm, M, const N: usize, const COUNT: usize> Deref for EncryptedArray<A, M, N, COUNT> {
+    type Target = [Encrypted<A, M, N>; COUNT];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<A: Algorithm, M, const N: usize, const COUNT: usize> Index<usize>
+    for EncryptedArray<A, M, N, COUNT>
+{
+    type Output = Encrypted<A, M, N>;
+
+    /// Returns the secret at `index`, without decrypting it.
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+impl<'a, A: Algorithm, M, const N: usize, const COUNT: usize> IntoIterator
+    for &'a EncryptedArray<A, M, N, COUNT>
+{
+    type Item = &'a Encrypted<A, M, N>;
+    type IntoIter = core::slice::Iter<'a, Encrypted<A, M, N>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<A: Algorithm, M, const N: usize, const COUNT: usize> fmt::Debug
+    for EncryptedArray<A, M, N, COUNT>
+{
+    /// Formats each secret like [`Encrypted`] does, never showing a buffer.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("EncryptedArray").field(&self.0).finish()
+    }
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::borrow_interior_mutable_const,
+    clippy::declare_interior_mutable_const,
+    clippy::explicit_auto_deref
+)]
+mod tests {
+    use super::*;
+    use crate::{ByteArray, StringLiteral, drop_strategy::Zeroize, rc4::ReEncrypt};
+
+    use std::{sync::Arc, thread, vec::Vec};
+
+    type XorKeys = EncryptedArray<Xor<0xAA, Zeroize>, StringLiteral, 5, 3>;
+
+    const KEYS: XorKeys = XorKeys::new([*b"alpha", *b"bravo", *b"delta"]);
+
+    #[test]
+    fn test_elements_decrypt_independently() {
+        let keys = KEYS;
+        for key in &keys {
+            assert_ne!(key.encrypted_bytes(), *b"alpha");
+        }
+
+        assert_eq!(&*keys[1], "bravo");
+        assert!(keys[1].is_decrypted());
+        assert!(!keys[0].is_decrypted());
+        assert!(!keys[2].is_decrypted());
+
+        let plaintexts: Vec<&str> = keys.iter().map(|key| &**key).collect();
+        assert_eq!(plaintexts, ["alpha", "bravo", "delta"]);
+    }
+
+    #[test]
+    fn test_rc4_shares_key() {
+        let mut keys = EncryptedArray::<Rc4<5, ReEncrypt<5>>, ByteArray, 2, 2>::new(
+            [[1, 2], [3, 4]],
+            *b"mykey",
+        );
+        let sealed = keys[0].encrypted_bytes();
+        assert_eq!(
+            sealed,
+            Encrypted::<Rc4<5, ReEncrypt<5>>, ByteArray, 2>::new([1, 2], *b"mykey")
+                .encrypted_bytes()
+        );
+        assert_eq!(&*keys[0], &[1, 2]);
+        assert_eq!(&*keys[1], &[3, 4]);
+
+        let mut secrets = keys.into_inner();
+        secrets[0].reset();
+        assert_eq!(secrets[0].encrypted_bytes(), sealed);
+        keys = EncryptedArray::from(secrets);
+        assert!(!keys[0].is_decrypted());
+        assert!(std::format!("{keys:?}").starts_with("EncryptedArray(["));
+    }
+
+    #[test]
+    fn test_empty_array() {
+        let keys = EncryptedArray::<Xor<0xAA, Zeroize>, ByteArray, 4, 0>::new([]);
+        assert!(keys.is_empty());
+    }
+
+    #[test]
+    fn test_array_shared_across_threads() {
+        let keys = Arc::new(KEYS);
+        let handles: Vec<_> = (0..3)
+            .map(|i| {
+                let keys = Arc::clone(&keys);
+                thread::spawn(move || assert_eq!(&*keys[i], ["alpha", "bravo", "delta"][i]))
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+}
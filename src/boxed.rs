@@ -0,0 +1,155 @@
+//! Heap-allocated secrets for values only known at runtime.
+//!
+//! [`EncryptedBox`] owns an [`Encrypted`] on the heap. It is meant for secrets read at
+//! startup, such as a database password from an environment variable, that should get
+//! the same protection as a compile-time secret: the buffer is encrypted at rest,
+//! decrypts on first dereference, and applies its drop strategy when the box is
+//! dropped. Being boxed, it is cheap to move and large secrets do not sit on the stack.
+//!
+//! Like [`Encrypted`], it is `Send` and `Sync` when the algorithm and its extra data
+//! are.
+//!
+//! # Example
+//!
+//! ```rust
+//! use const_secret::{ByteArray, boxed::EncryptedBox, drop_strategy::Zeroize, xor::Xor};
+//!
+//! let password: [u8; 6] = *b"hunter";
+//! let boxed = EncryptedBox::<Xor<0xAA, Zeroize>, ByteArray, 6>::new(password);
+//! assert_eq!(&*boxed, b"hunter");
+//! ```
+
+use alloc::boxed::Box;
+use core::{fmt, ops::Deref};
+
+use crate::{
+    Algorithm, Encrypted, Mode, SecretField, StringLiteral, drop_strategy::DropStrategy, rc4::Rc4,
+    xor::Xor,
+};
+
+/// An [`Encrypted`] allocated on the heap.
+///
+/// Dereferences to the plaintext exactly as the inner secret does. Use
+/// [`AsRef`]/[`AsMut`] to reach the [`Encrypted`] itself, for example to wipe it early.
+pub struct EncryptedBox<A: Algorithm, M, const N: usize>(Box<Encrypted<A, M, N>>);
+
+impl<A: Algorithm, M, const N: usize> EncryptedBox<A, M, N> {
+    /// Returns the boxed secret.
+    pub fn into_inner(self) -> Box<Encrypted<A, M, N>> {
+        self.0
+    }
+}
+
+impl<const KEY: u8, D: DropStrategy<Extra = ()>, M: Mode, const N: usize>
+    EncryptedBox<Xor<KEY, D>, M, N>
+{
+    /// Encrypts `buffer` with XOR into a new heap allocation.
+    pub fn new(buffer: [u8; N]) -> Self {
+        Self(Box::new(Encrypted::<Xor<KEY, D>, M, N>::new(buffer)))
+    }
+}
+
+impl<const KEY_LEN: usize, D: DropStrategy<Extra = [u8; KEY_LEN]>, M: Mode, const N: usize>
+    EncryptedBox<Rc4<KEY_LEN, D>, M, N>
+{
+    /// Encrypts `buffer` with RC4 under `key` into a new heap allocation.
+    pub fn new(buffer: [u8; N], key: [u8; KEY_LEN]) -> Self {
+        Self(Box::new(Encrypted::<Rc4<KEY_LEN, D>, M, N>::new(buffer, key)))
+    }
+}
+
+impl<A: Algorithm, M, const N: usize> From<Encrypted<A, M, N>> for EncryptedBox<A, M, N> {
+    /// Moves a secret built with any algorithm onto the heap.
+    fn from(secret: Encrypted<A, M, N>) -> Self {
+        Self(Box::new(secret))
+    }
+}
+
+impl<A: Algorithm, M: Mode, const N: usize> Deref for EncryptedBox<A, M, N> {
+    type Target = M::Target<N>;
+
+    /// Decrypts on first access, as dereferencing [`Encrypted`] does.
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<A: Algorithm, const N: usize> SecretField for EncryptedBox<A, StringLiteral, N> {
+    fn reveal(&self) -> &str {
+        self
+    }
+}
+
+impl<A: Algorithm, M, const N: usize> AsRef<Encrypted<A, M, N>> for EncryptedBox<A, M, N> {
+    fn as_ref(&self) -> &Encrypted<A, M, N> {
+        &self.0
+    }
+}
+
+impl<A: Algorithm, M, const N: usize> AsMut<Encrypted<A, M, N>> for EncryptedBox<A, M, N> {
+    fn as_mut(&mut self) -> &mut Encrypted<A, M, N> {
+        &mut self.0
+    }
+}
+
+impl<A: Algorithm, M, const N: usize> fmt::Debug for EncryptedBox<A, M, N> {
+    /// Formats like the inner [`Encrypted`], never showing the buffer.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("EncryptedBox").field(&self.0).finish()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::explicit_auto_deref)]
+mod tests {
+    use super::*;
+    use crate::{ByteArray, drop_strategy::Zeroize, rc4::ReEncrypt};
+
+    use std::{sync::Arc, thread, vec::Vec};
+
+    #[test]
+    fn test_xor_box_round_trip() {
+        let boxed = EncryptedBox::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        assert!(!boxed.as_ref().is_decrypted());
+        assert_eq!(boxed.reveal(), "hello");
+        assert_eq!(&*boxed, "hello");
+        assert!(boxed.as_ref().is_decrypted());
+    }
+
+    #[test]
+    fn test_rc4_box_round_trip() {
+        let mut boxed =
+            EncryptedBox::<Rc4<5, ReEncrypt<5>>, ByteArray, 3>::new([1, 2, 3], *b"mykey");
+        let sealed = boxed.as_ref().encrypted_bytes();
+        assert_eq!(&*boxed, &[1, 2, 3]);
+
+        boxed.as_mut().reset();
+        assert_eq!(boxed.as_ref().encrypted_bytes(), sealed);
+        assert!(!boxed.as_ref().is_decrypted());
+    }
+
+    #[test]
+    fn test_from_encrypted_and_wipe() {
+        let secret = Encrypted::<Xor<0x5A, Zeroize>, ByteArray, 2>::new([7, 8]);
+        let mut boxed = EncryptedBox::from(secret);
+        assert_eq!(&*boxed, &[7, 8]);
+
+        boxed.as_mut().zeroize_now();
+        assert!(boxed.as_ref().is_wiped());
+        assert!(std::format!("{boxed:?}").starts_with("EncryptedBox(Encrypted {"));
+    }
+
+    #[test]
+    fn test_box_shared_across_threads() {
+        let boxed = Arc::new(EncryptedBox::<Xor<0xAA, Zeroize>, StringLiteral, 6>::new(*b"shared"));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let boxed = Arc::clone(&boxed);
+                thread::spawn(move || assert_eq!(&**boxed, "shared"))
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+}
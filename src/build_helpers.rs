@@ -0,0 +1,117 @@
+//! Random keys generated at build time, for use from a `build.rs` script.
+//!
+//! A key written in the source is visible to anyone who can read the source. These
+//! helpers let a build script draw a fresh key from the OS random number generator and
+//! hand it to the compiler through an environment variable, so it only ever exists in
+//! the build. [`key_from_env!`](crate::key_from_env!) then decodes it into a constant.
+//!
+//! Add `const-secret` as a build dependency with the `build-helpers` feature:
+//!
+//! ```toml
+//! [build-dependencies]
+//! const-secret = { version = "0.1", features = ["build-helpers"] }
+//! ```
+//!
+//! ```rust,ignore
+//! // build.rs
+//! use const_secret::build_helpers::{emit_key, generate_rc4_key, generate_xor_key};
+//!
+//! fn main() {
+//!     emit_key("XOR_KEY", &[generate_xor_key()]);
+//!     emit_key("RC4_KEY", &generate_rc4_key::<16>());
+//! }
+//! ```
+//!
+//! ```rust
+//! // src/main.rs
+//! use const_secret::{Encrypted, StringLiteral, drop_strategy::Zeroize, key_from_env, rc4::Rc4};
+//!
+//! const RC4_KEY: [u8; 16] = key_from_env!("RC4_KEY");
+//! const XOR_KEY: [u8; 1] = key_from_env!("XOR_KEY");
+//!
+//! const SECRET: Encrypted<Rc4<16, Zeroize<[u8; 16]>>, StringLiteral, 5> =
+//!     Encrypted::<Rc4<16, Zeroize<[u8; 16]>>, StringLiteral, 5>::new(*b"hello", RC4_KEY);
+//! # assert_eq!(&*SECRET, "hello");
+//! # let _ = XOR_KEY[0];
+//! ```
+//!
+//! Cargo only re-runs a build script when its inputs change, so the key changes when
+//! `build.rs` does, or on a clean build. The key still ends up in the binary next to
+//! the ciphertext, as with any key in this crate; what changes is that it is not in
+//! the source and differs between builds.
+
+use std::{format, println, string::String};
+
+/// Returns a random single-byte key for [`Xor`](crate::xor::Xor).
+///
+/// Never returns 0, which `Xor` rejects at compile time because it leaves the
+/// plaintext unchanged.
+///
+/// # Panics
+///
+/// Panics if the OS random number generator fails, which fails the build.
+pub fn generate_xor_key() -> u8 {
+    loop {
+        let [key] = generate_rc4_key::<1>();
+        if key != 0 {
+            return key;
+        }
+    }
+}
+
+/// Returns a random `N`-byte key, e.g. for [`Rc4`](crate::rc4::Rc4) or
+/// [`XorN`](crate::xor::XorN).
+///
+/// # Panics
+///
+/// Panics if the OS random number generator fails, which fails the build.
+pub fn generate_rc4_key<const N: usize>() -> [u8; N] {
+    let mut key = [0u8; N];
+    if let Err(err) = getrandom::fill(&mut key) {
+        panic!("failed to generate a random key: {err}");
+    }
+    key
+}
+
+/// Passes `key` to the crate being built as the environment variable `name`, hex
+/// encoded, for [`key_from_env!`](crate::key_from_env!).
+///
+/// Must be called from a build script: it prints a `cargo:rustc-env` directive.
+pub fn emit_key(name: &str, key: &[u8]) {
+    println!("{}", rustc_env_directive(name, key));
+}
+
+/// Formats the `cargo:rustc-env` directive that sets `name` to `key` in hex.
+fn rustc_env_directive(name: &str, key: &[u8]) -> String {
+    let hex: String = key.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("cargo:rustc-env={name}={hex}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode_hex;
+
+    #[test]
+    fn test_xor_key_is_never_zero() {
+        // A zero byte comes up about once in 256 draws.
+        for _ in 0..4096 {
+            assert_ne!(generate_xor_key(), 0);
+        }
+    }
+
+    #[test]
+    fn test_generated_keys_differ() {
+        assert_ne!(generate_rc4_key::<32>(), generate_rc4_key::<32>());
+    }
+
+    #[test]
+    fn test_directive_round_trips_through_decode_hex() {
+        let key = [0x00, 0x7f, 0xab, 0xff];
+        let directive = rustc_env_directive("MY_KEY", &key);
+        assert_eq!(directive, "cargo:rustc-env=MY_KEY=007fabff");
+
+        let hex = directive.strip_prefix("cargo:rustc-env=MY_KEY=").unwrap();
+        assert_eq!(decode_hex::<4>(hex), key);
+    }
+}
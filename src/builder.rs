@@ -0,0 +1,410 @@
+//! Runtime sealing of secrets assembled from several pieces.
+//!
+//! The algorithm constructors such as `Encrypted::<Xor<..>, M, N>::new` are `const fn`s
+//! that need the whole plaintext up front. Some secrets are only known
+//! at runtime and arrive in parts, such as a fixed prefix followed by a fetched body.
+//! [`SealingBuilder`] collects those parts in a fixed `[u8; N]` scratch buffer and seals
+//! them into an [`Encrypted`] without going through an intermediate `String` or `Vec`.
+//!
+//! The scratch buffer is zeroized when the builder is dropped, whether or not
+//! [`finish`](SealingBuilder::finish) was called.
+//!
+//! A secret that arrives in one piece, such as a password typed into a setup wizard,
+//! can be sealed in one call with `Encrypted::seal_from`, which also zeroizes the
+//! caller's plaintext buffer.
+//!
+//! # Example
+//!
+//! ```rust
+//! use const_secret::{
+//!     Encrypted, StringLiteral,
+//!     drop_strategy::Zeroize,
+//!     rc4::Rc4,
+//! };
+//!
+//! let mut builder = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, StringLiteral, 11>::with_capacity_sealed();
+//! builder.push_segment(b"sk-").unwrap();
+//! builder.push_segment(b"live-").unwrap();
+//! builder.push_segment(b"123").unwrap();
+//!
+//! let secret = builder.finish(*b"mykey").unwrap();
+//! assert_eq!(&*secret, "sk-live-123");
+//! ```
+
+use core::{cell::UnsafeCell, fmt, marker::PhantomData, sync::atomic::AtomicU8};
+
+use crate::{Algorithm, ByteArray, Encrypted, STATE_UNENCRYPTED, StringLiteral, wipe};
+
+/// Errors returned while assembling or sealing a secret with [`SealingBuilder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SealError {
+    /// A segment did not fit into the remaining capacity of the builder.
+    Overflow {
+        /// Length of the rejected segment.
+        requested: usize,
+        /// Number of bytes that were still free.
+        remaining: usize,
+    },
+    /// [`finish`](SealingBuilder::finish) was called before all `N` bytes were written.
+    Incomplete {
+        /// Number of bytes written so far.
+        written: usize,
+        /// The total capacity `N`.
+        capacity: usize,
+    },
+    /// The assembled plaintext of a [`StringLiteral`] secret is not valid UTF-8.
+    InvalidUtf8(core::str::Utf8Error),
+}
+
+impl fmt::Display for SealError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overflow {
+                requested,
+                remaining,
+            } => write!(
+                f,
+                "segment of {requested} bytes exceeds the remaining capacity of {remaining} bytes"
+            ),
+            Self::Incomplete {
+                written,
+                capacity,
+            } => {
+                write!(f, "only {written} of {capacity} bytes were written before sealing")
+            }
+            Self::InvalidUtf8(err) => write!(f, "sealed string is not valid UTF-8: {err}"),
+        }
+    }
+}
+
+impl core::error::Error for SealError {}
+
+/// Assembles a plaintext from runtime segments and seals it into an [`Encrypted`].
+///
+/// Created with [`Encrypted::with_capacity_sealed`]. Segments are appended with
+/// [`push_segment`](Self::push_segment) and the secret is sealed with
+/// [`finish`](Self::finish), which requires exactly `N` bytes to have been written.
+pub struct SealingBuilder<A: Algorithm, M, const N: usize> {
+    /// Plaintext scratch buffer, zeroized on drop.
+    scratch: [u8; N],
+    /// Number of bytes written to `scratch` so far.
+    offset: usize,
+    /// Phantom marker to carry the algorithm and mode type information.
+    _phantom: PhantomData<(A, M)>,
+}
+
+impl<A: Algorithm, M, const N: usize> Encrypted<A, M, N> {
+    /// Starts assembling a secret of exactly `N` bytes at runtime.
+    ///
+    /// See [`SealingBuilder`] for details.
+    pub const fn with_capacity_sealed() -> SealingBuilder<A, M, N> {
+        SealingBuilder {
+            scratch: [0u8; N],
+            offset: 0,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<A: Algorithm, M, const N: usize> SealingBuilder<A, M, N> {
+    /// Appends `bytes` to the plaintext being assembled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SealError::Overflow`] if `bytes` does not fit into the remaining
+    /// capacity. Nothing is written in that case.
+    pub fn push_segment(&mut self, bytes: &[u8]) -> Result<(), SealError> {
+        let remaining = self.remaining();
+        if bytes.len() > remaining {
+            return Err(SealError::Overflow {
+                requested: bytes.len(),
+                remaining,
+            });
+        }
+
+        self.scratch[self.offset..self.offset + bytes.len()].copy_from_slice(bytes);
+        self.offset += bytes.len();
+        Ok(())
+    }
+
+    /// Returns the number of bytes written so far.
+    pub const fn len(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns `true` if no bytes have been written yet.
+    pub const fn is_empty(&self) -> bool {
+        self.offset == 0
+    }
+
+    /// Returns the number of bytes that can still be written.
+    pub const fn remaining(&self) -> usize {
+        N - self.offset
+    }
+
+    /// Returns [`SealError::Incomplete`] unless all `N` bytes have been written.
+    const fn check_complete(&self) -> Result<(), SealError> {
+        if self.offset != N {
+            return Err(SealError::Incomplete {
+                written: self.offset,
+                capacity: N,
+            });
+        }
+        Ok(())
+    }
+
+    /// Encrypts the assembled plaintext in the scratch buffer and moves it into an
+    /// [`Encrypted`]. The scratch buffer is zeroized when `self` is dropped.
+    fn seal(mut self, extra: A::Extra) -> Result<Encrypted<A, M, N>, SealError> {
+        self.check_complete()?;
+        A::check_extra(&extra);
+
+        A::encrypt(&mut self.scratch, &extra);
+
+        Ok(Encrypted {
+            buffer: UnsafeCell::new(self.scratch),
+            decryption_state: AtomicU8::new(STATE_UNENCRYPTED),
+            extra,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<A: Algorithm, const N: usize> SealingBuilder<A, ByteArray, N> {
+    /// Seals the assembled bytes using the algorithm's `extra` data (e.g. the RC4 key,
+    /// or `()` for XOR).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SealError::Incomplete`] if fewer than `N` bytes were written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the algorithm rejects `extra` (see [`Algorithm::check_extra`]), e.g.
+    /// an [`XorRuntime`](crate::xor::XorRuntime) key of 0.
+    pub fn finish(self, extra: A::Extra) -> Result<Encrypted<A, ByteArray, N>, SealError> {
+        self.seal(extra)
+    }
+}
+
+impl<A: Algorithm, const N: usize> SealingBuilder<A, StringLiteral, N> {
+    /// Seals the assembled string using the algorithm's `extra` data (e.g. the RC4 key,
+    /// or `()` for XOR).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SealError::Incomplete`] if fewer than `N` bytes were written, or
+    /// [`SealError::InvalidUtf8`] if the assembled bytes are not valid UTF-8.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the algorithm rejects `extra` (see [`Algorithm::check_extra`]), e.g.
+    /// an [`XorRuntime`](crate::xor::XorRuntime) key of 0.
+    pub fn finish(self, extra: A::Extra) -> Result<Encrypted<A, StringLiteral, N>, SealError> {
+        // A short input may end mid-character; report it as short, not as bad UTF-8.
+        self.check_complete()?;
+        if let Err(err) = core::str::from_utf8(&self.scratch) {
+            return Err(SealError::InvalidUtf8(err));
+        }
+        self.seal(extra)
+    }
+}
+
+impl<A: Algorithm, const N: usize> Encrypted<A, ByteArray, N> {
+    /// Seals `plaintext` at runtime and zeroizes it, whether or not sealing succeeds.
+    ///
+    /// ```rust
+    /// use const_secret::{ByteArray, Encrypted, drop_strategy::Zeroize, rc4::Rc4};
+    ///
+    /// let mut entered = *b"hunter2";
+    /// let secret =
+    ///     Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 7>::seal_from(&mut entered, *b"mykey")
+    ///         .unwrap();
+    /// assert_eq!(entered, [0; 7]);
+    /// assert_eq!(&*secret, b"hunter2");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SealError::Overflow`] or [`SealError::Incomplete`] if `plaintext` is
+    /// not exactly `N` bytes long.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the algorithm rejects `extra` (see [`Algorithm::check_extra`]), e.g.
+    /// an [`XorRuntime`](crate::xor::XorRuntime) key of 0.
+    pub fn seal_from(plaintext: &mut [u8], extra: A::Extra) -> Result<Self, SealError> {
+        let mut builder = Self::with_capacity_sealed();
+        let pushed = builder.push_segment(plaintext);
+        wipe(plaintext);
+        pushed?;
+        builder.finish(extra)
+    }
+}
+
+impl<A: Algorithm, const N: usize> Encrypted<A, StringLiteral, N> {
+    /// Seals `plaintext` at runtime and zeroizes it, whether or not sealing succeeds.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SealError::Overflow`] or [`SealError::Incomplete`] if `plaintext` is
+    /// not exactly `N` bytes long, or [`SealError::InvalidUtf8`] if it is not valid
+    /// UTF-8.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the algorithm rejects `extra` (see [`Algorithm::check_extra`]), e.g.
+    /// an [`XorRuntime`](crate::xor::XorRuntime) key of 0.
+    pub fn seal_from(plaintext: &mut [u8], extra: A::Extra) -> Result<Self, SealError> {
+        let mut builder = Self::with_capacity_sealed();
+        let pushed = builder.push_segment(plaintext);
+        wipe(plaintext);
+        pushed?;
+        builder.finish(extra)
+    }
+}
+
+impl<A: Algorithm, M, const N: usize> Drop for SealingBuilder<A, M, N> {
+    /// Zeroizes the scratch buffer so no assembled plaintext is left behind.
+    fn drop(&mut self) {
+        wipe(&mut self.scratch);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::explicit_auto_deref)]
+mod tests {
+    use super::*;
+    use crate::{
+        drop_strategy::Zeroize,
+        rc4::Rc4,
+        xor::{ReEncrypt, Xor, XorRuntime},
+    };
+
+    #[test]
+    #[should_panic(expected = "XorRuntime key of 0x00")]
+    fn test_finish_rejects_runtime_key_zero() {
+        let mut builder = Encrypted::<XorRuntime, ByteArray, 5>::with_capacity_sealed();
+        builder.push_segment(b"hello").unwrap();
+        let _ = builder.finish(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "XorRuntime key of 0x00")]
+    fn test_seal_from_rejects_runtime_key_zero() {
+        let mut input = *b"hello";
+        let _ = Encrypted::<XorRuntime, StringLiteral, 5>::seal_from(&mut input, 0);
+    }
+
+    #[test]
+    fn test_three_segments_xor_string() {
+        let mut builder =
+            Encrypted::<Xor<0xAA, ReEncrypt<0xAA>>, StringLiteral, 13>::with_capacity_sealed();
+        builder.push_segment(b"token").unwrap();
+        builder.push_segment(b"-").unwrap();
+        builder.push_segment(b"1234567").unwrap();
+        assert_eq!(builder.remaining(), 0);
+
+        let secret = builder.finish(()).unwrap();
+        let raw = unsafe { &*secret.buffer.get() };
+        assert_ne!(raw, b"token-1234567", "buffer must NOT be plaintext before deref");
+
+        let plain: &str = &*secret;
+        assert_eq!(plain, "token-1234567");
+    }
+
+    #[test]
+    fn test_three_segments_rc4_matches_const_new() {
+        const KEY: [u8; 5] = *b"mykey";
+        let expected = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 6>::new(*b"abcdef", KEY);
+
+        let mut builder =
+            Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 6>::with_capacity_sealed();
+        builder.push_segment(b"ab").unwrap();
+        builder.push_segment(b"cd").unwrap();
+        builder.push_segment(b"ef").unwrap();
+        let secret = builder.finish(KEY).unwrap();
+
+        let sealed = unsafe { &*secret.buffer.get() };
+        let constant = unsafe { &*expected.buffer.get() };
+        assert_eq!(sealed, constant, "runtime sealing must match compile-time encryption");
+        assert_eq!(&*secret, b"abcdef");
+    }
+
+    #[test]
+    fn test_overflow_and_incomplete() {
+        let mut builder = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 4>::with_capacity_sealed();
+        builder.push_segment(b"abc").unwrap();
+        assert_eq!(
+            builder.push_segment(b"de"),
+            Err(SealError::Overflow {
+                requested: 2,
+                remaining: 1
+            })
+        );
+        assert_eq!(builder.len(), 3);
+        assert_eq!(
+            builder.finish(()).err(),
+            Some(SealError::Incomplete {
+                written: 3,
+                capacity: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_seal_from_wipes_input() {
+        let mut input = *b"setup-wizard";
+        let secret =
+            Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 12>::seal_from(&mut input, ()).unwrap();
+        assert_eq!(input, [0; 12]);
+        assert!(!secret.is_decrypted());
+        assert_eq!(&*secret, "setup-wizard");
+
+        let mut short = *b"abc";
+        assert_eq!(
+            Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 4>::seal_from(&mut short, ()).err(),
+            Some(SealError::Incomplete {
+                written: 3,
+                capacity: 4
+            })
+        );
+        assert_eq!(short, [0; 3], "input is wiped on failure too");
+
+        let mut long = *b"abcde";
+        assert!(matches!(
+            Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 4>::seal_from(&mut long, ()),
+            Err(SealError::Overflow { .. })
+        ));
+        assert_eq!(long, [0; 5]);
+
+        let mut invalid = [0xC3, 0x28];
+        assert!(matches!(
+            Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 2>::seal_from(&mut invalid, ()),
+            Err(SealError::InvalidUtf8(_))
+        ));
+        assert_eq!(invalid, [0; 2]);
+    }
+
+    #[test]
+    fn test_invalid_utf8_string_is_rejected() {
+        let mut builder = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 2>::with_capacity_sealed();
+        builder.push_segment(&[0xC3, 0x28]).unwrap();
+        assert!(matches!(builder.finish(()), Err(SealError::InvalidUtf8(_))));
+    }
+
+    #[test]
+    fn test_short_string_is_incomplete_not_invalid() {
+        let mut builder = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 3>::with_capacity_sealed();
+        // The first byte of a two-byte character, cut off before its continuation.
+        builder.push_segment(&[0xC3]).unwrap();
+        assert_eq!(
+            builder.finish(()).err(),
+            Some(SealError::Incomplete {
+                written: 1,
+                capacity: 3
+            })
+        );
+    }
+}
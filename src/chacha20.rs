@@ -0,0 +1,346 @@
+//! `ChaCha20` stream cipher algorithm implementation.
+//!
+//! This module provides the `ChaCha20` stream cipher as specified in
+//! [RFC 8439](https://www.rfc-editor.org/rfc/rfc8439). Unlike [`rc4`](crate::rc4),
+//! `ChaCha20` has no known practical weaknesses, which makes the ciphertext embedded in
+//! the binary much harder to recover by analysis alone. The key still ships inside the
+//! binary, so this remains obfuscation rather than protection against a determined
+//! reverse engineer.
+//!
+//! # Algorithm
+//!
+//! `ChaCha20` expands a 256-bit key, a 96-bit nonce and a 32-bit block counter into
+//! 64-byte keystream blocks using 20 rounds of add-rotate-xor quarter rounds. The
+//! keystream is XOR'd with the plaintext, so the same operation encrypts and decrypts.
+//! The block counter starts at 1, matching the encryption examples of RFC 8439.
+//!
+//! # Types
+//!
+//! - [`ChaCha20<D>`](ChaCha20): The main algorithm type, generic over drop strategy
+//! - [`ReEncrypt`]: A drop strategy that re-encrypts data on drop
+//!
+//! # Example
+//!
+//! ```rust
+//! use const_secret::{
+//!     Encrypted, StringLiteral,
+//!     chacha20::{ChaCha20, Key, Nonce, ReEncrypt},
+//!     drop_strategy::Zeroize,
+//! };
+//!
+//! const KEY: [u8; 32] = *b"an example very very secret key.";
+//! const NONCE: [u8; 12] = *b"unique nonce";
+//!
+//! // Zeroize on drop (default)
+//! const SECRET: Encrypted<ChaCha20<Zeroize<(Key, Nonce)>>, StringLiteral, 5> =
+//!     Encrypted::<ChaCha20<Zeroize<(Key, Nonce)>>, StringLiteral, 5>::new(*b"hello", KEY, NONCE);
+//!
+//! // Re-encrypt on drop
+//! const SECRET2: Encrypted<ChaCha20<ReEncrypt>, StringLiteral, 6> =
+//!     Encrypted::<ChaCha20<ReEncrypt>, StringLiteral, 6>::new(*b"secret", KEY, NONCE);
+//!
+//! fn main() {
+//!     let s1: &str = &*SECRET;
+//!     assert_eq!(s1, "hello");
+//!
+//!     let s2: &str = &*SECRET2;
+//!     assert_eq!(s2, "secret");
+//! }
+//! ```
+
+use core::{cell::UnsafeCell, marker::PhantomData, sync::atomic::AtomicU8};
+
+use crate::{
+    Algorithm, Encrypted, Mode, STATE_UNENCRYPTED, check_plaintext,
+    drop_strategy::{DropStrategy, Residue, Zeroize},
+};
+
+/// A `ChaCha20` key.
+pub type Key = [u8; 32];
+/// A `ChaCha20` nonce.
+pub type Nonce = [u8; 12];
+
+/// The block counter used for the first 64 bytes of keystream.
+const INITIAL_COUNTER: u32 = 1;
+
+/// The constant words `"expand 32-byte k"`.
+const SIGMA: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+
+/// Reads a little-endian `u32` from `bytes` at `offset`.
+const fn load_le(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+}
+
+/// The `ChaCha` quarter round on words `a`, `b`, `c` and `d` of `state`.
+const fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+/// The `ChaCha20` block function: one 64-byte keystream block for `counter`.
+const fn block(key: &Key, nonce: &Nonce, counter: u32) -> [u8; 64] {
+    let mut initial = [0u32; 16];
+    let mut i = 0;
+    while i < 4 {
+        initial[i] = SIGMA[i];
+        i += 1;
+    }
+    let mut i = 0;
+    while i < 8 {
+        initial[4 + i] = load_le(key, i * 4);
+        i += 1;
+    }
+    initial[12] = counter;
+    let mut i = 0;
+    while i < 3 {
+        initial[13 + i] = load_le(nonce, i * 4);
+        i += 1;
+    }
+
+    // 20 rounds: 10 iterations of a column round followed by a diagonal round
+    let mut state = initial;
+    let mut round = 0;
+    while round < 10 {
+        quarter_round(&mut state, 0, 4, 8, 12);
+        quarter_round(&mut state, 1, 5, 9, 13);
+        quarter_round(&mut state, 2, 6, 10, 14);
+        quarter_round(&mut state, 3, 7, 11, 15);
+        quarter_round(&mut state, 0, 5, 10, 15);
+        quarter_round(&mut state, 1, 6, 11, 12);
+        quarter_round(&mut state, 2, 7, 8, 13);
+        quarter_round(&mut state, 3, 4, 9, 14);
+        round += 1;
+    }
+
+    // Add the initial state and serialize little-endian
+    let mut out = [0u8; 64];
+    let mut i = 0;
+    while i < 16 {
+        let word = state[i].wrapping_add(initial[i]).to_le_bytes();
+        out[i * 4] = word[0];
+        out[i * 4 + 1] = word[1];
+        out[i * 4 + 2] = word[2];
+        out[i * 4 + 3] = word[3];
+        i += 1;
+    }
+    out
+}
+
+/// Applies the `ChaCha20` keystream for `key` and `nonce` to `data` in place.
+///
+/// `ChaCha20` XORs the keystream into the buffer, so the same routine encrypts and
+/// decrypts. The final block is truncated when `data.len()` is not a multiple of 64.
+/// The 32-bit block counter limits a single secret to 256 GiB, far beyond any buffer
+/// that fits in a const.
+pub(crate) const fn apply_keystream(data: &mut [u8], key: &Key, nonce: &Nonce) {
+    let mut counter = INITIAL_COUNTER;
+    let mut offset = 0usize;
+    while offset < data.len() {
+        let keystream = block(key, nonce, counter);
+        let mut i = 0;
+        while i < 64 && offset + i < data.len() {
+            data[offset + i] ^= keystream[i];
+            i += 1;
+        }
+        offset += 64;
+        counter = counter.wrapping_add(1);
+    }
+}
+
+/// Re-encrypts the buffer using `ChaCha20` on drop.
+/// This ensures the plaintext never remains in memory after the value is dropped.
+pub struct ReEncrypt;
+
+impl DropStrategy for ReEncrypt {
+    type Extra = (Key, Nonce);
+    const RESIDUE: Residue = Residue::Ciphertext;
+
+    fn drop(data: &mut [u8], extra: &(Key, Nonce)) {
+        // Re-run ChaCha20 to re-encrypt the buffer
+        apply_keystream(data, &extra.0, &extra.1);
+    }
+}
+
+/// An algorithm that performs `ChaCha20` encryption and decryption.
+/// This algorithm is generic over drop strategy.
+///
+/// The 32-byte key and 12-byte nonce are stored alongside the encrypted data and
+/// are used to reproduce the keystream for decryption at runtime.
+pub struct ChaCha20<D: DropStrategy = Zeroize<(Key, Nonce)>>(PhantomData<D>);
+
+impl<D: DropStrategy<Extra = (Key, Nonce)>> Algorithm for ChaCha20<D> {
+    type Drop = D;
+    type Extra = (Key, Nonce);
+
+    fn encrypt(data: &mut [u8], extra: &(Key, Nonce)) {
+        apply_keystream(data, &extra.0, &extra.1);
+    }
+}
+
+impl<D: DropStrategy<Extra = (Key, Nonce)>, M: Mode, const N: usize> Encrypted<ChaCha20<D>, M, N> {
+    /// Creates a new encrypted buffer using `ChaCha20`.
+    ///
+    /// # Arguments
+    /// * `buffer` - The plaintext data to encrypt (must be an array of length N)
+    /// * `key` - The 256-bit `ChaCha20` key
+    /// * `nonce` - The 96-bit `ChaCha20` nonce
+    ///
+    /// This function performs `ChaCha20` encryption at compile time, generating one
+    /// 64-byte keystream block per 64 bytes of plaintext and XOR'ing it in.
+    pub const fn new(mut buffer: [u8; N], key: Key, nonce: Nonce) -> Self {
+        check_plaintext::<M, N>(&buffer);
+        apply_keystream(&mut buffer, &key, &nonce);
+
+        Encrypted {
+            buffer: UnsafeCell::new(buffer),
+            decryption_state: AtomicU8::new(STATE_UNENCRYPTED),
+            extra: (key, nonce),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::borrow_interior_mutable_const,
+    clippy::declare_interior_mutable_const,
+    clippy::explicit_auto_deref
+)]
+mod tests {
+    use super::*;
+    use crate::{ByteArray, StringLiteral, drop_strategy::Zeroize};
+
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use std::sync::Arc;
+    use std::thread;
+
+    /// Key `00 01 02 .. 1f` from RFC 8439 section 2.4.2.
+    const RFC_KEY: Key = {
+        let mut key = [0u8; 32];
+        let mut i = 0;
+        while i < 32 {
+            key[i] = i as u8;
+            i += 1;
+        }
+        key
+    };
+    const RFC_NONCE: Nonce = [0, 0, 0, 0, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+    const RFC_PLAINTEXT: [u8; 114] = *b"Ladies and Gentlemen of the class of '99: \
+        If I could offer you only one tip for the future, sunscreen would be it.";
+    const RFC_CIPHERTEXT: [u8; 114] = [
+        0x6e, 0x2e, 0x35, 0x9a, 0x25, 0x68, 0xf9, 0x80, 0x41, 0xba, 0x07, 0x28, 0xdd, 0x0d, 0x69,
+        0x81, 0xe9, 0x7e, 0x7a, 0xec, 0x1d, 0x43, 0x60, 0xc2, 0x0a, 0x27, 0xaf, 0xcc, 0xfd, 0x9f,
+        0xae, 0x0b, 0xf9, 0x1b, 0x65, 0xc5, 0x52, 0x47, 0x33, 0xab, 0x8f, 0x59, 0x3d, 0xab, 0xcd,
+        0x62, 0xb3, 0x57, 0x16, 0x39, 0xd6, 0x24, 0xe6, 0x51, 0x52, 0xab, 0x8f, 0x53, 0x0c, 0x35,
+        0x9f, 0x08, 0x61, 0xd8, 0x07, 0xca, 0x0d, 0xbf, 0x50, 0x0d, 0x6a, 0x61, 0x56, 0xa3, 0x8e,
+        0x08, 0x8a, 0x22, 0xb6, 0x5e, 0x52, 0xbc, 0x51, 0x4d, 0x16, 0xcc, 0xf8, 0x06, 0x81, 0x8c,
+        0xe9, 0x1a, 0xb7, 0x79, 0x37, 0x36, 0x5a, 0xf9, 0x0b, 0xbf, 0x74, 0xa3, 0x5b, 0xe6, 0xb4,
+        0x0b, 0x8e, 0xed, 0xf2, 0x78, 0x5e, 0x42, 0x87, 0x4d,
+    ];
+
+    type ZeroizeChaCha = ChaCha20<Zeroize<(Key, Nonce)>>;
+
+    const CONST_ENCRYPTED: Encrypted<ZeroizeChaCha, ByteArray, 114> =
+        Encrypted::<ZeroizeChaCha, ByteArray, 114>::new(RFC_PLAINTEXT, RFC_KEY, RFC_NONCE);
+
+    const CONST_ENCRYPTED_STR: Encrypted<ZeroizeChaCha, StringLiteral, 5> =
+        Encrypted::<ZeroizeChaCha, StringLiteral, 5>::new(*b"hello", RFC_KEY, RFC_NONCE);
+
+    #[test]
+    fn test_quarter_round_rfc_vector() {
+        // RFC 8439 section 2.1.1
+        let mut state = [0u32; 16];
+        state[0] = 0x1111_1111;
+        state[1] = 0x0102_0304;
+        state[2] = 0x9b8d_6f43;
+        state[3] = 0x0123_4567;
+        quarter_round(&mut state, 0, 1, 2, 3);
+        assert_eq!(&state[..4], &[0xea2a_92f4, 0xcb1c_f8ce, 0x4581_472e, 0x5881_c4bb]);
+    }
+
+    #[test]
+    fn test_ciphertext_matches_rfc_8439() {
+        let encrypted = CONST_ENCRYPTED;
+        let raw = unsafe { &*encrypted.buffer.get() };
+        assert_eq!(raw, &RFC_CIPHERTEXT);
+    }
+
+    #[test]
+    fn test_chacha20_bytearray_deref_decrypts() {
+        let encrypted = CONST_ENCRYPTED;
+        let decrypted: &[u8; 114] = &*encrypted;
+        assert_eq!(decrypted, &RFC_PLAINTEXT);
+    }
+
+    #[test]
+    fn test_chacha20_string_deref_decrypts() {
+        let encrypted = CONST_ENCRYPTED_STR;
+        let raw = unsafe { &*encrypted.buffer.get() };
+        assert_ne!(raw, b"hello", "buffer must NOT be plaintext before deref");
+
+        let decrypted: &str = &*encrypted;
+        assert_eq!(decrypted, "hello");
+    }
+
+    #[test]
+    fn test_round_trip_partial_blocks() {
+        // Lengths around block boundaries; only the last block is truncated.
+        for len in [0, 1, 63, 64, 65, 127, 128, 129, 200] {
+            let plain: Vec<u8> = (0..len).map(|i| (i * 7) as u8).collect();
+            let mut data = plain.clone();
+            apply_keystream(&mut data, &RFC_KEY, &RFC_NONCE);
+            if len > 0 {
+                assert_ne!(data, plain, "length {len}");
+            }
+            assert_eq!(&data[..], &encrypt_bytewise(&plain)[..], "length {len}");
+            apply_keystream(&mut data, &RFC_KEY, &RFC_NONCE);
+            assert_eq!(data, plain, "length {len}");
+        }
+    }
+
+    /// Encrypts `plain` one byte at a time so the comparison does not share the
+    /// block-truncation logic with `apply_keystream`.
+    fn encrypt_bytewise(plain: &[u8]) -> Vec<u8> {
+        plain
+            .iter()
+            .enumerate()
+            .map(|(i, byte)| {
+                let counter = INITIAL_COUNTER + (i / 64) as u32;
+                byte ^ block(&RFC_KEY, &RFC_NONCE, counter)[i % 64]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_chacha20_reencrypt_drop() {
+        let mut data = RFC_PLAINTEXT;
+        ReEncrypt::drop(&mut data, &(RFC_KEY, RFC_NONCE));
+        assert_eq!(data, RFC_CIPHERTEXT);
+    }
+
+    #[test]
+    fn test_chacha20_concurrent_deref_same_value() {
+        let shared = Arc::new(CONST_ENCRYPTED);
+        let mut handles: Vec<thread::JoinHandle<()>> = vec![];
+
+        for _ in 0..10 {
+            let shared_clone = Arc::clone(&shared);
+            let handle = thread::spawn(move || {
+                let decrypted: &[u8; 114] = &*shared_clone;
+                assert_eq!(decrypted, &RFC_PLAINTEXT);
+            });
+            handles.push(handle);
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+}
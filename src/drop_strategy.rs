@@ -6,9 +6,22 @@
 //!
 //! # Available Strategies
 //!
-//! - [`Zeroize`]: Overwrites the buffer with zeros using the `zeroize` crate
+//! - [`Zeroize`]: Overwrites the buffer with zeros, using the `zeroize` crate when the
+//!   `zeroize` feature is enabled
+//! - [`VolatileZeroize`]: Overwrites the buffer with zeros using volatile writes only
+//! - [`RandomFill`]: Overwrites the buffer with pseudorandom noise instead of zeros
+//! - [`FillWith`]: Overwrites the buffer with a fixed byte instead of zeros
+//! - [`MultiPassOverwrite`]: Overwrites the buffer several times with alternating
+//!   patterns, ending with zeros; [`TwoPassOverwrite`] writes `0xFF` and then `0x00`
+//! - [`RngFill`]: Overwrites the buffer with bytes from a random source, such as
+//!   [`OsRng`] with the `getrandom` feature
 //! - [`NoOp`]: Does nothing, leaving the data in memory as-is
 //!
+//! Combinators:
+//! - [`Chain`]: Applies one strategy and then another, e.g. re-encrypting and then
+//!   zeroizing
+//! - [`Conditional`]: Applies a strategy only when a const flag is set
+//!
 //! Algorithm-specific strategies:
 //! - [`xor::ReEncrypt`](crate::xor::ReEncrypt): Re-encrypts with XOR
 //! - [`rc4::ReEncrypt`](crate::rc4::ReEncrypt): Re-encrypts with RC4
@@ -19,26 +32,575 @@
 //! algorithms that may need to store additional data (like encryption keys).
 
 use core::marker::PhantomData;
-use zeroize::Zeroize as ZeroizeTrait;
+
+use crate::xor_prng::Xorshift64;
 
 pub trait DropStrategy {
     type Extra;
+
+    /// What [`DropStrategy::drop`] leaves in a decrypted buffer.
+    ///
+    /// [`Encrypted::reset`](crate::Encrypted::reset) uses this to decide which state
+    /// the secret is in afterwards. The default, [`Residue::Scrubbed`], is always safe:
+    /// at worst a secret that could have been decrypted again is treated as wiped.
+    const RESIDUE: Residue = Residue::Scrubbed;
+
+    /// Whether [`DropStrategy::drop`] encrypts the buffer at any step.
+    ///
+    /// Usually the same as leaving [`Residue::Ciphertext`], but a [`Chain`] that
+    /// re-encrypts and then scrubs re-encrypts without leaving ciphertext behind.
+    const REENCRYPTS: bool = matches!(Self::RESIDUE, Residue::Ciphertext);
+
     fn drop(data: &mut [u8], extra: &Self::Extra);
+
+    /// Applies the strategy to a buffer that still holds ciphertext, because the
+    /// secret was never decrypted.
+    ///
+    /// Encrypting ciphertext again would decrypt it with XOR and RC4, so the default
+    /// does nothing for a strategy that [re-encrypts](DropStrategy::REENCRYPTS) and
+    /// calls [`DropStrategy::drop`] otherwise. [`Chain`] skips only its re-encrypting
+    /// half.
+    fn drop_sealed(data: &mut [u8], extra: &Self::Extra) {
+        if !Self::REENCRYPTS {
+            Self::drop(data, extra);
+        }
+    }
+}
+
+/// What a [`DropStrategy`] leaves behind in a buffer that held plaintext.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Residue {
+    /// The buffer holds the original ciphertext again, so it can be decrypted again.
+    Ciphertext,
+    /// The buffer no longer holds anything meaningful (e.g. it was zeroized).
+    Scrubbed,
+    /// The buffer still holds the plaintext.
+    Plaintext,
+}
+
+impl Residue {
+    /// What is left after a strategy leaving `self` is followed by one leaving `next`.
+    ///
+    /// A strategy that leaves plaintext did not touch the buffer, so the other one
+    /// decides. Scrubbing after re-encrypting, or after scrubbing, leaves nothing
+    /// meaningful.
+    ///
+    /// # Panics
+    ///
+    /// Panics, which fails the build where it is evaluated, if `next` re-encrypts a
+    /// buffer that `self` did not leave as plaintext:
+    ///
+    /// - After ciphertext, encrypting again leaves neither: XOR or RC4 under the same
+    ///   key restores the plaintext, and under different keys it leaves bytes that no
+    ///   key decrypts to the secret.
+    /// - After scrubbing, encrypting the zeros leaves the keystream, i.e. the key for
+    ///   XOR.
+    const fn then(self, next: Self) -> Self {
+        match (self, next) {
+            (residue, Self::Plaintext) | (Self::Plaintext, residue) => residue,
+            (Self::Ciphertext, Self::Ciphertext) => {
+                panic!("`Chain` cannot apply two strategies that both re-encrypt")
+            }
+            (Self::Scrubbed, Self::Ciphertext) => {
+                panic!("`Chain` cannot re-encrypt a buffer that was already scrubbed")
+            }
+            (_, Self::Scrubbed) => Self::Scrubbed,
+        }
+    }
 }
 
 /// Zeroizes the buffer on drop. Generic over the Extra type to work with any algorithm.
 pub struct Zeroize<E = ()>(PhantomData<E>);
+/// Zeroizes the buffer on drop with `core::ptr::write_volatile`, one byte at a time.
+///
+/// Volatile writes cannot be removed as dead stores, even when the optimizer can prove
+/// the memory is never read again. Needs no dependency, regardless of the `zeroize`
+/// feature. Generic over the Extra type to work with any algorithm.
+pub struct VolatileZeroize<E = ()>(PhantomData<E>);
+/// Overwrites the buffer on drop with pseudorandom bytes, so it looks like noise
+/// rather than a conspicuous run of zeros.
+///
+/// The bytes come from a xorshift64 generator, which is fast and needs no OS
+/// randomness, but is not cryptographically strong: the goal is obfuscation. The
+/// generator is seeded from the buffer's address and length, never from its
+/// contents. At drop time the buffer holds plaintext, and xorshift output can be
+/// run backwards to its seed, so a content-derived seed would leak a digest of the
+/// secret. Generic over the Extra type to work with any algorithm.
+pub struct RandomFill<E = ()>(PhantomData<E>);
+/// Overwrites the buffer on drop with `BYTE`, using volatile writes.
+///
+/// A run of zeros can itself give away where a secret was. `FillWith<0xFF>` makes the
+/// buffer look like erased flash instead. Generic over the Extra type to work with any
+/// algorithm.
+pub struct FillWith<const BYTE: u8, E = ()>(PhantomData<E>);
+/// Overwrites the buffer on drop `PASSES` times, using volatile writes.
+///
+/// The passes cycle through `0xFF`, `0xAA` and `0x55`, and the last pass always
+/// writes `0x00`, so `MultiPassOverwrite<4>` writes `0xFF`, `0xAA`, `0x55`, `0x00`.
+/// This is a simplified take on multi-pass schemes such as Gutmann's, for compliance
+/// rules that ask for more than one overwrite; on RAM a single pass is as good.
+/// `PASSES` must be at least 1, which is checked at compile time. Generic over the
+/// Extra type to work with any algorithm.
+pub struct MultiPassOverwrite<const PASSES: usize, E = ()>(PhantomData<E>);
+/// Overwrites the buffer on drop with `0xFF` and then `0x00`, using volatile writes.
+pub type TwoPassOverwrite<E = ()> = MultiPassOverwrite<2, E>;
+/// Overwrites the buffer on drop with bytes from the random source `R`.
+///
+/// Unlike [`RandomFill`], whose noise comes from a fast but predictable generator,
+/// this uses a real random source, so the residue carries no structure at all: it
+/// defeats pattern scanners that look for zeros or for a known generator. That costs
+/// a call into the source on every drop, which for [`OsRng`] is a system call. Generic
+/// over the Extra type to work with any algorithm.
+pub struct RngFill<R, E = ()>(PhantomData<(R, E)>);
 /// Does nothing on drop. Generic over the Extra type to work with any algorithm.
 pub struct NoOp<E = ()>(PhantomData<E>);
 
+/// A source of random bytes for [`RngFill`].
+///
+/// Implement it on a marker type to plug in a platform RNG, e.g. a hardware TRNG on
+/// a microcontroller without an OS.
+pub trait ByteSource {
+    /// Returns a random byte.
+    fn next_byte() -> u8;
+
+    /// Fills `data` with random bytes. The default calls
+    /// [`next_byte`](Self::next_byte) once per byte.
+    fn fill(data: &mut [u8]) {
+        for byte in data {
+            *byte = Self::next_byte();
+        }
+    }
+}
+
+/// The operating system's random number generator, through `getrandom`.
+///
+/// If it fails, [`RandomFill`]'s generator fills the buffer instead, since a drop
+/// must not panic.
+#[cfg(feature = "getrandom")]
+pub struct OsRng;
+
+#[cfg(feature = "getrandom")]
+impl ByteSource for OsRng {
+    fn next_byte() -> u8 {
+        let mut byte = [0];
+        Self::fill(&mut byte);
+        byte[0]
+    }
+
+    fn fill(data: &mut [u8]) {
+        if getrandom::fill(data).is_err() {
+            RandomFill::<()>::drop(data, &());
+        }
+    }
+}
+/// Applies `A` and then `B` on drop, e.g. `Chain<ReEncrypt<0xAA>, VolatileZeroize>` to
+/// re-encrypt the plaintext and then overwrite the ciphertext with zeros.
+///
+/// Both strategies receive the same extra data. On a secret that was never
+/// decrypted, a re-encrypting half is skipped, as a lone re-encrypting strategy is, and
+/// the other half still runs.
+///
+/// Chaining two strategies that both re-encrypt fails to compile, since re-encrypting
+/// twice with XOR or RC4 under the same key would leave the plaintext behind:
+///
+/// ```compile_fail
+/// use const_secret::{ByteArray, Encrypted, drop_strategy::Chain, xor::{ReEncrypt, Xor}};
+///
+/// let secret =
+///     Encrypted::<Xor<0xAA, Chain<ReEncrypt<0xAA>, ReEncrypt<0xAA>>>, ByteArray, 2>::new([1, 2]);
+/// assert_eq!(&*secret, &[1, 2]);
+/// ```
+///
+/// So does re-encrypting after scrubbing, which would leave the keystream behind:
+///
+/// ```compile_fail
+/// use const_secret::{
+///     ByteArray, Encrypted,
+///     drop_strategy::{Chain, Zeroize},
+///     xor::{ReEncrypt, Xor},
+/// };
+///
+/// let secret =
+///     Encrypted::<Xor<0xAA, Chain<Zeroize, ReEncrypt<0xAA>>>, ByteArray, 2>::new([1, 2]);
+/// assert_eq!(&*secret, &[1, 2]);
+/// ```
+pub struct Chain<A, B>(PhantomData<(A, B)>);
+/// Applies `D` on drop when `ENABLED` is `true`, and does nothing otherwise.
+///
+/// Lets a const expression pick the strategy, e.g.
+/// `Conditional<{ !cfg!(debug_assertions) }, Zeroize>` to keep plaintext around for
+/// inspection in debug builds only.
+pub struct Conditional<const ENABLED: bool, D>(PhantomData<D>);
+
 impl<E> DropStrategy for Zeroize<E> {
     type Extra = E;
+    const RESIDUE: Residue = Residue::Scrubbed;
+
     fn drop(data: &mut [u8], _extra: &E) {
-        data.zeroize();
+        crate::wipe(data);
+    }
+}
+
+impl<E> DropStrategy for VolatileZeroize<E> {
+    type Extra = E;
+    const RESIDUE: Residue = Residue::Scrubbed;
+
+    fn drop(data: &mut [u8], _extra: &E) {
+        crate::wipe_volatile(data);
+    }
+}
+
+impl<E> DropStrategy for RandomFill<E> {
+    type Extra = E;
+    const RESIDUE: Residue = Residue::Scrubbed;
+
+    fn drop(data: &mut [u8], _extra: &E) {
+        let seed = (data.as_ptr().addr() as u64) ^ (data.len() as u64).rotate_left(32);
+        let mut generator = Xorshift64::new(seed);
+        for chunk in data.chunks_mut(8) {
+            for (byte, noise) in chunk.iter_mut().zip(generator.next_bytes()) {
+                // SAFETY: `byte` is a valid, aligned, exclusive reference.
+                unsafe { core::ptr::write_volatile(byte, noise) };
+            }
+        }
+        core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+impl<const BYTE: u8, E> DropStrategy for FillWith<BYTE, E> {
+    type Extra = E;
+    const RESIDUE: Residue = Residue::Scrubbed;
+
+    fn drop(data: &mut [u8], _extra: &E) {
+        for byte in data {
+            // SAFETY: `byte` is a valid, aligned, exclusive reference.
+            unsafe { core::ptr::write_volatile(byte, BYTE) };
+        }
+        core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+impl<const PASSES: usize, E> MultiPassOverwrite<PASSES, E> {
+    /// The patterns written by every pass but the last, in order, repeating.
+    const PATTERNS: [u8; 3] = [0xFF, 0xAA, 0x55];
+}
+
+impl<const PASSES: usize, E> DropStrategy for MultiPassOverwrite<PASSES, E> {
+    type Extra = E;
+    const RESIDUE: Residue = Residue::Scrubbed;
+
+    fn drop(data: &mut [u8], _extra: &E) {
+        const { assert!(PASSES >= 1, "MultiPassOverwrite needs at least one pass") };
+
+        for pass in 0..PASSES {
+            let pattern = if pass + 1 == PASSES {
+                0x00
+            } else {
+                Self::PATTERNS[pass % Self::PATTERNS.len()]
+            };
+            for byte in data.iter_mut() {
+                // SAFETY: `byte` is a valid, aligned, exclusive reference.
+                unsafe { core::ptr::write_volatile(byte, pattern) };
+            }
+            // Keep each pass whole in memory before the next one starts.
+            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+        }
+    }
+}
+
+impl<R: ByteSource, E> DropStrategy for RngFill<R, E> {
+    type Extra = E;
+    const RESIDUE: Residue = Residue::Scrubbed;
+
+    fn drop(data: &mut [u8], _extra: &E) {
+        R::fill(data);
+        // Stores made by the source could be removed as dead; storing each byte again
+        // with a volatile write keeps the plaintext overwritten.
+        for byte in data {
+            // SAFETY: `byte` is a valid, aligned, exclusive reference.
+            unsafe { core::ptr::write_volatile(byte, *byte) };
+        }
+        core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
     }
 }
 
 impl<E> DropStrategy for NoOp<E> {
     type Extra = E;
+    const RESIDUE: Residue = Residue::Plaintext;
+
     fn drop(_data: &mut [u8], _extra: &E) {}
 }
+
+impl<A: DropStrategy, B: DropStrategy<Extra = A::Extra>> DropStrategy for Chain<A, B> {
+    type Extra = A::Extra;
+    const RESIDUE: Residue = A::RESIDUE.then(B::RESIDUE);
+    const REENCRYPTS: bool = A::REENCRYPTS || B::REENCRYPTS;
+
+    fn drop(data: &mut [u8], extra: &A::Extra) {
+        // Evaluates `RESIDUE` for every chain that is used, to reject the orders
+        // `Residue::then` refuses.
+        const { Self::RESIDUE };
+        A::drop(data, extra);
+        B::drop(data, extra);
+    }
+
+    fn drop_sealed(data: &mut [u8], extra: &A::Extra) {
+        const { Self::RESIDUE };
+        A::drop_sealed(data, extra);
+        B::drop_sealed(data, extra);
+    }
+}
+
+impl<const ENABLED: bool, D: DropStrategy> DropStrategy for Conditional<ENABLED, D> {
+    type Extra = D::Extra;
+    const RESIDUE: Residue = if ENABLED {
+        D::RESIDUE
+    } else {
+        Residue::Plaintext
+    };
+    const REENCRYPTS: bool = ENABLED && D::REENCRYPTS;
+
+    fn drop(data: &mut [u8], extra: &D::Extra) {
+        if ENABLED {
+            D::drop(data, extra);
+        }
+    }
+
+    fn drop_sealed(data: &mut [u8], extra: &D::Extra) {
+        if ENABLED {
+            D::drop_sealed(data, extra);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::explicit_auto_deref)]
+mod tests {
+    use super::*;
+    use crate::{
+        ByteArray, Encrypted,
+        rc4::Rc4,
+        xor::{ReEncrypt, Xor},
+    };
+
+    #[test]
+    fn test_volatile_zeroize_clears_buffer() {
+        let mut data = [0xAAu8; 16];
+        <VolatileZeroize as DropStrategy>::drop(&mut data, &());
+        assert_eq!(data, [0u8; 16]);
+    }
+
+    #[test]
+    fn test_random_fill_leaves_noise() {
+        let plaintext = *b"a secret that must not linger!!!";
+        let mut first = plaintext;
+        let mut second = plaintext;
+        <RandomFill as DropStrategy>::drop(&mut first, &());
+        <RandomFill as DropStrategy>::drop(&mut second, &());
+
+        assert_ne!(first, plaintext);
+        assert_ne!(first, [0u8; 32]);
+        assert_ne!(first, second, "different buffers must get different noise");
+    }
+
+    #[test]
+    fn test_fill_with_byte() {
+        let mut data = *b"plaintext";
+        <FillWith<0xFF> as DropStrategy>::drop(&mut data, &());
+        assert_eq!(data, [0xFF; 9]);
+
+        let mut secret = Encrypted::<Xor<0xAA, FillWith<0xFF>>, ByteArray, 16>::new([7; 16]);
+        assert_eq!(&*secret, &[7; 16]);
+        secret.reset();
+        assert!(secret.is_wiped());
+        assert_eq!(unsafe { *secret.buffer.get() }, [0xFF; 16]);
+
+        let rc4 =
+            Encrypted::<Rc4<5, FillWith<0x5A, [u8; 5]>>, ByteArray, 4>::new(*b"data", *b"mykey");
+        assert_eq!(&*rc4, b"data");
+    }
+
+    #[test]
+    fn test_multi_pass_overwrite_ends_zeroed() {
+        let mut data = *b"plaintext";
+        <TwoPassOverwrite as DropStrategy>::drop(&mut data, &());
+        assert_eq!(data, [0; 9]);
+
+        let mut data = [0x42; 4];
+        <MultiPassOverwrite<7> as DropStrategy>::drop(&mut data, &());
+        assert_eq!(data, [0; 4]);
+        assert!(matches!(MultiPassOverwrite::<3>::RESIDUE, Residue::Scrubbed));
+
+        let mut secret = Encrypted::<Xor<0xAA, TwoPassOverwrite>, ByteArray, 8>::new([7; 8]);
+        assert_eq!(&*secret, &[7; 8]);
+        secret.reset();
+        assert!(secret.is_wiped());
+        assert_eq!(unsafe { *secret.buffer.get() }, [0; 8]);
+    }
+
+    #[test]
+    fn test_rng_fill_with_custom_source() {
+        use core::sync::atomic::{AtomicU8, Ordering};
+
+        struct Counter;
+        static NEXT: AtomicU8 = AtomicU8::new(1);
+        impl ByteSource for Counter {
+            fn next_byte() -> u8 {
+                NEXT.fetch_add(1, Ordering::Relaxed)
+            }
+        }
+
+        let plaintext = [0xAAu8; 8];
+        let mut data = plaintext;
+        <RngFill<Counter> as DropStrategy>::drop(&mut data, &());
+        assert_ne!(data, plaintext);
+        assert_ne!(data, [0; 8]);
+        assert!(data.windows(2).all(|pair| pair[1] == pair[0].wrapping_add(1)));
+
+        let mut secret = Encrypted::<Xor<0xAA, RngFill<Counter>>, ByteArray, 4>::new([7; 4]);
+        assert_eq!(&*secret, &[7; 4]);
+        secret.reset();
+        assert!(secret.is_wiped());
+    }
+
+    #[test]
+    #[cfg(feature = "getrandom")]
+    fn test_rng_fill_with_os_rng() {
+        let plaintext = *b"a secret that must not linger!!!";
+        let mut data = plaintext;
+        <RngFill<OsRng> as DropStrategy>::drop(&mut data, &());
+        assert_ne!(data, plaintext);
+        assert_ne!(data, [0u8; 32]);
+
+        let rc4 =
+            Encrypted::<Rc4<5, RngFill<OsRng, [u8; 5]>>, ByteArray, 4>::new(*b"data", *b"mykey");
+        assert_eq!(&*rc4, b"data");
+    }
+
+    #[test]
+    fn test_random_fill_with_algorithms() {
+        let mut secret = Encrypted::<Xor<0xAA, RandomFill>, ByteArray, 16>::new([7; 16]);
+        assert_eq!(&*secret, &[7; 16]);
+        secret.reset();
+        assert!(secret.is_wiped());
+        assert_ne!(unsafe { *secret.buffer.get() }, [7; 16]);
+
+        let rc4 = Encrypted::<Rc4<5, RandomFill<[u8; 5]>>, ByteArray, 4>::new(*b"data", *b"mykey");
+        assert_eq!(&*rc4, b"data");
+    }
+
+    #[test]
+    fn test_volatile_zeroize_with_algorithms() {
+        let mut xor = Encrypted::<Xor<0xAA, VolatileZeroize>, ByteArray, 16>::new([7; 16]);
+        assert_eq!(&*xor, &[7; 16]);
+        xor.reset();
+        assert!(xor.is_wiped());
+
+        let rc4 =
+            Encrypted::<Rc4<5, VolatileZeroize<[u8; 5]>>, ByteArray, 4>::new(*b"data", *b"mykey");
+        assert_eq!(&*rc4, b"data");
+    }
+
+    #[test]
+    fn test_chain_applies_both_in_order() {
+        let mut data = [0x0Fu8; 4];
+        <Chain<ReEncrypt<0xF0>, NoOp> as DropStrategy>::drop(&mut data, &());
+        assert_eq!(data, [0xFF; 4]);
+        <Chain<ReEncrypt<0xFF>, VolatileZeroize> as DropStrategy>::drop(&mut data, &());
+        assert_eq!(data, [0; 4]);
+
+        let mut secret =
+            Encrypted::<Xor<0xAA, Chain<ReEncrypt<0xAA>, VolatileZeroize>>, ByteArray, 16>::new(
+                [7; 16],
+            );
+        assert_eq!(&*secret, &[7; 16]);
+        secret.reset();
+        assert!(secret.is_wiped());
+        assert_eq!(unsafe { *secret.buffer.get() }, [0; 16]);
+
+        let rc4 = Encrypted::<Rc4<5, Chain<NoOp<[u8; 5]>, Zeroize<[u8; 5]>>>, ByteArray, 4>::new(
+            *b"data", *b"mykey",
+        );
+        assert_eq!(&*rc4, b"data");
+    }
+
+    #[test]
+    fn test_chain_residue() {
+        assert_eq!(<Chain<ReEncrypt<1>, NoOp>>::RESIDUE, Residue::Ciphertext);
+        assert_eq!(<Chain<NoOp, ReEncrypt<1>>>::RESIDUE, Residue::Ciphertext);
+        assert_eq!(<Chain<ReEncrypt<1>, Zeroize>>::RESIDUE, Residue::Scrubbed);
+        assert_eq!(<Chain<NoOp, NoOp>>::RESIDUE, Residue::Plaintext);
+
+        // Re-encrypting first leaves ciphertext that `reset` can decrypt again.
+        let mut secret =
+            Encrypted::<Xor<0xAA, Chain<ReEncrypt<0xAA>, NoOp>>, ByteArray, 2>::new([1, 2]);
+        assert_eq!(&*secret, &[1, 2]);
+        secret.reset();
+        assert!(!secret.is_decrypted());
+        assert_eq!(&*secret, &[1, 2]);
+    }
+
+    #[test]
+    fn test_chain_skips_reencrypt_on_sealed_secret() {
+        use std::sync::Mutex;
+
+        static SEEN: Mutex<[u8; 5]> = Mutex::new([0; 5]);
+
+        /// Records what the chain's first half left in the buffer, then zeroizes it.
+        struct Spy;
+
+        impl DropStrategy for Spy {
+            type Extra = ();
+
+            fn drop(data: &mut [u8], _extra: &()) {
+                SEEN.lock().unwrap().copy_from_slice(data);
+                crate::wipe(data);
+            }
+        }
+
+        // Never decrypted: `ReEncrypt` is skipped, so the spy sees the ciphertext.
+        let secret =
+            Encrypted::<Xor<0xAA, Chain<ReEncrypt<0xAA>, Spy>>, ByteArray, 5>::new(*b"hello");
+        let sealed = secret.encrypted_bytes();
+        drop(secret);
+        assert_eq!(*SEEN.lock().unwrap(), sealed);
+
+        // Decrypted: `ReEncrypt` restores the ciphertext before the spy runs.
+        let secret =
+            Encrypted::<Xor<0xAA, Chain<ReEncrypt<0xAA>, Spy>>, ByteArray, 5>::new(*b"hello");
+        assert_eq!(&*secret, b"hello");
+        drop(secret);
+        assert_eq!(*SEEN.lock().unwrap(), sealed);
+
+        // The other order leaves the ciphertext as it is.
+        let mut data = sealed;
+        <Chain<NoOp, ReEncrypt<0xAA>> as DropStrategy>::drop_sealed(&mut data, &());
+        assert_eq!(data, sealed);
+        <Chain<NoOp, Conditional<true, ReEncrypt<0xAA>>> as DropStrategy>::drop_sealed(
+            &mut data,
+            &(),
+        );
+        assert_eq!(data, sealed);
+        <Chain<ReEncrypt<0xAA>, VolatileZeroize> as DropStrategy>::drop_sealed(&mut data, &());
+        assert_eq!(data, [0; 5]);
+    }
+
+    #[test]
+    fn test_conditional() {
+        let mut data = [7u8; 4];
+        <Conditional<false, Zeroize> as DropStrategy>::drop(&mut data, &());
+        assert_eq!(data, [7; 4]);
+        assert_eq!(<Conditional<false, Zeroize>>::RESIDUE, Residue::Plaintext);
+
+        <Conditional<true, Zeroize> as DropStrategy>::drop(&mut data, &());
+        assert_eq!(data, [0; 4]);
+        assert_eq!(<Conditional<true, Zeroize>>::RESIDUE, Residue::Scrubbed);
+
+        let mut secret =
+            Encrypted::<Xor<0xAA, Conditional<false, Zeroize>>, ByteArray, 2>::new([1, 2]);
+        assert_eq!(&*secret, &[1, 2]);
+        secret.reset();
+        assert!(secret.is_decrypted());
+        assert_eq!(&*secret, &[1, 2]);
+    }
+}
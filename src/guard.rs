@@ -0,0 +1,142 @@
+//! Scoped plaintext access that applies the drop strategy when the scope ends.
+//!
+//! [`Encrypted::guard`] decrypts a secret and returns a [`DecryptedGuard`]. The
+//! plaintext is available through the guard for as long as it lives; when it is
+//! dropped, the secret is [`reset`](Encrypted::reset). What that means depends on the
+//! algorithm's [`DropStrategy`](crate::drop_strategy::DropStrategy): a `ReEncrypt`
+//! secret is sealed again and can be guarded again later, while a `Zeroize` secret is
+//! wiped.
+//!
+//! # Example
+//!
+//! ```rust
+//! use const_secret::{
+//!     Encrypted, StringLiteral,
+//!     xor::{ReEncrypt, Xor},
+//! };
+//!
+//! fn use_password(password: &str) -> usize {
+//!     password.len()
+//! }
+//!
+//! let mut secret =
+//!     Encrypted::<Xor<0xAA, ReEncrypt<0xAA>>, StringLiteral, 6>::new(*b"hunter");
+//!
+//! {
+//!     let password = secret.guard();
+//!     assert_eq!(use_password(&password), 6);
+//! }
+//! assert!(!secret.is_decrypted());
+//! ```
+
+use core::{fmt, ops::Deref};
+
+use crate::{Algorithm, Encrypted, Mode};
+
+/// Plaintext access to an [`Encrypted`] secret, ended by dropping the guard.
+///
+/// Created by [`Encrypted::guard`]. Dereferences to the same type as the secret: `str`
+/// for [`StringLiteral`](crate::StringLiteral) and `[u8; N]` for
+/// [`ByteArray`](crate::ByteArray).
+pub struct DecryptedGuard<'a, A: Algorithm, M, const N: usize> {
+    /// The guarded secret. Always decrypted while the guard is alive.
+    secret: &'a mut Encrypted<A, M, N>,
+}
+
+impl<A: Algorithm, M: Mode, const N: usize> Encrypted<A, M, N> {
+    /// Decrypts the secret and returns a guard that resets it when dropped.
+    ///
+    /// This takes `&mut self` for the same reason as [`reset`](Self::reset): the
+    /// guard overwrites the buffer when it is dropped, which must not happen while
+    /// another reference into the plaintext is alive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret was wiped or poisoned.
+    pub fn guard(&mut self) -> DecryptedGuard<'_, A, M, N> {
+        self.decrypted();
+        DecryptedGuard {
+            secret: self,
+        }
+    }
+}
+
+impl<A: Algorithm, M: Mode, const N: usize> Deref for DecryptedGuard<'_, A, M, N> {
+    type Target = M::Target<N>;
+
+    fn deref(&self) -> &Self::Target {
+        self.secret
+    }
+}
+
+impl<A: Algorithm, M, const N: usize> Drop for DecryptedGuard<'_, A, M, N> {
+    /// Applies the secret's drop strategy, ending the plaintext window.
+    fn drop(&mut self) {
+        self.secret.reset();
+    }
+}
+
+impl<A: Algorithm, M, const N: usize> fmt::Debug for DecryptedGuard<'_, A, M, N> {
+    /// Formats the guard without revealing the plaintext.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DecryptedGuard").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::explicit_auto_deref)]
+mod tests {
+    use crate::{
+        ByteArray, Encrypted, StringLiteral,
+        drop_strategy::{NoOp, Zeroize},
+        rc4::{self, Rc4},
+        xor::{ReEncrypt, Xor},
+    };
+
+    #[test]
+    fn test_reencrypt_guard_reseals() {
+        let mut secret = Encrypted::<Xor<0xAA, ReEncrypt<0xAA>>, StringLiteral, 5>::new(*b"hello");
+
+        for _ in 0..2 {
+            let guard = secret.guard();
+            assert_eq!(&*guard, "hello");
+            drop(guard);
+            assert!(!secret.is_decrypted());
+        }
+    }
+
+    #[test]
+    fn test_zeroize_guard_wipes() {
+        let mut secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([1, 2, 3]);
+
+        assert_eq!(&*secret.guard(), &[1, 2, 3]);
+        assert!(secret.is_wiped());
+    }
+
+    #[test]
+    fn test_noop_guard_keeps_plaintext() {
+        let mut secret = Encrypted::<Xor<0xAA, NoOp>, ByteArray, 3>::new([1, 2, 3]);
+
+        drop(secret.guard());
+        assert!(secret.is_decrypted());
+        assert_eq!(&*secret, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_rc4_guard() {
+        let mut secret =
+            Encrypted::<Rc4<5, rc4::ReEncrypt<5>>, StringLiteral, 4>::new(*b"data", *b"mykey");
+
+        assert_eq!(&*secret.guard(), "data");
+        assert!(!secret.is_decrypted());
+        assert_eq!(&*secret, "data");
+    }
+
+    #[test]
+    #[should_panic(expected = "secret was wiped")]
+    fn test_guard_after_wipe_panics() {
+        let mut secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([1, 2, 3]);
+        secret.zeroize_now();
+        let _ = secret.guard();
+    }
+}
@@ -7,14 +7,59 @@
 //! # Features
 //!
 //! - **Compile-time encryption**: Secrets are encrypted during compilation
-//! - **Multiple algorithms**: XOR (simple, fast) and RC4 (stream cipher)
+//! - **Multiple algorithms**: XOR (simple, fast), additive shift, RC4 (stream cipher),
+//!   `ChaCha20` (modern stream cipher), `Salsa20` (`NaCl`-compatible), and AES-128 and
+//!   SPECK128/256 (lightweight, for microcontrollers) in CTR mode
 //! - **Drop strategies**: Control what happens to decrypted data on drop:
 //!   - `Zeroize`: Overwrites memory with zeros
+//!   - `RandomFill`: Overwrites memory with pseudorandom noise
+//!   - `RngFill`: Overwrites memory with bytes from a random source
 //!   - `ReEncrypt`: Re-encrypts the data
 //!   - `NoOp`: Leaves data unchanged
-//! - **Thread-safe**: `Sync` implementation allows concurrent access
+//! - **Thread-safe**: `Sync` implementation allows concurrent access, with a configurable
+//!   [`spin`] yield hook for cooperative schedulers
+//! - **Runtime sealing**: [`builder::SealingBuilder`] seals secrets assembled at runtime
+//! - **`secret!` macro**: [`secret!`] declares a secret and infers its length from the literal
+//! - **Scoped access**: [`guard::DecryptedGuard`] applies the drop strategy when the scope ends
+//! - **Read-only placement**: [`read_only::ReadOnly`] decrypts out-of-band so the ciphertext can live in read-only memory
+//! - **Groups of secrets**: [`array::EncryptedArray`] declares several same-shaped secrets in one `const`
 //! - `no_std` compatible: Works in embedded environments
 //!
+//! # Cargo Features
+//!
+//! - `zeroize` (default): wipes buffers with the audited [`zeroize`](https://docs.rs/zeroize)
+//!   crate. Disabling it drops the dependency and falls back to volatile writes
+//!   followed by a compiler fence. The fallback is best-effort and does not carry the
+//!   `zeroize` crate's audited guarantees.
+//! - `alloc`: adds `Encrypted::to_decrypted_vec` and `From` conversions into `String`
+//!   and `Vec<u8>`, which hand out the plaintext in a heap buffer owned by the caller,
+//!   and `boxed::EncryptedBox`, a heap-allocated secret for values read at runtime.
+//!   Needs only the `alloc` crate, not `std`.
+//! - `secrecy`: implements [`secrecy::ExposeSecret`] for [`Encrypted`], exposing the
+//!   same type as dereferencing, and re-exports the `secrecy` crate, which needs `alloc`.
+//! - `macros`: [`macros::secret!`](macros), a procedural macro that builds a secret from a
+//!   string, byte string or C string literal and infers its length.
+//! - `build-helpers`: `build_helpers`, which generates random keys in a build script
+//!   and passes them to [`key_from_env!`]. Needs `std`.
+//! - `getrandom`: `drop_strategy::OsRng`, a source of OS randomness for the `RngFill`
+//!   drop strategy.
+//! - `simd`: XOR uses AVX2 for long buffers on `x86_64` CPUs that support it. Implies
+//!   `std`, for runtime feature detection.
+//! - `subtle`: implements `subtle::ConstantTimeEq` between [`ByteArray`] secrets,
+//!   comparing their plaintext.
+//! - `serde`: `Serialize` and `Deserialize` for [`Encrypted`] that carry only the
+//!   ciphertext. See `serde_support`.
+//! - `slog`, `tracing`: log secrets as `"[REDACTED]"` with `slog` or `tracing`. See
+//!   `logging`.
+//! - `debug-logging`: `Encrypted::with_plaintext_logging`, which logs the plaintext
+//!   instead, and `Encrypted::masked`, which shows only its last bytes. For
+//!   development only.
+//! - `std`: threads waiting for another thread to finish decrypting block on a
+//!   condition variable after spinning for a while, instead of burning a core. See
+//!   [`spin`] for setting a yield hook without `std`.
+//! - `parking_lot`: block on `parking_lot`'s condition variable instead of the standard
+//!   library's. Implies `std`.
+//!
 //! # Examples
 //!
 //! ## XOR Algorithm
@@ -43,7 +88,7 @@
 //!
 //! ## RC4 Algorithm
 //!
-//! RC4 is a stream cipher with variable-length keys (1-256 bytes).
+//! RC4 is a stream cipher with variable-length keys (5-256 bytes).
 //! **Note:** RC4 is cryptographically broken; use only for basic obfuscation:
 //!
 //! ```rust
@@ -111,6 +156,26 @@
 //! }
 //! ```
 //!
+//! ### `ByteSlice` Mode
+//!
+//! For binary data passed to APIs that take `&[u8]`. Returns `&[u8]` on dereference:
+//!
+//! ```rust
+//! use const_secret::{
+//!     ByteSlice, Encrypted,
+//!     drop_strategy::Zeroize,
+//!     xor::Xor,
+//! };
+//!
+//! const HMAC_KEY: Encrypted<Xor<0xBB, Zeroize>, ByteSlice, 4> =
+//!     Encrypted::<Xor<0xBB, Zeroize>, ByteSlice, 4>::new([0xDE, 0xAD, 0xBE, 0xEF]);
+//!
+//! fn main() {
+//!     let key: &[u8] = &*HMAC_KEY;
+//!     assert_eq!(key, &[0xDE, 0xAD, 0xBE, 0xEF]);
+//! }
+//! ```
+//!
 //! ## Choosing an Algorithm
 //!
 //! | Algorithm | Speed | Security | Use Case |
@@ -123,6 +188,8 @@
 //! | Strategy   | Behavior on Drop | Best For |
 //! |------------|------------------|----------|
 //! | `Zeroize`  | Overwrites with zeros | Maximum security |
+//! | `RandomFill` | Overwrites with noise | Avoiding conspicuous runs of zeros |
+//! | `RngFill`  | Overwrites with random bytes | Residue with no structure for scanners |
 //! | `ReEncrypt`| Re-encrypts data | If you prefer the residue to remain encrypted after using |
 //! | `NoOp`     | Leaves unchanged | Performance critical, non-sensitive |
 //!
@@ -132,7 +199,8 @@
 //! - [`Algorithm`]: Trait defining encryption algorithm and associated data
 //! - [`Encrypted<A, M, N>`]: Main struct holding encrypted data
 //! - [`DropStrategy`]: Trait for handling drop behavior
-//! - Mode markers: [`StringLiteral`] and [`ByteArray`]
+//! - Mode markers: [`StringLiteral`], [`ByteArray`], [`ByteSlice`] and [`CStrLiteral`]
+//! - [`SecretField`]: Trait for reading any string secret, whatever its algorithm and length
 
 #![no_std]
 #![cfg_attr(not(debug_assertions), deny(warnings))]
@@ -199,24 +267,104 @@
     rust_2018_idioms
 )]
 
-#[cfg(test)]
+#[cfg(any(test, feature = "std"))]
 extern crate std;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "alloc"))]
 extern crate alloc;
 
+// Lets the tests expand `macros::secret!`, which refers to `::const_secret`.
+#[cfg(all(test, feature = "macros"))]
+extern crate self as const_secret;
+
+#[macro_use]
+pub mod macros;
+
+pub mod add_shift;
+pub mod aes;
 pub mod align;
+pub mod array;
+#[cfg(feature = "alloc")]
+pub mod boxed;
+#[cfg(feature = "build-helpers")]
+pub mod build_helpers;
+pub mod builder;
+pub mod chacha20;
 pub mod drop_strategy;
+pub mod guard;
+#[cfg(any(feature = "slog", feature = "tracing", feature = "debug-logging"))]
+pub mod logging;
+pub mod presets;
 pub mod rc4;
+pub mod read_only;
+pub mod salsa20;
+#[cfg(feature = "serde")]
+pub mod serde_support;
+pub mod speck;
+pub mod spin;
 pub mod xor;
+pub mod xor_prng;
+
+#[cfg(feature = "secrecy")]
+pub use secrecy;
 
-use crate::drop_strategy::DropStrategy;
-use core::{cell::UnsafeCell, fmt, marker::PhantomData, sync::atomic::AtomicU8};
+use crate::{
+    drop_strategy::{DropStrategy, Residue},
+    spin::Backoff,
+};
+use core::{
+    cell::UnsafeCell,
+    ffi::CStr,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::{Deref, Index},
+    slice::SliceIndex,
+    sync::atomic::{AtomicU8, Ordering},
+};
 
 /// Decryption state constants for thread-safe lazy decryption
 pub(crate) const STATE_UNENCRYPTED: u8 = 0;
 pub(crate) const STATE_DECRYPTING: u8 = 1;
 pub(crate) const STATE_DECRYPTED: u8 = 2;
+pub(crate) const STATE_WIPED: u8 = 3;
+pub(crate) const STATE_POISONED: u8 = 4;
+pub(crate) const STATE_KEY_MISSING: u8 = 5;
+
+/// The lifecycle state of an [`Encrypted`], as returned by [`Encrypted::state`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DecryptionState {
+    /// The buffer holds ciphertext ("sealed"). Every secret without a deferred key
+    /// starts here.
+    Unencrypted,
+    /// A thread is decrypting the buffer. Transient.
+    Decrypting,
+    /// The buffer holds plaintext.
+    Decrypted,
+    /// The buffer was wiped, by [`Encrypted::zeroize_now`] or a scrubbing
+    /// [`reset`](Encrypted::reset).
+    Wiped,
+    /// Decryption panicked partway through, so the buffer holds neither ciphertext nor
+    /// plaintext.
+    Poisoned,
+    /// The key was deferred and has not been set yet.
+    KeyMissing,
+}
+
+impl DecryptionState {
+    /// Maps one of the internal state constants to the public enum.
+    const fn from_raw(state: u8) -> Self {
+        match state {
+            STATE_UNENCRYPTED => Self::Unencrypted,
+            STATE_DECRYPTING => Self::Decrypting,
+            STATE_DECRYPTED => Self::Decrypted,
+            STATE_WIPED => Self::Wiped,
+            STATE_KEY_MISSING => Self::KeyMissing,
+            _ => Self::Poisoned,
+        }
+    }
+}
 
 /// A trait that defines an encryption algorithm and its associated types.
 ///
@@ -234,6 +382,104 @@ pub trait Algorithm {
     ///
     /// For XOR this is `()` (no extra data needed), for RC4 this is the key array.
     type Extra;
+
+    /// Encrypts `data` in place at runtime.
+    ///
+    /// This must produce the same ciphertext as the algorithm's `const fn new`
+    /// constructor, so that buffers sealed at runtime decrypt like compile-time ones.
+    fn encrypt(data: &mut [u8], extra: &Self::Extra);
+
+    /// Decrypts `data` in place at runtime.
+    ///
+    /// Defaults to [`Algorithm::encrypt`], which is correct for every algorithm that
+    /// XORs a keystream into the buffer.
+    fn decrypt(data: &mut [u8], extra: &Self::Extra) {
+        Self::encrypt(data, extra);
+    }
+
+    /// Panics if `extra` is not a usable key, e.g. one that leaves the plaintext as it
+    /// is.
+    ///
+    /// Runs the checks the algorithm's constructors apply, for keys supplied at
+    /// runtime by [`Encrypted::rotate_key`] and [`Encrypted::set_key`]. Defaults to
+    /// accepting every key.
+    fn check_extra(_extra: &Self::Extra) {}
+}
+
+/// An algorithm that can decrypt a secret one byte at a time, front to back.
+///
+/// Used by [`Encrypted::decrypted_bytes`], which never holds more than one plaintext
+/// byte. Implemented by the XOR and RC4 algorithms.
+pub trait StreamDecrypt: Algorithm {
+    /// Keystream state carried from one byte to the next, such as the RC4 S-box.
+    ///
+    /// It is a byte array so that it can be wiped when decryption stops.
+    type State: AsMut<[u8]>;
+
+    /// Returns the state for decrypting the first byte.
+    fn start(extra: &Self::Extra) -> Self::State;
+
+    /// Decrypts `byte`, the ciphertext at `index`.
+    ///
+    /// Called with `index` 0, 1, 2, ... in order, each exactly once.
+    fn decrypt_byte(state: &mut Self::State, index: usize, byte: u8) -> u8;
+
+    /// Advances a fresh state past the first `count` bytes, as if they were decrypted.
+    ///
+    /// Defaults to running the keystream `count` times. Algorithms whose keystream
+    /// depends only on the index override it with a no-op.
+    fn skip(state: &mut Self::State, count: usize) {
+        for index in 0..count {
+            Self::decrypt_byte(state, index, 0);
+        }
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// How the plaintext of an [`Encrypted`] is presented once decrypted.
+///
+/// Implemented by the mode markers [`StringLiteral`], [`ByteArray`], [`ByteSlice`] and
+/// [`CStrLiteral`].
+/// The trait is sealed; it exists so that generic code can accept secrets of any mode.
+///
+/// Generic code can branch on [`UTF8`](Mode::UTF8) and
+/// [`NUL_TERMINATED`](Mode::NUL_TERMINATED) to treat string and byte secrets
+/// differently:
+///
+/// ```rust
+/// use const_secret::{ByteArray, Encrypted, Mode, StringLiteral, xor::Xor};
+///
+/// fn describe<M: Mode, const N: usize>(_: &Encrypted<Xor<0xAA>, M, N>) -> &'static str {
+///     if M::UTF8 { "text" } else { "bytes" }
+/// }
+///
+/// assert_eq!(describe(&Encrypted::<Xor<0xAA>, StringLiteral, 2>::new(*b"hi")), "text");
+/// assert_eq!(describe(&Encrypted::<Xor<0xAA>, ByteArray, 2>::new([1, 2])), "bytes");
+/// ```
+pub trait Mode: sealed::Sealed {
+    /// The type a secret of `N` bytes dereferences to.
+    type Target<const N: usize>: ?Sized;
+
+    /// Whether the plaintext must be a C string: a single NUL byte, at the end.
+    ///
+    /// `true` for [`CStrLiteral`]. Checked by every algorithm's constructor.
+    const NUL_TERMINATED: bool = false;
+
+    /// Whether the plaintext must be valid UTF-8.
+    ///
+    /// `true` for [`StringLiteral`]. Checked by every algorithm's constructor.
+    const UTF8: bool = false;
+
+    /// Reinterprets decrypted plaintext as [`Mode::Target`].
+    ///
+    /// # Safety
+    ///
+    /// `bytes` must be the decrypted plaintext of a secret constructed in this mode.
+    #[doc(hidden)]
+    unsafe fn view<const N: usize>(bytes: &[u8; N]) -> &Self::Target<N>;
 }
 
 /// Mode marker type indicating the encrypted data should be treated as a UTF-8 string literal.
@@ -241,10 +487,23 @@ pub trait Algorithm {
 /// When used as the `M` type parameter of [`Encrypted<A, M, N>`], dereferencing
 /// returns `&str` instead of `&[u8; N]`.
 ///
-/// # Safety
+/// The plaintext must be valid UTF-8. Constructors check this, so a `const` secret
+/// that violates it fails to compile:
+///
+/// ```compile_fail
+/// use const_secret::{Encrypted, StringLiteral, drop_strategy::Zeroize, xor::Xor};
+///
+/// const INVALID: Encrypted<Xor<0xAA, Zeroize>, StringLiteral, 2> =
+///     Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 2>::new([0xC3, 0x28]);
+/// # let _ = &*INVALID;
+/// ```
 ///
-/// The original plaintext must be valid UTF-8. The encryption algorithm must
-/// preserve the byte values such that decryption produces valid UTF-8.
+/// Dereferencing relies on this check without repeating it. Decryption only restores
+/// the original bytes under the right key, so every path that takes ciphertext or a
+/// key at runtime checks the plaintext too: [`Encrypted::set_key`],
+/// [`XorRuntime::from_ciphertext`](crate::xor::XorRuntime), the
+/// [`builder`] and deserialization all reject input that does not
+/// decrypt to valid UTF-8. [`Encrypted::try_as_str`] validates again on every call.
 pub struct StringLiteral;
 
 /// Mode marker type indicating the encrypted data should be treated as a byte array.
@@ -253,6 +512,200 @@ pub struct StringLiteral;
 /// returns `&[u8; N]` (a reference to the raw byte array).
 pub struct ByteArray;
 
+/// Mode marker type indicating the encrypted data should be treated as a byte slice.
+///
+/// When used as the `M` type parameter of [`Encrypted<A, M, N>`], dereferencing
+/// returns `&[u8]`, which is what most byte-consuming APIs (hashers, writers) take.
+pub struct ByteSlice;
+
+/// Mode marker type indicating the encrypted data should be treated as a C string.
+///
+/// When used as the `M` type parameter of [`Encrypted<A, M, N>`], dereferencing
+/// returns `&CStr`, whose pointer can be passed straight to C without copying the
+/// plaintext into a `CString`.
+///
+/// The plaintext must end with a NUL byte and contain no other. Constructors check
+/// this, so a `const` secret that violates it fails to compile:
+///
+/// ```compile_fail
+/// use const_secret::{CStrLiteral, Encrypted, drop_strategy::Zeroize, xor::Xor};
+///
+/// const MISSING_NUL: Encrypted<Xor<0xAA, Zeroize>, CStrLiteral, 5> =
+///     Encrypted::<Xor<0xAA, Zeroize>, CStrLiteral, 5>::new(*b"hello");
+/// # let _ = &*MISSING_NUL;
+/// ```
+pub struct CStrLiteral;
+
+impl sealed::Sealed for StringLiteral {}
+impl sealed::Sealed for ByteArray {}
+impl sealed::Sealed for ByteSlice {}
+impl sealed::Sealed for CStrLiteral {}
+
+impl Mode for StringLiteral {
+    type Target<const N: usize> = str;
+
+    const UTF8: bool = true;
+
+    unsafe fn view<const N: usize>(bytes: &[u8; N]) -> &str {
+        // SAFETY: the caller passes the decrypted plaintext of a `StringLiteral`
+        // secret. Const constructors check that plaintext is UTF-8, and every runtime
+        // path that supplies ciphertext or a key (`set_key`, `from_ciphertext`, the
+        // builder, deserialization) checks the plaintext it decrypts to before the
+        // secret becomes readable, so the key in use always yields these bytes.
+        unsafe { core::str::from_utf8_unchecked(bytes) }
+    }
+}
+
+impl Mode for ByteArray {
+    type Target<const N: usize> = [u8; N];
+
+    unsafe fn view<const N: usize>(bytes: &[u8; N]) -> &[u8; N] {
+        bytes
+    }
+}
+
+impl Mode for CStrLiteral {
+    type Target<const N: usize> = CStr;
+
+    const NUL_TERMINATED: bool = true;
+
+    unsafe fn view<const N: usize>(bytes: &[u8; N]) -> &CStr {
+        // A decryption that does not restore the terminator would let C read past the
+        // buffer. Checking the last byte is cheap, so do not rely on the algorithm alone.
+        assert!(bytes.last() == Some(&0), "C string plaintext lost its NUL terminator");
+        // SAFETY: `CStrLiteral` secrets are checked on construction to end with their
+        // only NUL byte, every algorithm decrypts back to exactly the original bytes, and
+        // the terminator was confirmed above.
+        unsafe { CStr::from_bytes_with_nul_unchecked(bytes) }
+    }
+}
+
+/// Panics unless `plaintext` is valid for mode `M`.
+///
+/// Called by every constructor, so in a `const` initializer the check happens at
+/// compile time. An empty plaintext is almost always a forgotten literal, so `N == 0`
+/// fails the build outright; [`Encrypted::EMPTY`] is the explicit way to get one.
+pub(crate) const fn check_plaintext<M: Mode, const N: usize>(plaintext: &[u8; N]) {
+    const {
+        assert!(
+            N > 0,
+            "empty secret: `N` must be greater than 0; use `Encrypted::EMPTY` on purpose"
+        );
+    };
+    if M::UTF8 {
+        assert!(core::str::from_utf8(plaintext).is_ok(), "string plaintext must be valid UTF-8");
+    }
+    if M::NUL_TERMINATED {
+        assert!(is_c_string(plaintext), "C string plaintext must end with its only NUL byte");
+    }
+}
+
+/// Returns `true` if `plaintext` is valid for mode `M`, i.e. if [`Mode::view`] may be
+/// called on it.
+///
+/// For plaintext that did not come from a constructor, such as ciphertext read back
+/// from storage and decrypted.
+pub(crate) const fn is_valid_plaintext<M: Mode>(plaintext: &[u8]) -> bool {
+    (!M::UTF8 || core::str::from_utf8(plaintext).is_ok())
+        && (!M::NUL_TERMINATED || is_c_string(plaintext))
+}
+
+/// Returns `true` if the only NUL byte in `bytes` is the last one.
+const fn is_c_string(bytes: &[u8]) -> bool {
+    let Some((&0, rest)) = bytes.split_last() else {
+        return false;
+    };
+    let mut i = 0;
+    while i < rest.len() {
+        if rest[i] == 0 {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Items used by exported macros. Not public API.
+#[doc(hidden)]
+pub mod __private {
+    /// `decode_hex`, for [`key_from_env!`](crate::key_from_env!).
+    pub const fn decode_hex<const N: usize>(hex: &str) -> [u8; N] {
+        crate::decode_hex(hex)
+    }
+}
+
+/// Passes a literal on to a constructor, failing the build with a clear message if its
+/// length is not the `N` the secret declares.
+///
+/// Constructors take `[u8; N]`, so a literal of the wrong length is already rejected,
+/// but as a bare type mismatch. Wrapping the literal reports it as
+/// `secret literal length does not match N`, with both lengths in the failing
+/// `literal::<L, N>` instance. The check runs at compile time even outside a `const`.
+/// To not write `N` at all, use [`secret!`] instead.
+///
+/// ```rust
+/// use const_secret::{Encrypted, StringLiteral, drop_strategy::Zeroize, literal, xor::Xor};
+///
+/// const SECRET: Encrypted<Xor<0xAA, Zeroize>, StringLiteral, 5> =
+///     Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(literal(*b"hello"));
+/// assert_eq!(&*SECRET, "hello");
+/// ```
+///
+/// ```compile_fail
+/// use const_secret::{Encrypted, StringLiteral, drop_strategy::Zeroize, literal, xor::Xor};
+///
+/// // error: secret literal length does not match N (in `literal::<6, 5>`)
+/// const SECRET: Encrypted<Xor<0xAA, Zeroize>, StringLiteral, 5> =
+///     Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(literal(*b"hello!"));
+/// ```
+#[must_use]
+pub const fn literal<const L: usize, const N: usize>(bytes: [u8; L]) -> [u8; N] {
+    const { assert!(L == N, "secret literal length does not match N") };
+
+    let mut out = [0u8; N];
+    let mut i = 0;
+    while i < N {
+        out[i] = bytes[i];
+        i += 1;
+    }
+    out
+}
+
+/// Decodes a hex string of exactly `2 * N` digits into bytes.
+///
+/// Accepts upper- and lowercase digits. Panics (at compile time in a `const`
+/// initializer) on a wrong length or a non-hex character.
+pub(crate) const fn decode_hex<const N: usize>(hex: &str) -> [u8; N] {
+    let hex = hex.as_bytes();
+    assert!(hex.len() == 2 * N, "hex secret must have exactly two digits per byte");
+
+    let mut out = [0u8; N];
+    let mut i = 0;
+    while i < N {
+        out[i] = (hex_digit(hex[2 * i]) << 4) | hex_digit(hex[2 * i + 1]);
+        i += 1;
+    }
+    out
+}
+
+/// The value of one hex digit.
+const fn hex_digit(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        b'a'..=b'f' => digit - b'a' + 10,
+        b'A'..=b'F' => digit - b'A' + 10,
+        _ => panic!("hex secret contains a non-hex digit"),
+    }
+}
+
+impl Mode for ByteSlice {
+    type Target<const N: usize> = [u8];
+
+    unsafe fn view<const N: usize>(bytes: &[u8; N]) -> &[u8] {
+        bytes
+    }
+}
+
 /// An encrypted container that holds data encrypted at compile time.
 ///
 /// This struct stores encrypted data that is decrypted on first access via
@@ -262,7 +715,8 @@ pub struct ByteArray;
 /// # Type Parameters
 ///
 /// - `A`: The encryption algorithm type implementing [`Algorithm`]
-/// - `M`: The mode marker type ([`StringLiteral`] or [`ByteArray`])
+/// - `M`: The mode marker type ([`StringLiteral`], [`ByteArray`], [`ByteSlice`] or
+///   [`CStrLiteral`])
 /// - `N`: The size of the encrypted buffer in bytes
 ///
 /// # Thread Safety
@@ -271,6 +725,61 @@ pub struct ByteArray;
 /// The first thread to access the data performs the decryption; subsequent
 /// accesses read the already-decrypted buffer.
 ///
+/// # Lifecycle
+///
+/// ```text
+///   KeyMissing
+///     │ set_key
+///     ▼      first deref              decrypt finishes
+///   Sealed ───────────────▶ Decrypting ─────────────────▶ Decrypted
+///     │                         │                             │
+///     │                         │ decrypt panics              │
+///     │                         ▼                             │
+///     │                     Poisoned                          │
+///     │                                                       │
+///     └──────────────────── zeroize_now ──────────────────────┴──▶ Wiped
+/// ```
+///
+/// - **`KeyMissing`**: the buffer holds ciphertext, but the secret was created with a
+///   deferred key (e.g. [`Rc4`](crate::rc4::Rc4)'s `new_with_deferred_key`) and
+///   [`set_key`](Self::set_key) has not been called yet.
+/// - **Sealed**: the buffer holds ciphertext.
+/// - **Decrypting**: one thread holds the lock to decrypt (or to take a
+///   [`with_decrypted`](Self::with_decrypted) snapshot); concurrent readers wait for it.
+/// - **Decrypted**: the buffer holds plaintext and every dereference reads it.
+/// - **Wiped**: [`zeroize_now`](Self::zeroize_now) zeroized the buffer. Terminal.
+/// - **Poisoned**: [`Algorithm::decrypt`] panicked partway through, so the buffer
+///   holds neither ciphertext nor plaintext. Terminal.
+///
+/// [`state`](Self::state) reports these as a [`DecryptionState`].
+///
+/// Dereferencing a wiped or poisoned secret panics (with `"secret was wiped"` or
+/// `"secret was poisoned"`) rather than returning bytes that could be mistaken for
+/// a real value. So does dereferencing a secret whose key is missing (with
+/// `"secret key was not set"`). Use [`try_deref`](Self::try_deref) to get `None` instead, or check
+/// [`is_wiped`](Self::is_wiped) first.
+///
+/// # Memory Layout
+///
+/// `Encrypted` is `#[repr(C)]`, so it can be embedded in `#[repr(C)]` structs shared
+/// with C. It is laid out as this C struct, where `Extra` is the algorithm's extra
+/// data (nothing for XOR, the key bytes for RC4):
+///
+/// ```c
+/// struct encrypted {
+///     uint8_t buffer[N]; /* ciphertext, or plaintext once decrypted */
+///     uint8_t state;     /* 0 sealed, 1 decrypting, 2 decrypted, 3 wiped, ... */
+///     Extra extra;
+/// };
+/// ```
+///
+/// C may read `buffer` and `state` while no Rust code is using the secret; `state`
+/// says whether `buffer` holds ciphertext or plaintext. C must not write to any field
+/// while Rust holds a reference, since Rust decrypts in place and treats `state` as
+/// an atomic lock. `extra` has a C layout only when `A::Extra` does, e.g. `()` or a
+/// byte array. [`EncryptedC`] names the type for FFI code and
+/// [`from_c`](Self::from_c) borrows a secret from a C pointer.
+///
 /// # Drop Behavior
 ///
 /// When dropped, the data is handled according to the algorithm's
@@ -297,17 +806,22 @@ pub struct ByteArray;
 ///     assert_eq!(decrypted, "hello");
 /// }
 /// ```
+#[repr(C)]
 pub struct Encrypted<A: Algorithm, M, const N: usize> {
     /// The encrypted/decrypted data buffer.
     ///
     /// Uses [`UnsafeCell`] for interior mutability to allow decryption on first access.
     buffer: UnsafeCell<[u8; N]>,
-    /// State of decryption (0=unencrypted, 1=decrypting, 2=decrypted).
+    /// State of decryption (0=unencrypted, 1=decrypting, 2=decrypted, 3=wiped, 4=poisoned,
+    /// 5=key missing).
     ///
     /// Uses atomic operations to ensure thread-safe lazy decryption.
     /// - `STATE_UNENCRYPTED` (0): Initial state, needs decryption
     /// - `STATE_DECRYPTING` (1): A thread is currently decrypting
     /// - `STATE_DECRYPTED` (2): Decryption complete, safe to read
+    /// - `STATE_WIPED` (3): Buffer zeroized by [`Encrypted::zeroize_now`], never readable again
+    /// - `STATE_POISONED` (4): Decryption panicked, never readable again
+    /// - `STATE_KEY_MISSING` (5): Needs [`Encrypted::set_key`] before it can be decrypted
     decryption_state: AtomicU8,
     /// Algorithm-specific extra data (e.g., the encryption key for RC4).
     extra: A::Extra,
@@ -315,43 +829,2506 @@ pub struct Encrypted<A: Algorithm, M, const N: usize> {
     _phantom: PhantomData<(A, M)>,
 }
 
+/// An [`Encrypted`] secret shared with C code.
+///
+/// [`Encrypted`] is already `#[repr(C)]`; this name marks FFI signatures and structs
+/// that rely on its [memory layout](Encrypted#memory-layout).
+pub type EncryptedC<A, M, const N: usize> = Encrypted<A, M, N>;
+
 impl<A: Algorithm, M, const N: usize> fmt::Debug for Encrypted<A, M, N> {
     /// Formats the `Encrypted` struct for debugging.
     ///
-    /// Note that the actual buffer contents are not displayed for security reasons.
-    /// Only the `decryption_state` is shown. The output uses `finish_non_exhaustive()`
-    /// to indicate there are additional fields not shown.
+    /// Shows the algorithm, the mode, the lifecycle state and the length, e.g.
+    /// `Encrypted { algorithm: const_secret::xor::Xor<..>, mode: StringLiteral,
+    /// state: sealed, buffer: [REDACTED; 5 bytes], .. }`. The buffer contents and the
+    /// algorithm's extra data (such as a key) are never shown, whatever the state, and
+    /// formatting never triggers decryption. Nor are the algorithm's generic parameters,
+    /// since algorithms such as [`xor::Xor`] keep their key there.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let algorithm = core::any::type_name::<A>();
+        let (algorithm, generics) = match algorithm.split_once('<') {
+            Some((path, _)) => (path, "<..>"),
+            None => (algorithm, ""),
+        };
+        let mode = core::any::type_name::<M>();
+        let mode = mode.rsplit("::").next().unwrap_or(mode);
+        let state = match self.state() {
+            DecryptionState::Unencrypted => "sealed",
+            DecryptionState::Decrypting => "decrypting",
+            DecryptionState::Decrypted => "decrypted",
+            DecryptionState::Wiped => "wiped",
+            DecryptionState::KeyMissing => "key missing",
+            DecryptionState::Poisoned => "poisoned",
+        };
+
         f.debug_struct("Encrypted")
-            .field("decryption_state", &self.decryption_state)
+            .field("algorithm", &format_args!("{algorithm}{generics}"))
+            .field("mode", &format_args!("{mode}"))
+            .field("state", &format_args!("{state}"))
+            .field("buffer", &format_args!("[REDACTED; {N} bytes]"))
             .finish_non_exhaustive()
     }
 }
 
+impl<A: Algorithm, const N: usize> fmt::Display for Encrypted<A, StringLiteral, N> {
+    /// Decrypts (as dereferencing does) and formats the plaintext.
+    ///
+    /// Unlike [`Debug`](fmt::Debug), this prints the secret itself. It is meant for
+    /// places that must emit the value, such as building a header, not for logging.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret was wiped or poisoned.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+/// A string secret of any algorithm and length.
+///
+/// Lets generic code, such as a config struct's accessors, handle string secrets
+/// without naming their full [`Encrypted`] type:
+///
+/// ```rust
+/// use const_secret::{
+///     Encrypted, SecretField, StringLiteral, drop_strategy::Zeroize, rc4::Rc4, xor::Xor,
+/// };
+///
+/// fn header(token: &impl SecretField) -> String {
+///     format!("Bearer {}", token.reveal())
+/// }
+///
+/// let xor = Encrypted::<Xor<0xAA>, StringLiteral, 3>::new(*b"abc");
+/// let rc4 = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, StringLiteral, 4>::new(*b"wxyz", *b"mykey");
+/// assert_eq!(header(&xor), "Bearer abc");
+/// assert_eq!(header(&rc4), "Bearer wxyz");
+/// ```
+pub trait SecretField {
+    /// Decrypts on first access, as dereferencing does, and returns the plaintext.
+    fn reveal(&self) -> &str;
+}
+
+impl<A: Algorithm, const N: usize> SecretField for Encrypted<A, StringLiteral, N> {
+    /// # Panics
+    ///
+    /// Panics if the secret was wiped or poisoned.
+    fn reveal(&self) -> &str {
+        self
+    }
+}
+
+impl<A: Algorithm, M, const N: usize> Clone for Encrypted<A, M, N>
+where
+    A::Extra: Clone,
+{
+    /// Returns a sealed copy, even if `self` has already been decrypted.
+    ///
+    /// The copy holds ciphertext and decrypts on its own first access. A decrypted
+    /// buffer is encrypted again into the copy, so cloning never duplicates plaintext.
+    /// Cloning a wiped or poisoned secret yields a wiped one, and cloning a secret
+    /// whose key is missing yields one that still needs [`Encrypted::set_key`].
+    fn clone(&self) -> Self {
+        let (buffer, state) = self.sealed_copy();
+
+        Encrypted {
+            buffer: UnsafeCell::new(buffer),
+            decryption_state: AtomicU8::new(state),
+            extra: self.extra.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<A: Algorithm, M, const N: usize> PartialEq for Encrypted<A, M, N>
+where
+    A::Extra: PartialEq,
+{
+    /// Compares the ciphertext and [`Algorithm::Extra`] (e.g. the key), without
+    /// decrypting either secret.
+    ///
+    /// This compares the encrypted representation, not the plaintext: two secrets
+    /// with the same plaintext under different keys are not equal. A decrypted secret
+    /// is compared by its ciphertext, so decrypting does not change the result. To
+    /// compare plaintexts instead, use [`ct_eq_secret`](Encrypted::ct_eq_secret) or,
+    /// with the `subtle` feature, `ConstantTimeEq` on [`ByteArray`] secrets; these can
+    /// disagree with `==` on the same pair.
+    ///
+    /// A wiped or poisoned secret has no ciphertext left. It equals another wiped or
+    /// poisoned secret with the same `Extra`, including itself, so that `Eq` stays
+    /// reflexive, and never equals a readable secret. A secret still waiting for its
+    /// key never equals one whose key was set.
+    fn eq(&self, other: &Self) -> bool {
+        let (ours, our_state) = self.sealed_copy();
+        let (theirs, their_state) = other.sealed_copy();
+        our_state == their_state && ours == theirs && self.extra == other.extra
+    }
+}
+
 impl<A: Algorithm, M, const N: usize> Drop for Encrypted<A, M, N> {
     /// Handles the encrypted data when the struct is dropped.
     ///
     /// Applies the algorithm's [`DropStrategy`]
     /// to the buffer. This may zeroize, re-encrypt, or leave the data unchanged
     /// depending on the configured strategy.
+    ///
+    /// A secret that was already wiped with [`Encrypted::zeroize_now`], or whose key was
+    /// never set, holds nothing worth protecting, so the strategy is skipped. A buffer
+    /// that still holds ciphertext gets [`DropStrategy::drop_sealed`], which skips
+    /// re-encryption, since that would decrypt it. A poisoned secret may hold partial
+    /// plaintext that no strategy can restore, so it is always zeroized.
     fn drop(&mut self) {
+        match *self.decryption_state.get_mut() {
+            STATE_WIPED | STATE_KEY_MISSING => return,
+            STATE_UNENCRYPTED => {
+                A::Drop::drop_sealed(self.buffer.get_mut(), &self.extra);
+                return;
+            }
+            STATE_POISONED => {
+                wipe(self.buffer.get_mut());
+                return;
+            }
+            _ => {}
+        }
         // SAFETY: `buffer` is initialized and exclusively borrowed through `&mut self`.
         let data_ref = unsafe { &mut *self.buffer.get() };
         A::Drop::drop(data_ref, &self.extra);
     }
 }
 
-// SAFETY: `Encrypted` is `Sync` because:
-// 1. The 3-state `decryption_state` (AtomicU8) ensures proper synchronization:
-//    - Only one thread can transition from UNENCRYPTED to DECRYPTING
-//    - Other threads spin-wait until state becomes DECRYPTED
-// 2. The thread that wins the race gets exclusive mutable access during decryption
-// 3. After decryption completes (state = DECRYPTED), the buffer is immutable
-// 4. Multiple threads can safely read the stable, decrypted buffer concurrently
-unsafe impl<A: Algorithm, M, const N: usize> Sync for Encrypted<A, M, N>
-where
-    A: Sync,
-    A::Extra: Sync,
-    M: Sync,
-{
+impl<A: Algorithm, M, const N: usize> Encrypted<A, M, N> {
+    /// Decrypts the buffer on first access and returns the plaintext.
+    ///
+    /// Returns the state (`STATE_WIPED`, `STATE_POISONED` or `STATE_KEY_MISSING`) if the
+    /// buffer cannot be read.
+    fn try_decrypted(&self) -> Result<&[u8; N], u8> {
+        // Fast path: already decrypted
+        if self.decryption_state.load(Ordering::Acquire) == STATE_DECRYPTED {
+            // SAFETY: `buffer` is initialized and lives as long as `self`.
+            return Ok(unsafe { &*self.buffer.get() });
+        }
+
+        let mut backoff = Backoff::new();
+        loop {
+            // Try to acquire the decryption lock by transitioning from UNENCRYPTED to DECRYPTING
+            match self.decryption_state.compare_exchange(
+                STATE_UNENCRYPTED,
+                STATE_DECRYPTING,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => {
+                    // If the algorithm panics, waiting threads must not spin forever.
+                    let mut guard = DecryptGuard {
+                        state: &self.decryption_state,
+                        finished: false,
+                    };
+
+                    // SAFETY: `buffer` is always initialized and points to valid `[u8; N]`.
+                    // We won the race, perform decryption with exclusive mutable access.
+                    let data = unsafe { &mut *self.buffer.get() };
+                    A::decrypt(data, &self.extra);
+
+                    // Decryption complete - the guard releases the lock by transitioning
+                    // to DECRYPTED when it goes out of scope
+                    guard.finished = true;
+                    break;
+                }
+                // Lost the race - another thread holds the lock. Spin (and eventually
+                // block or yield) until it is released, then retry: a `with_decrypted` snapshot releases it back to
+                // UNENCRYPTED rather than DECRYPTED
+                Err(STATE_DECRYPTING) => backoff.snooze(&self.decryption_state),
+                Err(STATE_DECRYPTED) => break,
+                Err(unreadable) => return Err(unreadable),
+            }
+        }
+
+        // SAFETY: `buffer` is initialized and lives as long as `self`.
+        // Decryption is complete (either by us or another thread), so it's safe
+        // to return a shared reference.
+        Ok(unsafe { &*self.buffer.get() })
+    }
+
+    /// Like [`try_decrypted`](Self::try_decrypted), but panics on an unreadable state.
+    fn decrypted(&self) -> &[u8; N] {
+        match self.try_decrypted() {
+            Ok(bytes) => bytes,
+            Err(unreadable) => unreadable_state_panic(unreadable),
+        }
+    }
+
+    /// Copies the plaintext into a scratch buffer without decrypting in place.
+    ///
+    /// If the secret has not been decrypted yet, the ciphertext is copied while holding
+    /// the decryption lock, and the copy is decrypted after the lock is released.
+    fn try_snapshot(&self) -> Result<Scratch<N>, u8> {
+        let mut backoff = Backoff::new();
+        loop {
+            match self.decryption_state.compare_exchange(
+                STATE_UNENCRYPTED,
+                STATE_DECRYPTING,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => {
+                    // SAFETY: we hold the lock, so no other thread is writing the buffer.
+                    let mut scratch = Scratch(unsafe { *self.buffer.get() });
+                    spin::unlock(&self.decryption_state, STATE_UNENCRYPTED);
+                    A::decrypt(&mut scratch.0, &self.extra);
+                    return Ok(scratch);
+                }
+                Err(STATE_DECRYPTED) => {
+                    // SAFETY: a decrypted buffer is only ever read through `&self`.
+                    return Ok(Scratch(unsafe { *self.buffer.get() }));
+                }
+                Err(STATE_DECRYPTING) => backoff.snooze(&self.decryption_state),
+                Err(unreadable) => return Err(unreadable),
+            }
+        }
+    }
+
+    /// Copies the ciphertext without decrypting, along with the state a copy starts in.
+    ///
+    /// A decrypted buffer is encrypted again into the copy. A wiped or poisoned secret
+    /// has no ciphertext left, so it yields zeros and `STATE_WIPED`.
+    fn sealed_copy(&self) -> ([u8; N], u8) {
+        let mut backoff = Backoff::new();
+        loop {
+            match self.decryption_state.compare_exchange(
+                STATE_UNENCRYPTED,
+                STATE_DECRYPTING,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => {
+                    // SAFETY: we hold the lock, so no other thread is writing the buffer.
+                    let ciphertext = unsafe { *self.buffer.get() };
+                    spin::unlock(&self.decryption_state, STATE_UNENCRYPTED);
+                    return (ciphertext, STATE_UNENCRYPTED);
+                }
+                Err(STATE_DECRYPTED) => {
+                    // SAFETY: a decrypted buffer is only ever read through `&self`.
+                    // The scratch is wiped if `encrypt` unwinds with plaintext in it.
+                    let mut scratch = Scratch(unsafe { *self.buffer.get() });
+                    A::encrypt(&mut scratch.0, &self.extra);
+                    return (scratch.0, STATE_UNENCRYPTED);
+                }
+                Err(STATE_KEY_MISSING) => {
+                    // SAFETY: `set_key` needs `&mut self`, so a buffer waiting for its key
+                    // is never written while `&self` is alive.
+                    return (unsafe { *self.buffer.get() }, STATE_KEY_MISSING);
+                }
+                Err(STATE_DECRYPTING) => backoff.snooze(&self.decryption_state),
+                Err(_) => return ([0; N], STATE_WIPED),
+            }
+        }
+    }
+
+    /// Returns a copy of the ciphertext, without decrypting.
+    ///
+    /// Useful for tests, audits, and persisting a secret. The result is always the
+    /// ciphertext: if the secret has already been decrypted, its plaintext is
+    /// encrypted again into the copy rather than handed out. A wiped or poisoned
+    /// secret has no ciphertext left and returns zeros.
+    pub fn encrypted_bytes(&self) -> [u8; N] {
+        self.sealed_copy().0
+    }
+
+    /// Returns a copy of the ciphertext as stored, or `None` once the secret has been
+    /// decrypted in place.
+    ///
+    /// Unlike [`encrypted_bytes`](Self::encrypted_bytes), this never encrypts anything:
+    /// `Some` is exactly the at-rest representation, such as the bytes a `const`
+    /// initializer placed in the binary. A secret whose key is missing is still at
+    /// rest and returns `Some`; a decrypted, wiped or poisoned one returns `None`.
+    ///
+    /// This returns a copy rather than a reference because a reference would let the
+    /// next dereference decrypt the buffer underneath it.
+    pub fn ciphertext(&self) -> Option<[u8; N]> {
+        let mut backoff = Backoff::new();
+        loop {
+            match self.decryption_state.compare_exchange(
+                STATE_UNENCRYPTED,
+                STATE_DECRYPTING,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => {
+                    // SAFETY: we hold the lock, so no other thread is writing the buffer.
+                    let ciphertext = unsafe { *self.buffer.get() };
+                    spin::unlock(&self.decryption_state, STATE_UNENCRYPTED);
+                    return Some(ciphertext);
+                }
+                Err(STATE_KEY_MISSING) => {
+                    // SAFETY: `set_key` needs `&mut self`, so a buffer waiting for its key
+                    // is never written while `&self` is alive.
+                    return Some(unsafe { *self.buffer.get() });
+                }
+                Err(STATE_DECRYPTING) => backoff.snooze(&self.decryption_state),
+                Err(_) => return None,
+            }
+        }
+    }
+
+    /// Compares the plaintext with `other` in constant time.
+    ///
+    /// Use this instead of `&*secret == other` to check an incoming token against a
+    /// secret: the comparison does not exit early on the first differing byte, and the
+    /// plaintext is decrypted into a scratch buffer that is zeroized afterwards, so the
+    /// secret itself stays sealed.
+    ///
+    /// A length mismatch returns `false`. Only the lengths leak through timing, never
+    /// the contents.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret was wiped or poisoned.
+    pub fn ct_eq(&self, other: &[u8]) -> bool {
+        let scratch = match self.try_snapshot() {
+            Ok(scratch) => scratch,
+            Err(unreadable) => unreadable_state_panic(unreadable),
+        };
+
+        let mut diff = 0u8;
+        for (i, byte) in scratch.0.iter().enumerate() {
+            diff |= byte ^ other.get(i).copied().unwrap_or(0);
+        }
+        // Keep the optimizer from turning the accumulation into an early exit.
+        let diff = core::hint::black_box(diff);
+        (diff == 0) & (other.len() == N)
+    }
+
+    /// Compares the plaintext with that of another secret of the same length, in
+    /// constant time.
+    ///
+    /// The two secrets may use different algorithms and modes: only their plaintext
+    /// bytes are compared. Both are decrypted into scratch buffers that are zeroized
+    /// afterwards, so neither is decrypted in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either secret was wiped or poisoned.
+    pub fn ct_eq_secret<A2: Algorithm, M2>(&self, other: &Encrypted<A2, M2, N>) -> bool {
+        let other = match other.try_snapshot() {
+            Ok(scratch) => scratch,
+            Err(unreadable) => unreadable_state_panic(unreadable),
+        };
+        self.ct_eq(&other.0)
+    }
+
+    /// Decrypts the secret (as dereferencing does) and returns a pointer to the first
+    /// plaintext byte, for passing to C as a `const uint8_t *`.
+    ///
+    /// The pointer covers [`len`](Self::len) bytes. It is valid only while the
+    /// plaintext stays in the buffer: it dangles once `self` is dropped or moved, and
+    /// it points at ciphertext or zeros after [`reset`](Self::reset),
+    /// [`zeroize_now`](Self::zeroize_now) or a [`guard`](Self::guard) ends. Since those
+    /// need `&mut self`, holding on to `&self` for as long as the pointer is used keeps
+    /// it valid. The plaintext must not be written through the pointer.
+    ///
+    /// For a [`CStrLiteral`] secret this points at the whole C string, including its
+    /// terminator; `(*secret).as_ptr()` gives the same pointer as `*const c_char`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret was wiped or poisoned.
+    #[must_use]
+    pub fn as_ptr(&self) -> *const u8 {
+        self.decrypted().as_ptr()
+    }
+
+    /// Decrypts the secret (as dereferencing does) and returns the byte at `index`.
+    ///
+    /// Works for every mode, e.g. to read a version prefix of a string secret. To read
+    /// one byte while the rest stays sealed, use [`get_byte`](Self::get_byte) instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= N`, or if the secret was wiped or poisoned.
+    #[must_use]
+    pub fn byte_at(&self, index: usize) -> u8 {
+        self.decrypted()[index]
+    }
+
+    /// Borrows a secret that C code holds a pointer to.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be non-null, aligned, and point to a secret that stays alive and is
+    /// not written to by C for `'a`. The secret must have been created by this crate
+    /// with the same `A`, `M` and `N`, or hold the same bytes as one that was: a state
+    /// byte set by C to anything else makes every later read undefined behavior.
+    #[must_use]
+    pub const unsafe fn from_c<'a>(raw: *const Self) -> &'a Self {
+        // SAFETY: the caller guarantees that `raw` points to a valid, live secret.
+        unsafe { &*raw }
+    }
+
+    /// Decrypts the secret (as dereferencing does) and copies the plaintext into `dst`.
+    ///
+    /// Copies `min(N, dst.len())` bytes from the start of the plaintext and returns
+    /// how many were copied. This copies plaintext into memory the caller controls:
+    /// the caller is responsible for zeroizing `dst` once it is done with it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret was wiped or poisoned.
+    pub fn copy_decrypted_to(&self, dst: &mut [u8]) -> usize {
+        let plain = self.decrypted();
+        let len = N.min(dst.len());
+        dst[..len].copy_from_slice(&plain[..len]);
+        len
+    }
+
+    /// Decrypts the secret (as dereferencing does) and returns a copy of the plaintext.
+    ///
+    /// Meant for small secrets. The copy is owned by the caller, who is responsible for
+    /// zeroizing it once it is done with it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret was wiped or poisoned.
+    #[must_use]
+    pub fn copy_decrypted_to_array(&self) -> [u8; N] {
+        *self.decrypted()
+    }
+
+    /// Returns the length of the secret in bytes, without decrypting it.
+    ///
+    /// This is always `N` and never touches the buffer, so it is safe to call on a
+    /// sealed secret, for example to pre-allocate an output buffer. For a
+    /// [`StringLiteral`] secret it shadows `str::len`, with the same result.
+    ///
+    /// Being a `const fn`, it can size arrays from a `static` secret:
+    ///
+    /// ```rust
+    /// use const_secret::{Encrypted, StringLiteral, drop_strategy::Zeroize, xor::Xor};
+    ///
+    /// static TOKEN: Encrypted<Xor<0xAA, Zeroize>, StringLiteral, 5> =
+    ///     Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+    ///
+    /// let mut out = [0u8; TOKEN.len()];
+    /// out.copy_from_slice(TOKEN.as_bytes());
+    /// assert_eq!(&out, b"hello");
+    /// ```
+    #[inline]
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Returns `true` if the secret is zero bytes long, without decrypting it.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Decrypts into a new `Vec`, leaving the stored buffer untouched.
+    ///
+    /// Like [`with_decrypted`](Self::with_decrypted), this does not cache plaintext in
+    /// the secret: the intermediate stack copy is zeroized, and a sealed secret stays
+    /// sealed. The returned `Vec` is owned by the caller, who is responsible for
+    /// zeroizing it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret was wiped or poisoned.
+    #[cfg(feature = "alloc")]
+    pub fn to_decrypted_vec(&self) -> alloc::vec::Vec<u8> {
+        match self.try_snapshot() {
+            Ok(scratch) => scratch.0.to_vec(),
+            Err(unreadable) => unreadable_state_panic(unreadable),
+        }
+    }
+
+    /// Returns the current lifecycle state.
+    ///
+    /// Like the `is_*` methods, this never triggers decryption. The state can change
+    /// as soon as it is read if other threads share the secret.
+    #[inline]
+    pub fn state(&self) -> DecryptionState {
+        DecryptionState::from_raw(self.decryption_state.load(Ordering::Acquire))
+    }
+
+    /// Returns `true` if the buffer currently holds decrypted plaintext.
+    ///
+    /// Unlike dereferencing, this never triggers decryption, so it can be used to log
+    /// lazy initialization or to assert that a code path did not decrypt.
+    #[inline]
+    pub fn is_decrypted(&self) -> bool {
+        self.decryption_state.load(Ordering::Acquire) == STATE_DECRYPTED
+    }
+
+    /// Returns `true` while another thread is in the middle of decrypting the buffer.
+    ///
+    /// The buffer is in transition between ciphertext and plaintext, so it is readable
+    /// as neither. This is a transient state, intended for diagnostics only.
+    #[inline]
+    #[doc(alias = "is_decrypting")]
+    pub fn is_encrypting(&self) -> bool {
+        self.decryption_state.load(Ordering::Acquire) == STATE_DECRYPTING
+    }
+
+    /// Returns `true` if the secret was wiped with [`zeroize_now`](Self::zeroize_now).
+    #[inline]
+    pub fn is_wiped(&self) -> bool {
+        self.decryption_state.load(Ordering::Acquire) == STATE_WIPED
+    }
+
+    /// Zeroizes the buffer immediately instead of waiting for drop.
+    ///
+    /// The secret moves to a terminal wiped state: any later dereference panics with
+    /// `"secret was wiped"` rather than handing out zeros that could pass for a real
+    /// value, and the [`DropStrategy`] no longer runs on drop.
+    ///
+    /// This takes `&mut self` because references returned by an earlier dereference
+    /// may still be alive while other threads share `&self`. Exclusive access is the
+    /// only way to guarantee that no reader observes the buffer being overwritten.
+    pub fn zeroize_now(&mut self) {
+        wipe(self.buffer.get_mut());
+        *self.decryption_state.get_mut() = STATE_WIPED;
+    }
+
+    /// Re-encrypts the secret under a new key (or other [`Algorithm::Extra`]).
+    ///
+    /// Meant for secrets with a `ReEncrypt` drop strategy, whose residue would
+    /// otherwise be the same well-known ciphertext on every run: rotate to a key drawn
+    /// at startup and memory scanners can no longer look for that pattern. Only
+    /// algorithms that keep their key in `Extra` can rotate it, e.g.
+    /// [`Rc4`](crate::rc4::Rc4) or [`XorN`](crate::xor::XorN); [`Xor`](crate::xor::Xor)'s
+    /// key is part of its type.
+    ///
+    /// A decrypted secret is encrypted straight under the new key; a sealed one is
+    /// decrypted with the old key first. Either way the secret ends up sealed. This
+    /// takes `&mut self` so no reader can observe the buffer or key mid-rotation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret was wiped or poisoned, or its key was never set, or if
+    /// the algorithm rejects the new key (see [`Algorithm::check_extra`]), in which
+    /// case the secret is left as it was. If the algorithm itself panics while
+    /// re-encrypting, the secret is poisoned.
+    pub fn rotate_key(&mut self, extra: A::Extra) {
+        let state = *self.decryption_state.get_mut();
+        if state != STATE_UNENCRYPTED && state != STATE_DECRYPTED {
+            unreadable_state_panic(state);
+        }
+        A::check_extra(&extra);
+
+        // Neither ciphertext nor plaintext while rotating: poisoned if we unwind.
+        *self.decryption_state.get_mut() = STATE_POISONED;
+        let buffer = self.buffer.get_mut();
+        if state == STATE_UNENCRYPTED {
+            A::decrypt(buffer, &self.extra);
+        }
+        self.extra = extra;
+        A::encrypt(buffer, &self.extra);
+        *self.decryption_state.get_mut() = STATE_UNENCRYPTED;
+    }
+
+    /// Applies the [`DropStrategy`] now to end the plaintext window early.
+    ///
+    /// Useful for a long-lived secret held as a field: dereference it when needed,
+    /// then `reset` it. What happens next depends on the strategy's
+    /// [`RESIDUE`](DropStrategy::RESIDUE):
+    ///
+    /// - [`Residue::Ciphertext`] (e.g. `ReEncrypt`): the secret is sealed again and the
+    ///   next dereference decrypts it again.
+    /// - [`Residue::Scrubbed`] (e.g. `Zeroize`): the secret is wiped, exactly as with
+    ///   [`zeroize_now`](Self::zeroize_now). Treating the zeroized buffer as ciphertext
+    ///   would hand out garbage, or invalid UTF-8 for a [`StringLiteral`].
+    /// - [`Residue::Plaintext`] (e.g. `NoOp`): nothing changes.
+    ///
+    /// Secrets that are not decrypted are left as they are. Like
+    /// [`zeroize_now`](Self::zeroize_now), this takes `&mut self` so no reader can
+    /// still hold a reference into the buffer.
+    pub fn reset(&mut self) {
+        if *self.decryption_state.get_mut() != STATE_DECRYPTED {
+            return;
+        }
+
+        A::Drop::drop(self.buffer.get_mut(), &self.extra);
+        let state = match A::Drop::RESIDUE {
+            Residue::Ciphertext => STATE_UNENCRYPTED,
+            Residue::Scrubbed => STATE_WIPED,
+            Residue::Plaintext => STATE_DECRYPTED,
+        };
+        self.decryption_state.store(state, Ordering::Release);
+    }
+
+    /// Encrypts the cached plaintext again, so the secret is sealed but still usable.
+    ///
+    /// Meant for application code that keeps a secret for the whole program, such as
+    /// a `static` on an embedded device, and wants the plaintext out of memory between
+    /// uses: dereference, use the value, then `forget_plaintext`. The next dereference
+    /// decrypts again.
+    ///
+    /// Unlike [`reset`](Self::reset), this ignores the [`DropStrategy`] and always
+    /// restores the ciphertext with [`Algorithm::encrypt`]. A zeroizing strategy would
+    /// leave nothing to decrypt, and decrypting zeros would not give back the secret.
+    ///
+    /// Secrets that are not decrypted are left as they are.
+    ///
+    /// This takes `&mut self` rather than `&self`: a reader may still hold the `&str`
+    /// or `&[u8]` that dereferencing returned, and encrypting the buffer under it would
+    /// change memory behind a shared reference. The state machine only guards the
+    /// decryption itself, not references handed out afterwards.
+    #[doc(alias = "reencrypt_now")]
+    pub fn forget_plaintext(&mut self) {
+        if *self.decryption_state.get_mut() != STATE_DECRYPTED {
+            return;
+        }
+
+        // Neither ciphertext nor plaintext while encrypting: poisoned if we unwind.
+        *self.decryption_state.get_mut() = STATE_POISONED;
+        A::encrypt(self.buffer.get_mut(), &self.extra);
+        *self.decryption_state.get_mut() = STATE_UNENCRYPTED;
+    }
+}
+
+impl<A: Algorithm, M: Mode, const N: usize> Encrypted<A, M, N> {
+    /// Supplies the key of a secret created with a deferred key.
+    ///
+    /// Until this is called, the key is not stored anywhere in the secret (or the
+    /// binary), and dereferencing panics with `"secret key was not set"`. The key must
+    /// be the one the secret was encrypted with. For [`StringLiteral`] and
+    /// [`CStrLiteral`] secrets, a scratch copy is decrypted with `extra` first and a key
+    /// that does not yield a valid plaintext for the mode is rejected, so a wrong key
+    /// can never produce a `&str` that is not UTF-8. For byte modes, a different key
+    /// decrypts to garbage.
+    ///
+    /// This takes `&mut self` so the key is never written while another thread reads
+    /// it: set the key before sharing the secret.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret was not created with a deferred key, if its key was
+    /// already set, if the algorithm rejects it (see [`Algorithm::check_extra`]), or
+    /// if `extra` does not decrypt it to a valid plaintext for the mode. A rejected key leaves the secret waiting for its key.
+    pub fn set_key(&mut self, extra: A::Extra) {
+        assert!(
+            *self.decryption_state.get_mut() == STATE_KEY_MISSING,
+            "secret key was already set"
+        );
+        A::check_extra(&extra);
+        if M::UTF8 || M::NUL_TERMINATED {
+            let mut scratch = Scratch(*self.buffer.get_mut());
+            A::decrypt(&mut scratch.0, &extra);
+            assert!(
+                is_valid_plaintext::<M>(&scratch.0),
+                "key does not decrypt the secret to a valid plaintext for its mode"
+            );
+        }
+        self.extra = extra;
+        *self.decryption_state.get_mut() = STATE_UNENCRYPTED;
+    }
+
+    /// Checked version of dereferencing.
+    ///
+    /// Returns `None` instead of panicking if the secret is wiped, poisoned, or still
+    /// waiting for its key.
+    pub fn try_deref(&self) -> Option<&M::Target<N>> {
+        // SAFETY: `try_decrypted` only returns fully decrypted plaintext.
+        self.try_decrypted().ok().map(|bytes| unsafe { M::view(bytes) })
+    }
+
+    /// Runs `f` on a temporary plaintext copy, leaving the stored buffer untouched.
+    ///
+    /// `f` receives the same type dereferencing yields: `&str` for [`StringLiteral`]
+    /// and `&[u8; N]` for [`ByteArray`].
+    ///
+    /// Unlike dereferencing, this does not cache plaintext in the secret: a sealed
+    /// secret stays sealed, and the temporary copy lives on the stack and is zeroized
+    /// when `f` returns (or unwinds). Use it for secrets that are read rarely but live
+    /// long, such as a `static`. If the secret was already decrypted by dereferencing,
+    /// the cached plaintext is copied and stays cached; call [`reset`](Self::reset) to
+    /// apply the drop strategy to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret was wiped or poisoned.
+    pub fn with_decrypted<R>(&self, f: impl FnOnce(&M::Target<N>) -> R) -> R {
+        match self.try_snapshot() {
+            // SAFETY: `try_snapshot` only returns fully decrypted plaintext.
+            Ok(scratch) => f(unsafe { M::view(&scratch.0) }),
+            Err(unreadable) => unreadable_state_panic(unreadable),
+        }
+    }
+
+    /// Decrypts the secret if needed and lets `f` modify the plaintext bytes in place.
+    ///
+    /// Useful for normalizing a value once, e.g. upper-casing an API key. Afterwards,
+    /// if the drop strategy's [`RESIDUE`](DropStrategy::RESIDUE) is
+    /// [`Residue::Ciphertext`] (e.g. `ReEncrypt`), the modified plaintext is encrypted
+    /// again and the secret is sealed. Otherwise (e.g. `Zeroize`) the modified
+    /// plaintext stays decrypted until [`reset`](Self::reset) or drop.
+    ///
+    /// Modifying the bytes can break what the mode guarantees, such as UTF-8 for
+    /// [`StringLiteral`] or a single trailing NUL for [`CStrLiteral`]. The plaintext is
+    /// checked after `f` returns; if it is no longer valid, the secret is wiped and
+    /// poisoned and this panics, rather than later handing out an invalid `&str`.
+    ///
+    /// ```rust
+    /// use const_secret::{Encrypted, StringLiteral, xor::{ReEncrypt, Xor}};
+    ///
+    /// let mut key = Encrypted::<Xor<0xAA, ReEncrypt<0xAA>>, StringLiteral, 6>::new(*b"abc123");
+    /// key.with_decrypted_mut(|bytes| bytes.make_ascii_uppercase());
+    /// assert!(!key.is_decrypted());
+    /// assert_eq!(&*key, "ABC123");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret was wiped or poisoned or its key was never set, or if `f`
+    /// leaves plaintext that is invalid for the mode. If `f` panics, the secret is
+    /// poisoned.
+    pub fn with_decrypted_mut<R, F>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut [u8]) -> R,
+    {
+        let state = *self.decryption_state.get_mut();
+        if state != STATE_UNENCRYPTED && state != STATE_DECRYPTED {
+            unreadable_state_panic(state);
+        }
+
+        // The plaintext is only trusted again once it has been checked: poisoned if
+        // decryption or `f` unwinds.
+        *self.decryption_state.get_mut() = STATE_POISONED;
+        let buffer = self.buffer.get_mut();
+        if state == STATE_UNENCRYPTED {
+            A::decrypt(buffer, &self.extra);
+        }
+        let result = f(buffer);
+        if !is_valid_plaintext::<M>(buffer) {
+            wipe(buffer);
+            panic!("plaintext modified by with_decrypted_mut is not valid for the mode");
+        }
+
+        *self.decryption_state.get_mut() = if A::Drop::RESIDUE == Residue::Ciphertext {
+            A::Drop::drop(buffer, &self.extra);
+            STATE_UNENCRYPTED
+        } else {
+            STATE_DECRYPTED
+        };
+        result
+    }
+
+    /// Returns the length of the secret in bytes, without decrypting it.
+    ///
+    /// This is always `N`: for [`StringLiteral`] secrets it is the UTF-8 byte length of
+    /// the string. Useful for length-prefixed framing in code that is generic over the
+    /// [`Mode`].
+    pub const fn sealed_byte_len(&self) -> usize {
+        N
+    }
+}
+
+impl<A: Algorithm<Extra = ()>, M: Mode> Encrypted<A, M, 0> {
+    /// An intentionally empty secret, for algorithms that store no key.
+    ///
+    /// Constructors reject `N == 0` at compile time, because an empty secret is
+    /// usually a forgotten literal:
+    ///
+    /// ```compile_fail
+    /// use const_secret::{ByteArray, Encrypted, drop_strategy::Zeroize, xor::Xor};
+    ///
+    /// let _ = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 0>::new([]);
+    /// ```
+    ///
+    /// Use this constant where an empty secret is meant:
+    ///
+    /// ```rust
+    /// use const_secret::{Encrypted, StringLiteral, drop_strategy::Zeroize, xor::Xor};
+    ///
+    /// static NO_PASSWORD: Encrypted<Xor<0xAA, Zeroize>, StringLiteral, 0> = Encrypted::EMPTY;
+    /// assert_eq!(&*NO_PASSWORD, "");
+    /// ```
+    ///
+    /// Modes that cannot be empty, such as [`CStrLiteral`], fail the build when it is used.
+    #[allow(clippy::declare_interior_mutable_const)]
+    pub const EMPTY: Self = {
+        assert!(is_valid_plaintext::<M>(&[]), "this mode has no empty plaintext");
+        Encrypted {
+            buffer: UnsafeCell::new([]),
+            decryption_state: AtomicU8::new(STATE_UNENCRYPTED),
+            extra: (),
+            _phantom: PhantomData,
+        }
+    };
+}
+
+impl<A: Algorithm, const N: usize> Encrypted<A, StringLiteral, N> {
+    /// Decrypts and returns the plaintext as a `&str`, validating it as UTF-8.
+    ///
+    /// Dereferencing trusts that the secret was constructed from valid UTF-8. This
+    /// method checks instead, so corrupted input (for example a secret sealed with the
+    /// wrong key, or from bytes that were never UTF-8) surfaces as an error in tests
+    /// rather than as undefined behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`Utf8Error`](core::str::Utf8Error) if the plaintext is not valid
+    /// UTF-8.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret was wiped or poisoned.
+    pub fn try_as_str(&self) -> Result<&str, core::str::Utf8Error> {
+        core::str::from_utf8(self.decrypted())
+    }
+
+    /// Decrypts the secret and returns a pointer to the string as a `c_char`, for C
+    /// APIs that take a pointer and a length.
+    ///
+    /// The string is **not** NUL-terminated, so it must not be passed where C expects
+    /// a `const char *` C string; use a [`CStrLiteral`] secret for that. The pointer
+    /// is valid as long as the one from [`as_ptr`](Self::as_ptr), which has the
+    /// details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret was wiped or poisoned.
+    #[must_use]
+    pub fn as_str_ptr(&self) -> *const core::ffi::c_char {
+        self.as_ptr().cast()
+    }
+}
+
+impl<A: Algorithm, M: Mode, const N: usize> Deref for Encrypted<A, M, N> {
+    type Target = M::Target<N>;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: `decrypted` only returns fully decrypted plaintext.
+        unsafe { M::view(self.decrypted()) }
+    }
+}
+
+/// Implements a byte-wise formatting trait for the byte modes.
+///
+/// The plaintext is decrypted as dereferencing does, then each byte is written with
+/// `$byte_fmt`. Like [`Display`](fmt::Display), this prints the secret itself.
+macro_rules! impl_byte_fmt {
+    ($($trait:ident => $byte_fmt:literal),*) => {$(
+        impl<A: Algorithm, const N: usize> fmt::$trait for Encrypted<A, ByteArray, N> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.iter().try_for_each(|byte| write!(f, $byte_fmt, byte))
+            }
+        }
+
+        impl<A: Algorithm, const N: usize> fmt::$trait for Encrypted<A, ByteSlice, N> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.iter().try_for_each(|byte| write!(f, $byte_fmt, byte))
+            }
+        }
+    )*};
+}
+
+impl_byte_fmt!(LowerHex => "{:02x}", UpperHex => "{:02X}", Binary => "{:08b}");
+
+/// Decrypts (as dereferencing does) and compares the plaintext.
+///
+/// The comparison exits on the first differing byte. Use [`Encrypted::ct_eq`] to check
+/// untrusted input against a secret.
+impl<A: Algorithm, const N: usize> PartialEq<str> for Encrypted<A, StringLiteral, N> {
+    fn eq(&self, other: &str) -> bool {
+        **self == *other
+    }
+}
+
+impl<A: Algorithm, const N: usize> PartialEq<&str> for Encrypted<A, StringLiteral, N> {
+    fn eq(&self, other: &&str) -> bool {
+        **self == **other
+    }
+}
+
+impl<A: Algorithm, const N: usize> PartialEq<Encrypted<A, StringLiteral, N>> for str {
+    fn eq(&self, other: &Encrypted<A, StringLiteral, N>) -> bool {
+        *self == **other
+    }
+}
+
+impl<A: Algorithm, const N: usize> PartialEq<Encrypted<A, StringLiteral, N>> for &str {
+    fn eq(&self, other: &Encrypted<A, StringLiteral, N>) -> bool {
+        **self == **other
+    }
+}
+
+/// Decrypts (as dereferencing does) and compares the plaintext.
+///
+/// The comparison exits on the first differing byte. Use [`Encrypted::ct_eq`] to check
+/// untrusted input against a secret.
+impl<A: Algorithm, const N: usize> PartialEq<[u8; N]> for Encrypted<A, ByteArray, N> {
+    fn eq(&self, other: &[u8; N]) -> bool {
+        **self == *other
+    }
+}
+
+impl<A: Algorithm, const N: usize> PartialEq<[u8]> for Encrypted<A, ByteArray, N> {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+/// Secrets compare by state, ciphertext and key (see the `PartialEq` impl), which is
+/// reflexive in every state: a secret poisoned through `&self` while it is a map key
+/// still equals itself and keeps its hash.
+impl<A: Algorithm, M, const N: usize> Eq for Encrypted<A, M, N> where A::Extra: Eq {}
+
+/// Hashes the ciphertext and [`Algorithm::Extra`] (e.g. the key), without decrypting,
+/// so secrets can be used as `HashMap` keys.
+///
+/// This agrees with the `PartialEq` impl: it hashes the encrypted representation, so
+/// the same plaintext under different keys hashes differently, and decrypting does
+/// not change the hash. The plaintext never reaches the hasher. To key a map by
+/// plaintext instead, hash the dereferenced value.
+///
+/// For the same reason, secrets do not implement [`Borrow`](core::borrow::Borrow) for
+/// their plaintext type: a `str` hashes differently from the secret that holds it, so
+/// a map keyed by secrets is looked up with another secret, not with the plaintext:
+///
+/// ```compile_fail
+/// use std::collections::HashMap;
+///
+/// use const_secret::{ByteArray, Encrypted, drop_strategy::Zeroize, rc4::Rc4};
+///
+/// type Token = Encrypted<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 4>;
+///
+/// let mut map = HashMap::new();
+/// map.insert(Token::new(*b"tok1", *b"mykey"), "admin");
+/// let _ = map.get(b"tok1");
+/// ```
+impl<A: Algorithm, M, const N: usize> Hash for Encrypted<A, M, N>
+where
+    A::Extra: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let (ciphertext, sealed_state) = self.sealed_copy();
+        sealed_state.hash(state);
+        ciphertext.hash(state);
+        self.extra.hash(state);
+    }
+}
+
+/// Decrypts (as dereferencing does) and returns the plaintext.
+impl<A: Algorithm, const N: usize> AsRef<str> for Encrypted<A, StringLiteral, N> {
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+/// Decrypts (as dereferencing does) and returns the UTF-8 bytes of the plaintext.
+impl<A: Algorithm, const N: usize> AsRef<[u8]> for Encrypted<A, StringLiteral, N> {
+    fn as_ref(&self) -> &[u8] {
+        str::as_bytes(self)
+    }
+}
+
+/// Decrypts (as dereferencing does) and returns the plaintext.
+impl<A: Algorithm, const N: usize> AsRef<[u8]> for Encrypted<A, ByteArray, N> {
+    fn as_ref(&self) -> &[u8] {
+        &**self
+    }
+}
+
+impl<A: Algorithm, const N: usize> AsRef<[u8; N]> for Encrypted<A, ByteArray, N> {
+    fn as_ref(&self) -> &[u8; N] {
+        self
+    }
+}
+
+/// Decrypts (as dereferencing does) and returns the plaintext.
+impl<A: Algorithm, const N: usize> AsRef<[u8]> for Encrypted<A, ByteSlice, N> {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+/// Decrypts (as dereferencing does) and copies the plaintext into a new `String`.
+///
+/// The copy lives on the heap, outside the secret: the secret's drop strategy scrubs
+/// its own buffer when it is consumed here, but never the returned `String`, which
+/// the caller must zeroize.
+///
+/// # Panics
+///
+/// Panics if the secret was wiped or poisoned.
+#[cfg(feature = "alloc")]
+impl<A: Algorithm, const N: usize> From<Encrypted<A, StringLiteral, N>> for alloc::string::String {
+    fn from(secret: Encrypted<A, StringLiteral, N>) -> Self {
+        Self::from(&*secret)
+    }
+}
+
+/// Decrypts (as dereferencing does) and copies the plaintext into a new `Vec`.
+///
+/// The copy lives on the heap, outside the secret: the secret's drop strategy scrubs
+/// its own buffer when it is consumed here, but never the returned `Vec`, which the
+/// caller must zeroize.
+///
+/// # Panics
+///
+/// Panics if the secret was wiped or poisoned.
+#[cfg(feature = "alloc")]
+impl<A: Algorithm, const N: usize> From<Encrypted<A, ByteArray, N>> for alloc::vec::Vec<u8> {
+    fn from(secret: Encrypted<A, ByteArray, N>) -> Self {
+        secret.to_vec()
+    }
+}
+
+impl<A: Algorithm, const N: usize> Encrypted<A, ByteArray, N> {
+    /// Returns an iterator over the plaintext bytes.
+    ///
+    /// Nothing is decrypted until the first call to `next`, which decrypts as
+    /// dereferencing does. `for byte in &secret` does the same.
+    pub fn bytes(&self) -> Bytes<'_, A, ByteArray, N> {
+        Bytes {
+            secret: self,
+            pos: 0,
+        }
+    }
+}
+
+impl<A: StreamDecrypt, M, const N: usize> Encrypted<A, M, N> {
+    /// Returns an iterator that decrypts the plaintext one byte at a time.
+    ///
+    /// Unlike [`bytes`](Encrypted::bytes) and dereferencing, the secret itself stays
+    /// sealed: the iterator copies the ciphertext and advances the keystream on each
+    /// call to `next`, so only the byte being yielded is ever plaintext. The keystream
+    /// state is wiped when the iterator is dropped. Useful for feeding a secret into
+    /// a hasher without a decrypted copy lying around.
+    ///
+    /// ```rust
+    /// use const_secret::{ByteArray, Encrypted, drop_strategy::Zeroize, rc4::Rc4};
+    ///
+    /// let secret = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 3>::new([1, 2, 3], *b"mykey");
+    /// let sum: u32 = secret.decrypted_bytes().map(u32::from).sum();
+    /// assert_eq!(sum, 6);
+    /// assert!(!secret.is_decrypted());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret was wiped or poisoned, or its key was never set.
+    pub fn decrypted_bytes(&self) -> DecryptedBytes<A, N> {
+        match self.sealed_copy() {
+            (ciphertext, STATE_UNENCRYPTED) => DecryptedBytes {
+                ciphertext,
+                state: A::start(&self.extra),
+                pos: 0,
+            },
+            (_, unreadable) => unreadable_state_panic(unreadable),
+        }
+    }
+
+    /// Decrypts the single byte at `index`, or returns `None` if it is out of bounds.
+    ///
+    /// The rest of the secret stays sealed: the byte is read from the ciphertext and
+    /// decrypted on its own, and a secret that was not decrypted yet is still not
+    /// decrypted afterwards. For XOR this is O(1); for RC4 the keystream is run up to
+    /// `index`. If the secret has already been decrypted, the cached byte is returned.
+    ///
+    /// ```rust
+    /// use const_secret::{ByteArray, Encrypted, drop_strategy::Zeroize, xor::Xor};
+    ///
+    /// let secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([2, 0xBE, 0xEF]);
+    /// assert_eq!(secret.get_byte(0), Some(2));
+    /// assert_eq!(secret.get_byte(3), None);
+    /// assert!(!secret.is_decrypted());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret was wiped or poisoned, or its key was never set.
+    pub fn get_byte(&self, index: usize) -> Option<u8> {
+        if index >= N {
+            return None;
+        }
+        let mut backoff = Backoff::new();
+        let ciphertext = loop {
+            match self.decryption_state.compare_exchange(
+                STATE_UNENCRYPTED,
+                STATE_DECRYPTING,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => {
+                    // SAFETY: we hold the lock, so no other thread is writing the buffer.
+                    let byte = unsafe { (*self.buffer.get())[index] };
+                    spin::unlock(&self.decryption_state, STATE_UNENCRYPTED);
+                    break byte;
+                }
+                Err(STATE_DECRYPTED) => {
+                    // SAFETY: a decrypted buffer is only ever read through `&self`.
+                    return Some(unsafe { (*self.buffer.get())[index] });
+                }
+                Err(STATE_DECRYPTING) => backoff.snooze(&self.decryption_state),
+                Err(unreadable) => unreadable_state_panic(unreadable),
+            }
+        };
+
+        let mut state = A::start(&self.extra);
+        A::skip(&mut state, index);
+        let byte = A::decrypt_byte(&mut state, index, ciphertext);
+        wipe(state.as_mut());
+        Some(byte)
+    }
+}
+
+impl<A: Algorithm, const N: usize> Encrypted<A, ByteArray, N> {
+    /// Decrypts (as dereferencing does) and returns the byte or bytes at `index`, or
+    /// `None` if it is out of bounds.
+    ///
+    /// Takes the same indices as `<[u8]>::get`: `secret.get(3)` is an `Option<&u8>`
+    /// and `secret.get(1..3)` an `Option<&[u8]>`.
+    pub fn get<I: SliceIndex<[u8]>>(&self, index: I) -> Option<&I::Output> {
+        self.decrypted().get(index)
+    }
+
+    /// Decrypts (as dereferencing does) and returns the plaintext as lowercase ASCII
+    /// hex digits, without allocating.
+    ///
+    /// `HEX_LEN` must equal `2 * N`, which is checked at compile time; it is usually
+    /// inferred from the type the result is assigned to. The returned array holds
+    /// the secret in plain form, so wipe it once it is no longer needed.
+    ///
+    /// ```rust
+    /// use const_secret::{ByteArray, Encrypted, drop_strategy::Zeroize, xor::Xor};
+    ///
+    /// let token = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 4>::new([0xde, 0xad, 0xbe, 0xef]);
+    /// let hex: [u8; 8] = token.encode_hex();
+    /// assert_eq!(&hex, b"deadbeef");
+    /// ```
+    #[must_use]
+    pub fn encode_hex<const HEX_LEN: usize>(&self) -> [u8; HEX_LEN] {
+        self.encode_hex_with(b"0123456789abcdef")
+    }
+
+    /// Like [`encode_hex`](Self::encode_hex), but with uppercase hex digits.
+    #[must_use]
+    pub fn encode_hex_upper<const HEX_LEN: usize>(&self) -> [u8; HEX_LEN] {
+        self.encode_hex_with(b"0123456789ABCDEF")
+    }
+
+    /// Hex-encodes the plaintext using `digits` for the values `0..16`.
+    fn encode_hex_with<const HEX_LEN: usize>(&self, digits: &[u8; 16]) -> [u8; HEX_LEN] {
+        const { assert!(HEX_LEN == 2 * N, "hex encoding needs exactly two digits per byte") };
+
+        let mut hex = [0u8; HEX_LEN];
+        for (pair, byte) in hex.chunks_exact_mut(2).zip(self.decrypted()) {
+            pair[0] = digits[usize::from(byte >> 4)];
+            pair[1] = digits[usize::from(byte & 0x0f)];
+        }
+        hex
+    }
+}
+
+/// Adds `as_<int>_le`/`as_<int>_be` to byte secrets exactly as long as `<int>`.
+macro_rules! impl_int_readers {
+    ($($int:ident: $len:literal => $le:ident, $be:ident;)*) => {$(
+        impl<A: Algorithm> Encrypted<A, ByteArray, $len> {
+            #[doc = concat!("Reads the plaintext as a little-endian `", stringify!($int), "`.")]
+            ///
+            /// Decrypts a copy into a stack scratch buffer, as
+            /// [`with_decrypted`](Self::with_decrypted) does, and zeroizes it once the
+            /// integer is assembled. The secret itself is not decrypted in place.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the secret was wiped or poisoned.
+            #[must_use]
+            pub fn $le(&self) -> $int {
+                self.with_decrypted(|bytes| $int::from_le_bytes(*bytes))
+            }
+
+            #[doc = concat!("Reads the plaintext as a big-endian `", stringify!($int), "`.")]
+            ///
+            #[doc = concat!("Like [`", stringify!($le), "`](Self::", stringify!($le), ").")]
+            ///
+            /// # Panics
+            ///
+            /// Panics if the secret was wiped or poisoned.
+            #[must_use]
+            pub fn $be(&self) -> $int {
+                self.with_decrypted(|bytes| $int::from_be_bytes(*bytes))
+            }
+        }
+    )*};
+}
+
+impl_int_readers! {
+    u16: 2 => as_u16_le, as_u16_be;
+    u32: 4 => as_u32_le, as_u32_be;
+    u64: 8 => as_u64_le, as_u64_be;
+}
+
+/// Decrypts (as dereferencing does) and indexes the plaintext like a slice:
+/// `secret[3]` is a `u8`, and `secret[1..4]` (or any other range) is a `[u8]`.
+///
+/// Panics if the index is out of bounds.
+impl<A: Algorithm, I: SliceIndex<[u8]>, const N: usize> Index<I> for Encrypted<A, ByteArray, N> {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &I::Output {
+        &self.decrypted()[index]
+    }
+}
+
+impl<'a, A: Algorithm, const N: usize> IntoIterator for &'a Encrypted<A, ByteArray, N> {
+    type Item = u8;
+    type IntoIter = Bytes<'a, A, ByteArray, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.bytes()
+    }
+}
+
+/// An iterator over the plaintext bytes of a secret, created by
+/// [`Encrypted::bytes`].
+///
+/// The secret is decrypted on the first call to `next`.
+pub struct Bytes<'a, A: Algorithm, M, const N: usize> {
+    /// The secret whose bytes are yielded.
+    secret: &'a Encrypted<A, M, N>,
+    /// The index of the next byte.
+    pos: usize,
+}
+
+impl<A: Algorithm, M, const N: usize> Iterator for Bytes<'_, A, M, N> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.pos == N {
+            return None;
+        }
+        let byte = self.secret.decrypted()[self.pos];
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = N - self.pos;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<A: Algorithm, M, const N: usize> ExactSizeIterator for Bytes<'_, A, M, N> {}
+
+impl<A: Algorithm, M, const N: usize> core::iter::FusedIterator for Bytes<'_, A, M, N> {}
+
+impl<A: Algorithm, M, const N: usize> fmt::Debug for Bytes<'_, A, M, N> {
+    /// Formats the position without revealing any bytes.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bytes").field("pos", &self.pos).field("len", &N).finish()
+    }
+}
+
+/// An iterator that decrypts a secret one byte at a time, created by
+/// [`Encrypted::decrypted_bytes`].
+///
+/// Holds a copy of the ciphertext and the keystream state, which is wiped on drop.
+pub struct DecryptedBytes<A: StreamDecrypt, const N: usize> {
+    /// The ciphertext being decrypted.
+    ciphertext: [u8; N],
+    /// The keystream state for the byte at `pos`.
+    state: A::State,
+    /// The index of the next byte.
+    pos: usize,
+}
+
+impl<A: StreamDecrypt, const N: usize> Iterator for DecryptedBytes<A, N> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let &byte = self.ciphertext.get(self.pos)?;
+        let byte = A::decrypt_byte(&mut self.state, self.pos, byte);
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = N - self.pos;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<A: StreamDecrypt, const N: usize> ExactSizeIterator for DecryptedBytes<A, N> {}
+
+impl<A: StreamDecrypt, const N: usize> core::iter::FusedIterator for DecryptedBytes<A, N> {}
+
+impl<A: StreamDecrypt, const N: usize> Drop for DecryptedBytes<A, N> {
+    fn drop(&mut self) {
+        wipe(self.state.as_mut());
+    }
+}
+
+impl<A: StreamDecrypt, const N: usize> fmt::Debug for DecryptedBytes<A, N> {
+    /// Formats the position without revealing any bytes or keystream state.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DecryptedBytes").field("pos", &self.pos).field("len", &N).finish()
+    }
+}
+
+/// Decrypts (as dereferencing does) and returns the plaintext: `str` for
+/// [`StringLiteral`], `[u8; N]` for [`ByteArray`], and so on.
+#[cfg(feature = "secrecy")]
+impl<A: Algorithm, M: Mode, const N: usize> secrecy::ExposeSecret<M::Target<N>>
+    for Encrypted<A, M, N>
+{
+    fn expose_secret(&self) -> &M::Target<N> {
+        self
+    }
+}
+
+/// Compares the plaintext of two byte secrets in constant time, as
+/// [`Encrypted::ct_eq_secret`] does.
+///
+/// This differs from `==`, which compares the ciphertext and key without decrypting:
+/// the same plaintext under two different keys is `ct_eq` but not `==`. Both secrets
+/// are decrypted into scratch copies for the comparison; neither is decrypted in
+/// place.
+///
+/// The inherent [`Encrypted::ct_eq`], which compares with a byte slice, takes
+/// precedence in method-call syntax. Call this one as
+/// `ConstantTimeEq::ct_eq(&a, &b)`.
+///
+/// # Panics
+///
+/// Panics if either secret was wiped or poisoned.
+#[cfg(feature = "subtle")]
+impl<A: Algorithm, const N: usize> subtle::ConstantTimeEq for Encrypted<A, ByteArray, N> {
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        let snapshot = |secret: &Self| match secret.try_snapshot() {
+            Ok(scratch) => scratch,
+            Err(unreadable) => unreadable_state_panic(unreadable),
+        };
+        let (ours, theirs) = (snapshot(self), snapshot(other));
+        subtle::ConstantTimeEq::ct_eq(&ours.0[..], &theirs.0[..])
+    }
+}
+
+// SAFETY: `Encrypted` is `Sync` because:
+// 1. The 3-state `decryption_state` (AtomicU8) ensures proper synchronization:
+//    - Only one thread can transition from UNENCRYPTED to DECRYPTING
+//    - Other threads spin-wait until state becomes DECRYPTED
+// 2. The thread that wins the race gets exclusive mutable access during decryption
+// 3. After decryption completes (state = DECRYPTED), the buffer is immutable
+// 4. Multiple threads can safely read the stable, decrypted buffer concurrently
+// 5. Wiping (`zeroize_now`), supplying a deferred key (`set_key`) and rotating the
+//    key (`rotate_key`) require `&mut self`, so they never race with readers
+unsafe impl<A: Algorithm, M, const N: usize> Sync for Encrypted<A, M, N>
+where
+    A: Sync,
+    A::Extra: Sync,
+    M: Sync,
+{
+}
+
+/// Panics with the message for a `decryption_state` that cannot be read.
+#[cold]
+#[track_caller]
+fn unreadable_state_panic(state: u8) -> ! {
+    match state {
+        STATE_WIPED => panic!("secret was wiped"),
+        STATE_KEY_MISSING => panic!("secret key was not set"),
+        _ => panic!("secret was poisoned"),
+    }
+}
+
+/// Releases the decryption lock, even if the algorithm unwinds.
+struct DecryptGuard<'a> {
+    /// The `decryption_state` of the secret being decrypted.
+    state: &'a AtomicU8,
+    /// Set once decryption returned normally.
+    finished: bool,
+}
+
+impl Drop for DecryptGuard<'_> {
+    /// Publishes `STATE_DECRYPTED`, or `STATE_POISONED` if decryption did not finish.
+    ///
+    /// Uses Release ordering to ensure all decryption writes are visible to other threads.
+    fn drop(&mut self) {
+        let state = if self.finished {
+            STATE_DECRYPTED
+        } else {
+            STATE_POISONED
+        };
+        spin::unlock(self.state, state);
+    }
+}
+
+/// Overwrites `data` with zeros in a way the compiler will not optimize out.
+///
+/// Uses the `zeroize` crate when the `zeroize` feature is enabled (the default).
+/// Otherwise falls back to volatile writes followed by a compiler fence, which is
+/// the same technique but without the crate's audit and platform-specific care.
+pub(crate) fn wipe(data: &mut [u8]) {
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(data);
+
+    #[cfg(not(feature = "zeroize"))]
+    wipe_volatile(data);
+}
+
+/// Overwrites `data` with zeros using volatile writes followed by a compiler fence.
+///
+/// Volatile writes cannot be elided as dead stores, and the fence keeps them from
+/// being reordered past whatever frees or reuses the memory next.
+pub(crate) fn wipe_volatile(data: &mut [u8]) {
+    for byte in data.iter_mut() {
+        // SAFETY: `byte` is a valid, aligned, exclusive reference.
+        unsafe { core::ptr::write_volatile(byte, 0) };
+    }
+    core::sync::atomic::compiler_fence(Ordering::SeqCst);
+}
+
+/// A stack buffer holding a temporary plaintext copy.
+///
+/// The contents are zeroized when the scratch goes out of scope, including when the
+/// code using it unwinds.
+pub(crate) struct Scratch<const N: usize>(pub(crate) [u8; N]);
+
+impl<const N: usize> Drop for Scratch<N> {
+    fn drop(&mut self) {
+        wipe(&mut self.0);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::explicit_auto_deref)]
+mod tests {
+    use super::*;
+    use crate::{
+        drop_strategy::{NoOp, Zeroize},
+        rc4::Rc4,
+        xor::{ReEncrypt, ReEncryptN, Xor, Xor2},
+    };
+
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+
+    /// An algorithm whose decryption always panics.
+    struct Panicking;
+
+    impl Algorithm for Panicking {
+        type Drop = Zeroize;
+        type Extra = ();
+
+        fn encrypt(_data: &mut [u8], _extra: &()) {}
+
+        fn decrypt(_data: &mut [u8], _extra: &()) {
+            panic!("decryption failed");
+        }
+    }
+
+    fn panicking_secret() -> Encrypted<Panicking, ByteArray, 4> {
+        Encrypted {
+            buffer: UnsafeCell::new([1, 2, 3, 4]),
+            decryption_state: AtomicU8::new(STATE_UNENCRYPTED),
+            extra: (),
+            _phantom: PhantomData,
+        }
+    }
+
+    #[test]
+    fn test_try_deref_before_and_after_wipe() {
+        let mut bytes = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([1, 2, 3]);
+        let mut string = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 2>::new(*b"hi");
+        assert_eq!(bytes.try_deref(), Some(&[1, 2, 3]));
+        assert_eq!(string.try_deref(), Some("hi"));
+        assert!(!bytes.is_wiped());
+
+        bytes.zeroize_now();
+        string.zeroize_now();
+        assert!(bytes.is_wiped());
+        assert!(string.is_wiped());
+        assert_eq!(bytes.try_deref(), None);
+        assert_eq!(string.try_deref(), None);
+    }
+
+    #[test]
+    fn test_is_decrypted_does_not_decrypt() {
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([1, 2, 3]);
+        assert!(!secret.is_decrypted());
+        assert!(!secret.is_encrypting());
+        assert!(!secret.is_decrypted(), "querying the state must not decrypt");
+
+        let _ = &*secret;
+        assert!(secret.is_decrypted());
+        assert!(!secret.is_encrypting());
+
+        secret.decryption_state.store(STATE_DECRYPTING, Ordering::Release);
+        assert!(secret.is_encrypting());
+        assert!(!secret.is_decrypted());
+        secret.decryption_state.store(STATE_DECRYPTED, Ordering::Release);
+    }
+
+    #[test]
+    fn test_with_decrypted_leaves_buffer_sealed() {
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        let sealed = unsafe { *secret.buffer.get() };
+
+        let len = secret.with_decrypted(|plain: &str| {
+            assert_eq!(plain, "hello");
+            plain.len()
+        });
+        assert_eq!(len, 5);
+        assert!(!secret.is_decrypted());
+        assert_eq!(unsafe { *secret.buffer.get() }, sealed);
+
+        // Works the same once the secret has been decrypted in place.
+        assert_eq!(&*secret, "hello");
+        secret.with_decrypted(|plain| assert_eq!(plain, "hello"));
+
+        let bytes = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([1, 2, 3]);
+        bytes.with_decrypted(|plain: &[u8; 3]| assert_eq!(plain, &[1, 2, 3]));
+    }
+
+    #[test]
+    #[should_panic(expected = "secret was wiped")]
+    fn test_with_decrypted_after_wipe_panics() {
+        let mut secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([1, 2, 3]);
+        secret.zeroize_now();
+        secret.with_decrypted(|_| ());
+    }
+
+    #[test]
+    fn test_reset_reencrypt_seals_again() {
+        let mut secret = Encrypted::<Xor<0xAA, ReEncrypt<0xAA>>, StringLiteral, 5>::new(*b"hello");
+        let sealed = unsafe { *secret.buffer.get() };
+        assert_eq!(&*secret, "hello");
+
+        secret.reset();
+        assert!(!secret.is_decrypted());
+        assert_eq!(unsafe { *secret.buffer.get() }, sealed);
+        assert_eq!(&*secret, "hello", "a re-encrypted secret decrypts again");
+
+        // Resetting a sealed secret must not encrypt it twice.
+        secret.reset();
+        secret.reset();
+        assert_eq!(unsafe { *secret.buffer.get() }, sealed);
+    }
+
+    #[test]
+    fn test_reset_zeroize_wipes() {
+        let mut secret = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        assert_eq!(&*secret, "hello");
+        secret.reset();
+        assert!(secret.is_wiped());
+        assert_eq!(unsafe { *secret.buffer.get() }, [0u8; 5]);
+        assert_eq!(secret.try_deref(), None);
+    }
+
+    #[test]
+    fn test_reset_noop_keeps_plaintext() {
+        let mut secret = Encrypted::<Xor<0xAA, NoOp>, ByteArray, 3>::new([1, 2, 3]);
+        assert_eq!(&*secret, &[1, 2, 3]);
+        secret.reset();
+        assert!(secret.is_decrypted());
+        assert_eq!(&*secret, &[1, 2, 3]);
+    }
+
+    #[test]
+    #[allow(clippy::declare_interior_mutable_const, clippy::borrow_interior_mutable_const)]
+    fn test_state_follows_lifecycle() {
+        const SECRET: Encrypted<Xor<0xAA, Zeroize>, StringLiteral, 5> =
+            Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+
+        let mut secret = SECRET;
+        assert_eq!(secret.state(), DecryptionState::Unencrypted);
+        let _ = &*secret;
+        assert_eq!(secret.state(), DecryptionState::Decrypted);
+        assert_eq!(SECRET.state(), DecryptionState::Unencrypted, "const copies start sealed");
+        secret.zeroize_now();
+        assert_eq!(secret.state(), DecryptionState::Wiped);
+
+        let deferred = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 1>::new_with_deferred_key(
+            [0],
+            *b"mykey",
+        );
+        assert_eq!(deferred.state(), DecryptionState::KeyMissing);
+    }
+
+    #[test]
+    fn test_with_decrypted_mut() {
+        let mut sealed = Encrypted::<Xor<0xAA, ReEncrypt<0xAA>>, ByteArray, 3>::new([1, 2, 3]);
+        let sum = sealed.with_decrypted_mut(|bytes| {
+            bytes.reverse();
+            bytes.iter().map(|&b| u32::from(b)).sum::<u32>()
+        });
+        assert_eq!(sum, 6);
+        assert!(!sealed.is_decrypted(), "a re-encrypting strategy seals it again");
+        assert_eq!(unsafe { *sealed.buffer.get() }, [3 ^ 0xAA, 2 ^ 0xAA, 1 ^ 0xAA]);
+        assert_eq!(&*sealed, &[3, 2, 1]);
+
+        let mut kept = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        kept.with_decrypted_mut(|bytes| bytes.make_ascii_uppercase());
+        assert!(kept.is_decrypted());
+        assert_eq!(&*kept, "HELLO");
+    }
+
+    #[test]
+    fn test_with_decrypted_mut_rejects_invalid_utf8() {
+        let mut secret = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 2>::new(*b"hi");
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            secret.with_decrypted_mut(|bytes| bytes[0] = 0xFF);
+        }));
+        assert!(result.is_err());
+        assert_eq!(secret.state(), DecryptionState::Poisoned);
+        assert_eq!(secret.try_deref(), None);
+        assert_eq!(unsafe { *secret.buffer.get() }, [0; 2]);
+    }
+
+    #[test]
+    fn test_forget_plaintext_seals_whatever_the_strategy() {
+        let mut secret = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        let sealed = unsafe { *secret.buffer.get() };
+        assert_eq!(&*secret, "hello");
+
+        secret.forget_plaintext();
+        assert!(!secret.is_decrypted());
+        assert!(!secret.is_wiped());
+        assert_eq!(unsafe { *secret.buffer.get() }, sealed);
+        assert_eq!(&*secret, "hello", "the secret decrypts again");
+
+        secret.forget_plaintext();
+        secret.forget_plaintext();
+        assert_eq!(unsafe { *secret.buffer.get() }, sealed, "a sealed secret is left alone");
+
+        let mut noop = Encrypted::<Xor<0xAA, NoOp>, ByteArray, 3>::new([1, 2, 3]);
+        assert_eq!(&*noop, &[1, 2, 3]);
+        noop.forget_plaintext();
+        assert!(!noop.is_decrypted());
+        assert_eq!(&*noop, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_reencrypt_drop_of_sealed_secret_is_skipped() {
+        use core::sync::atomic::AtomicUsize;
+
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        /// Counts calls instead of re-encrypting.
+        struct CountingReEncrypt;
+
+        impl DropStrategy for CountingReEncrypt {
+            type Extra = ();
+            const RESIDUE: Residue = Residue::Ciphertext;
+
+            fn drop(_data: &mut [u8], _extra: &()) {
+                CALLS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        drop(Encrypted::<Xor<0xAA, CountingReEncrypt>, ByteArray, 3>::new([1, 2, 3]));
+        assert_eq!(CALLS.load(Ordering::Relaxed), 0, "ciphertext must not be re-encrypted");
+
+        let secret = Encrypted::<Xor<0xAA, CountingReEncrypt>, ByteArray, 3>::new([1, 2, 3]);
+        let _ = &*secret;
+        drop(secret);
+        assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_try_as_str() {
+        let valid = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        assert_eq!(valid.try_as_str(), Ok("hello"));
+
+        // Constructors reject invalid UTF-8, so seal it by hand.
+        let invalid = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 2> {
+            buffer: UnsafeCell::new([0xC3 ^ 0xAA, 0x28 ^ 0xAA]),
+            decryption_state: AtomicU8::new(STATE_UNENCRYPTED),
+            extra: (),
+            _phantom: PhantomData,
+        };
+        let err = invalid.try_as_str().unwrap_err();
+        assert_eq!(err.valid_up_to(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "string plaintext must be valid UTF-8")]
+    fn test_string_new_rejects_invalid_utf8() {
+        let bytes = core::hint::black_box([0xC3, 0x28]);
+        let _ = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 2>::new(bytes);
+    }
+
+    #[test]
+    fn test_ct_eq() {
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 6>::new(*b"sk-123");
+        assert!(secret.ct_eq(b"sk-123"));
+        assert!(!secret.ct_eq(b"sk-124"));
+        assert!(!secret.ct_eq(b"xk-123"));
+        assert!(!secret.ct_eq(b"sk-12"), "shorter input");
+        assert!(!secret.ct_eq(b"sk-1234"), "longer input with matching prefix");
+        assert!(!secret.ct_eq(b""));
+        assert!(!secret.is_decrypted(), "comparison must not decrypt in place");
+
+        let empty = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 0>::EMPTY;
+        assert!(empty.ct_eq(&[]));
+        assert!(!empty.ct_eq(&[0]));
+    }
+
+    #[test]
+    fn test_ct_eq_secret() {
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 4>::new(*b"tokn");
+        let same =
+            Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, StringLiteral, 4>::new(*b"tokn", *b"mykey");
+        assert!(secret.ct_eq_secret(&same));
+        assert!(same.ct_eq_secret(&secret));
+
+        // Differences at either end must both be caught.
+        let first = Encrypted::<Xor<0x11, Zeroize>, ByteArray, 4>::new(*b"Tokn");
+        let last = Encrypted::<Xor<0x11, Zeroize>, ByteArray, 4>::new(*b"tokN");
+        assert!(!secret.ct_eq_secret(&first));
+        assert!(!secret.ct_eq_secret(&last));
+
+        assert!(!secret.is_decrypted() && !same.is_decrypted());
+    }
+
+    #[test]
+    #[cfg(feature = "subtle")]
+    fn test_subtle_constant_time_eq() {
+        use subtle::ConstantTimeEq;
+
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 4>::new(*b"tokn");
+        let same = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 4>::new(*b"tokn");
+        let other = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 4>::new(*b"tokN");
+        assert!(bool::from(ConstantTimeEq::ct_eq(&secret, &same)));
+        assert!(!bool::from(ConstantTimeEq::ct_eq(&secret, &other)));
+        assert!(!secret.is_decrypted());
+
+        // Plaintext comparison, unlike `==`, which compares ciphertext and key.
+        type Rc4Token = Encrypted<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 4>;
+        let a = Rc4Token::new(*b"tokn", *b"key-a");
+        let b = Rc4Token::new(*b"tokn", *b"key-b");
+        assert!(bool::from(ConstantTimeEq::ct_eq(&a, &b)));
+        assert!(a != b);
+    }
+
+    #[test]
+    fn test_as_ptr_points_at_plaintext() {
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        let ptr = secret.as_ptr();
+        assert!(secret.is_decrypted());
+        assert_eq!(ptr, secret.as_str_ptr().cast());
+        let plain = unsafe { core::slice::from_raw_parts(ptr, secret.len()) };
+        assert_eq!(plain, b"hello");
+
+        let cstr = Encrypted::<Xor<0xAA, Zeroize>, CStrLiteral, 3>::new(*b"ok\0");
+        let c_ptr = cstr.as_ptr();
+        assert_eq!(c_ptr.cast(), (*cstr).as_ptr());
+        assert_eq!(unsafe { CStr::from_ptr(c_ptr.cast()) }, c"ok");
+    }
+
+    #[test]
+    fn test_copy_decrypted_to() {
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+
+        let mut exact = [0u8; 5];
+        assert_eq!(secret.copy_decrypted_to(&mut exact), 5);
+        assert_eq!(&exact, b"hello");
+        assert!(secret.is_decrypted());
+
+        let mut again = [0u8; 5];
+        assert_eq!(secret.copy_decrypted_to(&mut again), 5);
+        assert_eq!(again, exact);
+
+        let mut short = [0u8; 3];
+        assert_eq!(secret.copy_decrypted_to(&mut short), 3);
+        assert_eq!(&short, b"hel");
+
+        let mut long = [0xFFu8; 8];
+        assert_eq!(secret.copy_decrypted_to(&mut long), 5);
+        assert_eq!(&long, b"hello\xFF\xFF\xFF");
+
+        assert_eq!(&secret.copy_decrypted_to_array(), b"hello");
+        assert_eq!(secret.copy_decrypted_to_array(), secret.copy_decrypted_to_array());
+    }
+
+    #[test]
+    fn test_literal_passes_matching_length_through() {
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new(literal(*b"abc"));
+        assert_eq!(&*secret, b"abc");
+        assert_eq!(literal::<0, 0>([]), [0u8; 0]);
+    }
+
+    #[test]
+    fn test_index() {
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 5>::new([10, 11, 12, 13, 14]);
+        assert_eq!(secret[3], 13);
+        assert!(secret.is_decrypted());
+        assert_eq!(&secret[1..4], &[11, 12, 13]);
+        assert_eq!(&secret[3..], &[13, 14]);
+
+        assert_eq!(secret.get(0), Some(&10));
+        assert_eq!(secret.get(5), None);
+        assert_eq!(secret.get(3..5), Some(&[13, 14][..]));
+        assert_eq!(secret.get(3..6), None);
+        assert_eq!(secret.get(..2), Some(&[10, 11][..]));
+
+        let result = catch_unwind(AssertUnwindSafe(|| secret[5]));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_bytes_iterator() {
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 4>::new([1, 2, 3, 4]);
+        let mut bytes = secret.bytes();
+        assert_eq!(bytes.len(), 4);
+        assert!(!secret.is_decrypted(), "creating the iterator must not decrypt");
+
+        assert_eq!(bytes.next(), Some(1));
+        assert!(secret.is_decrypted());
+        assert_eq!(bytes.len(), 3);
+        assert_eq!(bytes.collect::<std::vec::Vec<_>>(), [2, 3, 4]);
+
+        let mut sum = 0u32;
+        for byte in &secret {
+            sum += u32::from(byte);
+        }
+        assert_eq!(sum, 10);
+        assert_eq!(secret.bytes().max(), Some(4));
+    }
+
+    #[test]
+    fn test_decrypted_bytes_leaves_secret_sealed() {
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        let mut bytes = secret.decrypted_bytes();
+        assert_eq!(bytes.len(), 5);
+        assert_eq!(bytes.next(), Some(b'h'));
+        assert_eq!(bytes.collect::<std::vec::Vec<_>>(), b"ello");
+        assert!(!secret.is_decrypted());
+
+        assert_eq!(&*secret, "hello");
+        assert!(secret.decrypted_bytes().eq(*b"hello"), "a decrypted secret streams too");
+    }
+
+    #[test]
+    fn test_get_byte_leaves_secret_sealed() {
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([7, 8, 9]);
+        assert_eq!(secret.get_byte(0), Some(7));
+        assert_eq!(secret.get_byte(2), Some(9));
+        assert_eq!(secret.get_byte(3), None);
+        assert!(!secret.is_decrypted());
+        assert_eq!(secret.encrypted_bytes(), [7 ^ 0xAA, 8 ^ 0xAA, 9 ^ 0xAA]);
+
+        let _ = &*secret;
+        assert_eq!(secret.get_byte(1), Some(8));
+
+        let rc4 = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 300>::new(
+            core::array::from_fn(|i| i as u8),
+            *b"mykey",
+        );
+        assert_eq!(rc4.get_byte(0), Some(0));
+        assert_eq!(rc4.get_byte(299), Some(43));
+        assert!(!rc4.is_decrypted());
+    }
+
+    #[test]
+    #[should_panic(expected = "secret was wiped")]
+    fn test_decrypted_bytes_after_wipe_panics() {
+        let mut secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([1, 2, 3]);
+        secret.zeroize_now();
+        let _ = secret.decrypted_bytes();
+    }
+
+    #[test]
+    fn test_wipe_zeroes_bytes() {
+        let mut data = [0xFFu8; 33];
+        wipe(&mut data);
+        assert_eq!(data, [0u8; 33]);
+    }
+
+    #[test]
+    fn test_wipe_before_first_deref() {
+        let mut secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([1, 2, 3]);
+        secret.zeroize_now();
+        assert!(secret.is_wiped());
+        assert_eq!(secret.try_deref(), None);
+        assert_eq!(unsafe { *secret.buffer.get() }, [0u8; 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "secret was wiped")]
+    fn test_bytearray_deref_after_wipe_panics() {
+        let mut secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([1, 2, 3]);
+        let _ = &*secret;
+        secret.zeroize_now();
+        let _ = &*secret;
+    }
+
+    fn framed_len<A: Algorithm, M: Mode, const N: usize>(secret: &Encrypted<A, M, N>) -> usize {
+        secret.sealed_byte_len()
+    }
+
+    #[test]
+    fn test_partial_eq_str() {
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        assert!(secret == "hello");
+        assert!(secret != "world");
+        assert!(*"hello" == secret);
+        assert!("hello" == secret);
+        assert!(secret == *"hello");
+
+        let rc4 = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, StringLiteral, 4>::new(*b"data", *b"mykey");
+        assert!(rc4 == "data");
+        assert!("data" == rc4);
+        assert!(rc4 != "dat");
+    }
+
+    #[test]
+    fn test_partial_eq_bytes() {
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([1, 2, 3]);
+        assert!(secret == [1, 2, 3]);
+        assert!(secret != [1, 2, 4]);
+        assert!(secret == [1u8, 2, 3][..]);
+        assert!(secret != [1u8, 2][..]);
+
+        let rc4 = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 2>::new([9, 8], *b"mykey");
+        assert!(rc4 == [9, 8]);
+        assert!(rc4 == [9u8, 8][..]);
+    }
+
+    #[test]
+    fn test_debug_hides_plaintext() {
+        use alloc::format;
+
+        let mut secret = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        // `type_name` output is not stable, so only its start is checked.
+        let debug = format!("{secret:?}");
+        assert!(debug.starts_with("Encrypted { algorithm: const_secret::xor::Xor<..>,"));
+        assert!(!debug.contains("170"), "the key must not be shown: {debug}");
+        assert!(!debug.contains("Zeroize"));
+        assert!(
+            debug
+                .ends_with("mode: StringLiteral, state: sealed, buffer: [REDACTED; 5 bytes], .. }")
+        );
+
+        let _ = &*secret;
+        let debug = format!("{secret:?}");
+        assert!(debug.contains("state: decrypted"));
+        assert!(!debug.contains("hello"));
+
+        secret.zeroize_now();
+        assert!(format!("{secret:?}").contains("state: wiped"));
+
+        let rc4 = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 2>::new_with_deferred_key(
+            [9, 8],
+            *b"mykey",
+        );
+        let debug = format!("{rc4:#?}");
+        assert!(debug.contains("algorithm: const_secret::rc4::Rc4<..>"));
+        assert!(debug.contains("mode: ByteArray"));
+        assert!(debug.contains("state: key missing"));
+        assert!(debug.contains("buffer: [REDACTED; 2 bytes]"));
+    }
+
+    #[test]
+    fn test_display_formats_plaintext() {
+        use alloc::format;
+
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        assert_eq!(format!("{secret}"), "hello");
+        assert_eq!(format!("[{secret:>7}]"), "[  hello]");
+    }
+
+    #[test]
+    fn test_byte_slice_mode() {
+        let xor = Encrypted::<Xor<0xAA, Zeroize>, ByteSlice, 3>::new([1, 2, 3]);
+        let slice: &[u8] = &*xor;
+        assert_eq!(slice, &[1, 2, 3][..]);
+
+        let rc4 = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteSlice, 4>::new(*b"data", *b"mykey");
+        assert_eq!(&*rc4, b"data".as_slice());
+        assert_eq!(rc4.with_decrypted(<[u8]>::len), 4);
+    }
+
+    #[test]
+    fn test_cstr_mode() {
+        let xor = Encrypted::<Xor<0xAA, Zeroize>, CStrLiteral, 6>::new(*b"hello\0");
+        let cstr: &CStr = &*xor;
+        assert_eq!(cstr, c"hello");
+        assert_eq!(cstr.to_bytes_with_nul(), b"hello\0");
+
+        let rc4 = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, CStrLiteral, 5>::new(*b"data\0", *b"mykey");
+        assert_eq!(&*rc4, c"data");
+        rc4.with_decrypted(|plain| assert_eq!(plain.count_bytes(), 4));
+    }
+
+    #[test]
+    #[should_panic(expected = "must end with its only NUL byte")]
+    fn test_cstr_mode_rejects_missing_nul() {
+        let _ = Encrypted::<Xor<0xAA, Zeroize>, CStrLiteral, 5>::new(*b"hello");
+    }
+
+    #[test]
+    #[should_panic(expected = "must end with its only NUL byte")]
+    fn test_cstr_mode_rejects_interior_nul() {
+        let _ = Encrypted::<Xor<0xAA, Zeroize>, CStrLiteral, 4>::new(*b"a\0b\0");
+    }
+
+    #[test]
+    #[should_panic(expected = "lost its NUL terminator")]
+    fn test_cstr_mode_confirms_terminator() {
+        // Bypass the constructor's check: this ciphertext decrypts to "ab\xAA".
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, CStrLiteral, 3> {
+            buffer: UnsafeCell::new([b'a' ^ 0xAA, b'b' ^ 0xAA, 0]),
+            decryption_state: AtomicU8::new(STATE_UNENCRYPTED),
+            extra: (),
+            _phantom: PhantomData,
+        };
+        let _ = &*secret;
+    }
+
+    #[test]
+    fn test_as_ref() {
+        fn as_str(value: impl AsRef<str>) -> usize {
+            value.as_ref().len()
+        }
+        fn as_bytes(value: &impl AsRef<[u8]>) -> &[u8] {
+            value.as_ref()
+        }
+
+        let string = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        assert_eq!(as_str(&string), 5);
+        assert_eq!(AsRef::<str>::as_ref(&string), "hello");
+        assert_eq!(as_bytes(&string), b"hello");
+
+        let bytes = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 2>::new([9, 8], *b"mykey");
+        assert_eq!(as_bytes(&bytes), &[9, 8]);
+        assert_eq!(AsRef::<[u8; 2]>::as_ref(&bytes), &[9, 8]);
+
+        let slice = Encrypted::<Xor<0xAA, Zeroize>, ByteSlice, 2>::new([7, 6]);
+        assert_eq!(as_bytes(&slice), &[7, 6]);
+    }
+
+    #[test]
+    fn test_clone_is_sealed() {
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        let ciphertext = unsafe { *secret.buffer.get() };
+
+        let before = secret.clone();
+        assert_eq!(unsafe { *before.buffer.get() }, ciphertext);
+
+        assert_eq!(&*secret, "hello");
+        let after = secret.clone();
+        assert!(!after.is_decrypted());
+        assert_eq!(unsafe { *after.buffer.get() }, ciphertext, "clone must hold ciphertext");
+        assert_eq!(&*after, "hello");
+
+        let rc4 = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 4>::new(*b"data", *b"mykey");
+        let rc4_ciphertext = unsafe { *rc4.buffer.get() };
+        let _ = &*rc4;
+        let rc4_clone = rc4.clone();
+        assert_eq!(unsafe { *rc4_clone.buffer.get() }, rc4_ciphertext);
+        assert_eq!(&*rc4_clone, b"data");
+    }
+
+    #[test]
+    fn test_clone_derived_config_across_threads() {
+        type ApiKey = Encrypted<Rc4<5, Zeroize<[u8; 5]>>, StringLiteral, 6>;
+
+        #[derive(Clone)]
+        struct Config {
+            api_key: ApiKey,
+        }
+
+        let config = Config {
+            api_key: ApiKey::new(*b"s3cr3t", *b"mykey"),
+        };
+        assert_eq!(&*config.api_key, "s3cr3t");
+
+        let sent = config.clone();
+        assert!(!sent.api_key.is_decrypted(), "the clone starts sealed");
+        let handle = std::thread::spawn(move || std::string::String::from(&*sent.api_key));
+        assert_eq!(handle.join().unwrap(), "s3cr3t");
+    }
+
+    #[test]
+    fn test_clone_of_wiped_is_wiped() {
+        let mut secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([1, 2, 3]);
+        secret.zeroize_now();
+        let clone = secret.clone();
+        assert!(clone.is_wiped());
+        assert!(clone.try_deref().is_none());
+    }
+
+    #[test]
+    fn test_rotate_key() {
+        use crate::{rc4, xor::XorN};
+
+        let old = *b"old-k";
+        let new = *b"new-k";
+        let expected =
+            Encrypted::<Rc4<5, rc4::ReEncrypt<5>>, StringLiteral, 5>::new(*b"hello", new);
+
+        // Decrypted: encrypted straight under the new key.
+        let mut decrypted =
+            Encrypted::<Rc4<5, rc4::ReEncrypt<5>>, StringLiteral, 5>::new(*b"hello", old);
+        assert_eq!(&*decrypted, "hello");
+        decrypted.rotate_key(new);
+        assert!(!decrypted.is_decrypted());
+        assert_eq!(unsafe { *decrypted.buffer.get() }, unsafe { *expected.buffer.get() });
+        assert_eq!(&*decrypted, "hello");
+
+        // Sealed: decrypted with the old key first.
+        let mut sealed = Encrypted::<XorN<2, ReEncryptN<2>>, ByteArray, 3>::new([1, 2, 3], [9, 8]);
+        sealed.rotate_key([7, 6]);
+        assert_eq!(unsafe { *sealed.buffer.get() }, [1 ^ 7, 2 ^ 6, 3 ^ 7]);
+        assert_eq!(&*sealed, &[1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "secret was wiped")]
+    fn test_rotate_key_after_wipe_panics() {
+        let mut secret =
+            Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 2>::new([1, 2], *b"mykey");
+        secret.zeroize_now();
+        secret.rotate_key(*b"new-k");
+    }
+
+    #[test]
+    fn test_decode_hex() {
+        assert_eq!(decode_hex::<4>("deadbeef"), [0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(decode_hex::<2>("00Ff"), [0x00, 0xff]);
+        assert_eq!(decode_hex::<0>(""), [0u8; 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "exactly two digits per byte")]
+    fn test_decode_hex_rejects_odd_length() {
+        let _ = decode_hex::<2>("abc");
+    }
+
+    #[test]
+    #[should_panic(expected = "non-hex digit")]
+    fn test_decode_hex_rejects_non_hex() {
+        let _ = decode_hex::<2>("abzz");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_to_decrypted_vec() {
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        assert_eq!(secret.to_decrypted_vec(), b"hello");
+        assert!(!secret.is_decrypted());
+        assert_ne!(unsafe { &*secret.buffer.get() }, b"hello");
+
+        let rc4 = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 2>::new([9, 8], *b"mykey");
+        let _ = &*rc4;
+        assert_eq!(rc4.to_decrypted_vec(), [9, 8]);
+    }
+
+    #[test]
+    fn test_byte_at_decrypts() {
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 4>::new(*b"v2:x");
+        assert_eq!(secret.byte_at(1), b'2');
+        assert!(secret.is_decrypted());
+
+        let result = catch_unwind(AssertUnwindSafe(|| secret.byte_at(4)));
+        assert!(result.is_err(), "out-of-bounds index must panic");
+    }
+
+    #[test]
+    fn test_c_layout() {
+        use core::mem::offset_of;
+
+        type XorC = EncryptedC<Xor<0xAA, Zeroize>, ByteArray, 4>;
+        type Rc4C = EncryptedC<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 4>;
+        assert_eq!(offset_of!(XorC, buffer), 0);
+        assert_eq!(offset_of!(XorC, decryption_state), 4);
+        assert_eq!(size_of::<XorC>(), 5);
+        assert_eq!(offset_of!(Rc4C, decryption_state), 4);
+        assert_eq!(offset_of!(Rc4C, extra), 5);
+        assert_eq!(size_of::<Rc4C>(), 10);
+
+        let secret = XorC::new(*b"data");
+        let raw = core::ptr::from_ref(&secret);
+        // SAFETY: `raw` points to `secret`, which outlives the borrow.
+        let borrowed = unsafe { XorC::from_c(raw) };
+        assert_eq!(&**borrowed, b"data");
+        // SAFETY: C sees the state byte right after the buffer.
+        let state = unsafe { *raw.cast::<u8>().add(4) };
+        assert_eq!(state, STATE_DECRYPTED);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_into_string_and_vec() {
+        use alloc::{string::String, vec::Vec};
+
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        assert_eq!(String::from(secret), "hello");
+
+        let bytes: Vec<u8> =
+            Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 2>::new([9, 8], *b"mykey").into();
+        assert_eq!(bytes, [9, 8]);
+    }
+
+    #[test]
+    #[cfg(feature = "secrecy")]
+    fn test_expose_secret() {
+        use crate::secrecy::ExposeSecret;
+
+        fn expose<S: ?Sized>(secret: &impl ExposeSecret<S>) -> &S {
+            secret.expose_secret()
+        }
+
+        let text = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        assert_eq!(expose::<str>(&text), "hello");
+        assert!(text.is_decrypted());
+
+        let bytes = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 2>::new([9, 8], *b"mykey");
+        assert_eq!(expose::<[u8; 2]>(&bytes), &[9, 8]);
+    }
+
+    #[test]
+    fn test_hex_and_binary_formatting() {
+        use alloc::format;
+
+        let bytes = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([0x0f, 0xa0, 0x01]);
+        assert_eq!(format!("{bytes:x}"), "0fa001");
+        assert_eq!(format!("{bytes:X}"), "0FA001");
+        assert_eq!(format!("{bytes:b}"), "000011111010000000000001");
+
+        let rc4 = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteSlice, 2>::new([0xde, 0xad], *b"mykey");
+        assert_eq!(format!("{rc4:x}"), "dead");
+        assert_eq!(format!("{rc4:X}"), "DEAD");
+    }
+
+    #[test]
+    fn test_encode_hex() {
+        let bytes = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([0x0f, 0xa0, 0x01]);
+        assert_eq!(bytes.encode_hex::<6>(), *b"0fa001");
+        assert_eq!(bytes.encode_hex_upper::<6>(), *b"0FA001");
+
+        let hex: [u8; 4] =
+            Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 2>::new([0xde, 0xad], *b"mykey")
+                .encode_hex();
+        assert_eq!(&hex, b"dead");
+
+        let empty = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 0>::EMPTY;
+        assert_eq!(empty.encode_hex::<0>(), [0u8; 0]);
+    }
+
+    #[test]
+    fn test_partial_eq_compares_ciphertext() {
+        type Secret = Encrypted<Rc4<5, Zeroize<[u8; 5]>>, StringLiteral, 5>;
+
+        let a = Secret::new(*b"hello", *b"mykey");
+        let b = Secret::new(*b"hello", *b"mykey");
+        let other_plaintext = Secret::new(*b"world", *b"mykey");
+        let other_key = Secret::new(*b"hello", *b"yekym");
+        assert!(a == b);
+        assert!(a != other_plaintext);
+        assert!(a != other_key, "same plaintext under a different key is not equal");
+
+        // Decrypting does not change the result, and comparing does not decrypt.
+        assert_eq!(&*a, "hello");
+        assert!(a == b);
+        assert!(!b.is_decrypted());
+
+        let mut wiped = Secret::new(*b"hello", *b"mykey");
+        wiped.zeroize_now();
+        assert!(wiped != b);
+        assert!(wiped == wiped, "Eq must be reflexive for wiped secrets");
+
+        let mut other_wiped = Secret::new(*b"world", *b"mykey");
+        other_wiped.zeroize_now();
+        assert!(wiped == other_wiped, "wiped secrets have no ciphertext left to differ in");
+
+        // Poisoning only needs `&self`, so it can happen to a secret used as a map key.
+        let poisoned = panicking_secret();
+        assert!(catch_unwind(AssertUnwindSafe(|| poisoned[0])).is_err());
+        assert!(poisoned == poisoned, "Eq must be reflexive for poisoned secrets");
+        assert!(poisoned != panicking_secret());
+    }
+
+    #[test]
+    // Decrypting in place does not change the hash, so the interior mutability is fine.
+    #[allow(clippy::mutable_key_type)]
+    fn test_hash_ciphertext_as_map_key() {
+        use std::{collections::HashMap, hash::BuildHasher};
+
+        type Token = Encrypted<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 4>;
+
+        let mut permissions = HashMap::new();
+        permissions.insert(Token::new(*b"tok1", *b"mykey"), "admin");
+        permissions.insert(Token::new(*b"tok2", *b"mykey"), "read");
+        assert_eq!(permissions.get(&Token::new(*b"tok1", *b"mykey")), Some(&"admin"));
+        assert_eq!(permissions.get(&Token::new(*b"tok3", *b"mykey")), None);
+
+        let hasher = std::hash::RandomState::new();
+        let token = Token::new(*b"same", *b"mykey");
+        let sealed_hash = hasher.hash_one(&token);
+        assert_ne!(
+            sealed_hash,
+            hasher.hash_one(Token::new(*b"same", *b"yekym")),
+            "the same plaintext under another key hashes differently"
+        );
+
+        assert_eq!(&*token, b"same");
+        assert_eq!(hasher.hash_one(&token), sealed_hash, "decrypting keeps the hash");
+
+        let sealed = Token::new(*b"same", *b"mykey");
+        let _ = hasher.hash_one(&sealed);
+        assert!(!sealed.is_decrypted(), "hashing does not decrypt");
+    }
+
+    #[test]
+    fn test_encrypted_bytes() {
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([1, 2, 3]);
+        let ciphertext = [1 ^ 0xAA, 2 ^ 0xAA, 3 ^ 0xAA];
+        assert_eq!(secret.encrypted_bytes(), ciphertext);
+        assert!(!secret.is_decrypted());
+
+        assert_eq!(&*secret, &[1, 2, 3]);
+        assert_eq!(secret.encrypted_bytes(), ciphertext, "never the plaintext");
+
+        let mut wiped = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([1, 2, 3]);
+        wiped.zeroize_now();
+        assert_eq!(wiped.encrypted_bytes(), [0; 3]);
+    }
+
+    #[test]
+    fn test_ciphertext_only_at_rest() {
+        let mut secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([1, 2, 3]);
+        assert_eq!(secret.ciphertext(), Some([1 ^ 0xAA, 2 ^ 0xAA, 3 ^ 0xAA]));
+        assert!(!secret.is_decrypted(), "reading the ciphertext must not decrypt");
+
+        let _ = &*secret;
+        assert_eq!(secret.ciphertext(), None);
+
+        secret.zeroize_now();
+        assert_eq!(secret.ciphertext(), None);
+
+        let deferred = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 2>::new_with_deferred_key(
+            [9, 8],
+            *b"mykey",
+        );
+        let eager = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 2>::new([9, 8], *b"mykey");
+        assert_eq!(deferred.ciphertext(), eager.ciphertext());
+    }
+
+    #[test]
+    fn test_len_does_not_decrypt() {
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        assert_eq!(secret.len(), 5);
+        assert!(!secret.is_empty());
+        assert!(!secret.is_decrypted());
+
+        let empty = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 0>::EMPTY;
+        assert_eq!(empty.len(), 0);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_secret_field_reveal() {
+        struct Config {
+            user: Encrypted<Xor<0xAA, Zeroize>, StringLiteral, 5>,
+            token: Encrypted<Rc4<5, Zeroize<[u8; 5]>>, StringLiteral, 3>,
+        }
+
+        let config = Config {
+            user: Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"admin"),
+            token: Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, StringLiteral, 3>::new(*b"t0k", *b"mykey"),
+        };
+        let fields: [&dyn SecretField; 2] = [&config.user, &config.token];
+        let revealed: std::vec::Vec<&str> = fields.iter().map(|field| field.reveal()).collect();
+        assert_eq!(revealed, ["admin", "t0k"]);
+        assert!(config.user.is_decrypted());
+    }
+
+    #[test]
+    fn test_int_readers() {
+        let short = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 2>::new([0x12, 0x34]);
+        assert_eq!(short.as_u16_le(), 0x3412);
+        assert_eq!(short.as_u16_be(), 0x1234);
+
+        let word = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 4>::new(
+            0xDEAD_BEEF_u32.to_le_bytes(),
+            *b"mykey",
+        );
+        assert_eq!(word.as_u32_le(), 0xDEAD_BEEF);
+        assert_eq!(word.as_u32_be(), 0xEFBE_ADDE);
+
+        let nonce = 0x0123_4567_89AB_CDEF_u64;
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 8>::new(nonce.to_be_bytes());
+        assert_eq!(secret.as_u64_be(), nonce);
+        assert_eq!(secret.as_u64_le(), nonce.swap_bytes());
+        assert!(!secret.is_decrypted(), "reading an integer must not decrypt in place");
+
+        let mut wiped = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 8>::new([0; 8]);
+        wiped.zeroize_now();
+        assert!(catch_unwind(AssertUnwindSafe(|| wiped.as_u64_le())).is_err());
+    }
+
+    #[test]
+    fn test_mode_constants() {
+        fn kind<M: Mode>() -> (bool, bool) {
+            (M::UTF8, M::NUL_TERMINATED)
+        }
+
+        assert_eq!(kind::<StringLiteral>(), (true, false));
+        assert_eq!(kind::<ByteArray>(), (false, false));
+        assert_eq!(kind::<ByteSlice>(), (false, false));
+        assert_eq!(kind::<CStrLiteral>(), (false, true));
+    }
+
+    #[test]
+    fn test_empty_constant() {
+        let string = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 0>::EMPTY;
+        assert!(!string.is_decrypted());
+        assert_eq!(&*string, "");
+
+        let bytes = Encrypted::<Xor2<0x01, 0x02, Zeroize>, ByteArray, 0>::EMPTY;
+        assert_eq!(&*bytes, &[0u8; 0]);
+    }
+
+    static LEN_SECRET: Encrypted<Xor<0xAA, Zeroize>, StringLiteral, 5> =
+        Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+    static EMPTY_SECRET: Encrypted<Xor<0xAA, Zeroize>, ByteArray, 0> =
+        Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 0>::EMPTY;
+
+    #[test]
+    fn test_len_in_const_context() {
+        const LEN: usize = LEN_SECRET.len();
+        const {
+            assert!(!LEN_SECRET.is_empty());
+            assert!(EMPTY_SECRET.is_empty());
+        }
+
+        let buffer = [0u8; LEN];
+        assert_eq!(buffer.len(), 5);
+        assert!(!LEN_SECRET.is_decrypted());
+    }
+
+    #[test]
+    fn test_sealed_byte_len_is_mode_agnostic() {
+        let bytes = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([1, 2, 3]);
+        let string = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 6>::new(*b"h\xC3\xA9llo");
+        assert_eq!(framed_len(&bytes), 3);
+        assert_eq!(framed_len(&string), 6);
+        // Reading the length does not decrypt.
+        assert_eq!(bytes.decryption_state.load(Ordering::Acquire), STATE_UNENCRYPTED);
+        assert_eq!(string.decryption_state.load(Ordering::Acquire), STATE_UNENCRYPTED);
+
+        // For string mode it matches the UTF-8 byte length, not the char count.
+        assert_eq!(string.len(), 6);
+        assert_eq!(string.chars().count(), 5);
+    }
+
+    #[test]
+    fn test_panicking_decrypt_poisons() {
+        let secret = panicking_secret();
+        let first = catch_unwind(AssertUnwindSafe(|| {
+            let _ = &*secret;
+        }));
+        assert!(first.is_err());
+        assert_eq!(secret.decryption_state.load(Ordering::Acquire), STATE_POISONED);
+        assert_eq!(secret.try_deref(), None);
+        assert!(!secret.is_wiped());
+
+        let second = catch_unwind(AssertUnwindSafe(|| {
+            let _ = &*secret;
+        }));
+        let message = *second.unwrap_err().downcast::<&str>().unwrap();
+        assert_eq!(message, "secret was poisoned");
+    }
 }
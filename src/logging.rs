@@ -0,0 +1,248 @@
+//! Structured logging of secrets without leaking the plaintext.
+//!
+//! With the `slog` feature, [`Encrypted`] implements `slog::Value`, so a secret can
+//! be passed straight to `slog::o!` or a logging macro. With the `tracing` feature,
+//! [`Encrypted::tracing_value`] gives a value to record in a `tracing` span or event.
+//! Either way the logged value is the string `"[REDACTED]"`, and logging never
+//! decrypts the secret.
+//!
+//! `tracing::Value` is sealed and cannot be implemented outside `tracing`, hence the
+//! method instead of a trait impl.
+//!
+//! During development it can help to see the real value. With the `debug-logging`
+//! feature, [`Encrypted::with_plaintext_logging`] wraps a secret so that it logs (and
+//! displays) its plaintext, and [`Encrypted::masked`] shows only its last few bytes.
+//! Do not enable that feature in release builds.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[cfg(feature = "slog")]
+//! # {
+//! use const_secret::{Encrypted, StringLiteral, drop_strategy::Zeroize, xor::Xor};
+//!
+//! static API_KEY: Encrypted<Xor<0xAA, Zeroize>, StringLiteral, 6> =
+//!     Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 6>::new(*b"s3cr3t");
+//!
+//! let logger = slog::Logger::root(slog::Discard, slog::o!("api_key" => &API_KEY));
+//! slog::info!(logger, "connecting");
+//! # }
+//! ```
+
+#[cfg(feature = "debug-logging")]
+use core::fmt;
+
+use crate::{Algorithm, Encrypted};
+#[cfg(feature = "debug-logging")]
+use crate::{Mode, StringLiteral, unreadable_state_panic};
+
+/// What is logged in place of a secret.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Logs [`REDACTED`] under `key`. The secret is not decrypted.
+#[cfg(feature = "slog")]
+impl<A: Algorithm, M, const N: usize> slog::Value for Encrypted<A, M, N> {
+    fn serialize(
+        &self,
+        _record: &slog::Record<'_>,
+        key: slog::Key,
+        serializer: &mut dyn slog::Serializer,
+    ) -> slog::Result {
+        serializer.emit_str(key, REDACTED)
+    }
+}
+
+impl<A: Algorithm, M, const N: usize> Encrypted<A, M, N> {
+    /// Returns a `tracing` value that records [`REDACTED`] instead of the secret.
+    ///
+    /// ```rust
+    /// use const_secret::{Encrypted, StringLiteral, drop_strategy::Zeroize, xor::Xor};
+    ///
+    /// let api_key = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 6>::new(*b"s3cr3t");
+    /// tracing::info!(api_key = api_key.tracing_value(), "connecting");
+    /// ```
+    #[cfg(feature = "tracing")]
+    #[must_use]
+    pub fn tracing_value(&self) -> impl tracing::Value + use<A, M, N> {
+        REDACTED
+    }
+
+    /// Wraps the secret so that it logs its plaintext instead of [`REDACTED`].
+    ///
+    /// The wrapper implements `Display` and, with the `slog` feature, `slog::Value`,
+    /// and decrypts (as dereferencing does) when formatted. Record it in `tracing`
+    /// with `%secret.with_plaintext_logging()`. Meant for development only.
+    #[cfg(feature = "debug-logging")]
+    #[must_use]
+    pub fn with_plaintext_logging(&self) -> PlaintextLogging<'_, A, M, N> {
+        PlaintextLogging {
+            secret: self,
+        }
+    }
+
+    /// Wraps the secret so that it displays as `****` followed by its last
+    /// `visible_suffix` bytes, e.g. `****mnop`.
+    ///
+    /// Each time it is formatted, the secret is decrypted into a scratch buffer that
+    /// is zeroized afterwards, so a sealed secret stays sealed. A
+    /// [`StringLiteral`] suffix is shown as text if it starts on a character
+    /// boundary, and as hex otherwise; other modes are always shown as hex.
+    ///
+    /// ```rust
+    /// use const_secret::{Encrypted, StringLiteral, drop_strategy::Zeroize, xor::Xor};
+    ///
+    /// let key = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 8>::new(*b"abcdmnop");
+    /// assert_eq!(key.masked(4).to_string(), "****mnop");
+    /// assert!(!key.is_decrypted());
+    /// ```
+    #[cfg(feature = "debug-logging")]
+    #[must_use]
+    pub fn masked(&self, visible_suffix: usize) -> MaskedSecret<'_, A, M, N> {
+        MaskedSecret {
+            secret: self,
+            visible_suffix,
+        }
+    }
+}
+
+/// A secret that logs its plaintext, returned by [`Encrypted::with_plaintext_logging`].
+#[cfg(feature = "debug-logging")]
+pub struct PlaintextLogging<'a, A: Algorithm, M, const N: usize> {
+    secret: &'a Encrypted<A, M, N>,
+}
+
+#[cfg(feature = "debug-logging")]
+impl<A: Algorithm, const N: usize> fmt::Display for PlaintextLogging<'_, A, StringLiteral, N> {
+    /// Formats the plaintext.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret was wiped or poisoned.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.secret, f)
+    }
+}
+
+/// A secret that displays only its last few bytes, returned by [`Encrypted::masked`].
+#[cfg(feature = "debug-logging")]
+pub struct MaskedSecret<'a, A: Algorithm, M, const N: usize> {
+    secret: &'a Encrypted<A, M, N>,
+    visible_suffix: usize,
+}
+
+#[cfg(feature = "debug-logging")]
+impl<A: Algorithm, M: Mode, const N: usize> fmt::Display for MaskedSecret<'_, A, M, N> {
+    /// Formats `****` and the visible suffix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret was wiped or poisoned.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scratch = match self.secret.try_snapshot() {
+            Ok(scratch) => scratch,
+            Err(unreadable) => unreadable_state_panic(unreadable),
+        };
+        let start = N.saturating_sub(self.visible_suffix);
+        f.write_str("****")?;
+        match core::str::from_utf8(&scratch.0) {
+            Ok(text) if M::UTF8 && text.is_char_boundary(start) => f.write_str(&text[start..]),
+            _ => scratch.0[start..].iter().try_for_each(|byte| write!(f, "{byte:02x}")),
+        }
+    }
+}
+
+#[cfg(all(feature = "debug-logging", feature = "slog"))]
+impl<A: Algorithm, const N: usize> slog::Value for PlaintextLogging<'_, A, StringLiteral, N> {
+    fn serialize(
+        &self,
+        _record: &slog::Record<'_>,
+        key: slog::Key,
+        serializer: &mut dyn slog::Serializer,
+    ) -> slog::Result {
+        serializer.emit_arguments(key, &format_args!("{self}"))
+    }
+}
+
+#[cfg(all(test, any(feature = "slog", feature = "debug-logging")))]
+mod tests {
+    use super::*;
+    use crate::{StringLiteral, drop_strategy::Zeroize, xor::Xor};
+    #[cfg(feature = "slog")]
+    use core::fmt;
+
+    type Secret = Encrypted<Xor<0xAA, Zeroize>, StringLiteral, 6>;
+
+    #[cfg(feature = "slog")]
+    fn log_value(value: &dyn slog::Value) -> std::string::String {
+        use std::string::{String, ToString};
+
+        struct Capture(String);
+
+        impl slog::Serializer for Capture {
+            fn emit_arguments(
+                &mut self,
+                _key: slog::Key,
+                val: &fmt::Arguments<'_>,
+            ) -> slog::Result {
+                self.0 = val.to_string();
+                Ok(())
+            }
+        }
+
+        let mut capture = Capture(String::new());
+        let message = format_args!("");
+        let record = slog::record!(slog::Level::Info, "", &message, slog::b!());
+        value.serialize(&record, "secret", &mut capture).unwrap();
+        capture.0
+    }
+
+    #[test]
+    #[cfg(feature = "slog")]
+    fn test_slog_value_is_redacted() {
+        let secret = Secret::new(*b"s3cr3t");
+        assert_eq!(log_value(&secret), REDACTED);
+        assert_eq!(log_value(&&secret), REDACTED);
+
+        assert_eq!(&*secret, "s3cr3t");
+        assert_eq!(log_value(&secret), REDACTED, "decrypted secrets stay redacted");
+    }
+
+    #[test]
+    #[cfg(feature = "slog")]
+    fn test_slog_value_does_not_decrypt() {
+        let secret = Secret::new(*b"s3cr3t");
+        let _ = log_value(&secret);
+        assert!(!secret.is_decrypted());
+    }
+
+    #[test]
+    #[cfg(feature = "debug-logging")]
+    fn test_plaintext_logging() {
+        use std::string::ToString;
+
+        let secret = Secret::new(*b"s3cr3t");
+        assert_eq!(secret.with_plaintext_logging().to_string(), "s3cr3t");
+        #[cfg(feature = "slog")]
+        assert_eq!(log_value(&secret.with_plaintext_logging()), "s3cr3t");
+    }
+
+    #[test]
+    #[cfg(feature = "debug-logging")]
+    fn test_masked_suffix() {
+        use crate::ByteArray;
+        use std::string::ToString;
+
+        let secret = Secret::new(*b"s3cr3t");
+        assert_eq!(secret.masked(2).to_string(), "****3t");
+        assert_eq!(secret.masked(0).to_string(), "****");
+        assert_eq!(secret.masked(100).to_string(), "****s3cr3t");
+        assert!(!secret.is_decrypted(), "masking does not cache plaintext");
+
+        let accented = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 3>::new(*b"a\xC3\xA9");
+        assert_eq!(accented.masked(2).to_string(), "****\u{e9}");
+        assert_eq!(accented.masked(1).to_string(), "****a9", "mid-character falls back to hex");
+
+        let bytes = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 3>::new([0xDE, 0xAD, 0x01]);
+        assert_eq!(bytes.masked(2).to_string(), "****ad01");
+    }
+}
@@ -0,0 +1,271 @@
+//! Macros that declare secrets without spelling out their length.
+//!
+//! - [`secret!`](crate::secret!) declares a `const` secret from a byte string literal.
+//!   It is always available, at the crate root.
+//! - `macros::secret!` builds a secret value from a string, byte string or C string
+//!   literal, and passes any further arguments (such as a key) on to the algorithm's
+//!   constructor. It is a procedural macro from the `const-secret-macros` crate and
+//!   needs the `macros` feature.
+//! - [`key_from_env!`](crate::key_from_env!) decodes a key passed in by a build script.
+
+/// Declares an encrypted `const` without spelling out its type.
+///
+/// The buffer length `N` (and the RC4 key length) is computed from the byte string
+/// literals, so it can never disagree with the data.
+///
+/// ```text
+/// secret!(<vis> NAME, xor = <u8 key>, <drop>, <mode>, b"data");
+/// secret!(<vis> NAME, rc4 = b"mykey", <drop>, <mode>, b"data");
+/// ```
+///
+/// - `<drop>` is one of `zeroize`, `reencrypt` or `noop`.
+/// - `<mode>` is `str` for [`StringLiteral`](crate::StringLiteral), `bytes` for
+///   [`ByteArray`](crate::ByteArray), `slice` for [`ByteSlice`](crate::ByteSlice) or
+///   `cstr` for [`CStrLiteral`](crate::CStrLiteral). A `cstr` literal must spell out its
+///   trailing `\0`.
+///
+/// # Example
+///
+/// ```rust
+/// use const_secret::secret;
+///
+/// secret!(API_KEY, xor = 0xAA, zeroize, str, b"hello");
+/// secret!(pub(crate) TOKEN, rc4 = b"my-key", reencrypt, bytes, b"\x01\x02\x03");
+///
+/// fn main() {
+///     assert_eq!(&*API_KEY, "hello");
+///     assert_eq!(&*TOKEN, &[1, 2, 3]);
+/// }
+/// ```
+#[macro_export]
+macro_rules! secret {
+    ($vis:vis $name:ident, xor = $key:expr, $drop:ident, $mode:ident, $data:literal $(,)?) => {
+        // Every use of the `const` gets a fresh, sealed copy, which is the point.
+        #[allow(clippy::declare_interior_mutable_const)]
+        $vis const $name: $crate::Encrypted<
+            $crate::xor::Xor<{ $key }, $crate::secret!(@xor_drop $drop, $key)>,
+            $crate::secret!(@mode $mode),
+            { $data.len() },
+        > = $crate::Encrypted::<
+            $crate::xor::Xor<{ $key }, $crate::secret!(@xor_drop $drop, $key)>,
+            $crate::secret!(@mode $mode),
+            { $data.len() },
+        >::new(*$data);
+    };
+    ($vis:vis $name:ident, rc4 = $key:literal, $drop:ident, $mode:ident, $data:literal $(,)?) => {
+        // Every use of the `const` gets a fresh, sealed copy, which is the point.
+        #[allow(clippy::declare_interior_mutable_const)]
+        $vis const $name: $crate::Encrypted<
+            $crate::rc4::Rc4<{ $key.len() }, $crate::secret!(@rc4_drop $drop, $key)>,
+            $crate::secret!(@mode $mode),
+            { $data.len() },
+        > = $crate::Encrypted::<
+            $crate::rc4::Rc4<{ $key.len() }, $crate::secret!(@rc4_drop $drop, $key)>,
+            $crate::secret!(@mode $mode),
+            { $data.len() },
+        >::new(*$data, *$key);
+    };
+
+    (@mode str) => { $crate::StringLiteral };
+    (@mode bytes) => { $crate::ByteArray };
+    (@mode slice) => { $crate::ByteSlice };
+    (@mode cstr) => { $crate::CStrLiteral };
+
+    (@xor_drop zeroize, $key:expr) => { $crate::drop_strategy::Zeroize };
+    (@xor_drop noop, $key:expr) => { $crate::drop_strategy::NoOp };
+    (@xor_drop reencrypt, $key:expr) => { $crate::xor::ReEncrypt<{ $key }> };
+
+    (@rc4_drop zeroize, $key:literal) => {
+        $crate::drop_strategy::Zeroize<[u8; { $key.len() }]>
+    };
+    (@rc4_drop noop, $key:literal) => { $crate::drop_strategy::NoOp<[u8; { $key.len() }]> };
+    (@rc4_drop reencrypt, $key:literal) => { $crate::rc4::ReEncrypt<{ $key.len() }> };
+}
+
+/// Builds an [`Encrypted`](crate::Encrypted) value from a literal, inferring its
+/// length `N`.
+///
+/// ```text
+/// secret!(algorithm: <Algorithm>, mode: <Mode>, <literal> [, <constructor args>...])
+/// ```
+///
+/// The literal is checked at compile time: string literals must be ASCII, a
+/// [`StringLiteral`](crate::StringLiteral) secret must be UTF-8, and a
+/// [`CStrLiteral`](crate::CStrLiteral) secret must end with its only NUL byte.
+///
+/// ```rust
+/// use const_secret::{
+///     Encrypted, StringLiteral,
+///     drop_strategy::Zeroize,
+///     macros,
+///     rc4::Rc4,
+///     xor::Xor,
+/// };
+///
+/// const API_KEY: Encrypted<Xor<0xAA, Zeroize>, StringLiteral, 5> =
+///     macros::secret!(algorithm: Xor<0xAA, Zeroize>, mode: StringLiteral, "hello");
+///
+/// let token = macros::secret!(
+///     algorithm: Rc4<5, Zeroize<[u8; 5]>>,
+///     mode: StringLiteral,
+///     "token",
+///     *b"mykey",
+/// );
+/// assert_eq!(&*API_KEY, "hello");
+/// assert_eq!(&*token, "token");
+/// ```
+///
+/// ```compile_fail
+/// use const_secret::{ByteArray, drop_strategy::Zeroize, macros, xor::Xor};
+///
+/// let _ = macros::secret!(algorithm: Xor<0xAA, Zeroize>, mode: ByteArray, "pässword");
+/// ```
+///
+/// ```compile_fail
+/// use const_secret::{CStrLiteral, drop_strategy::Zeroize, macros, xor::Xor};
+///
+/// let _ = macros::secret!(algorithm: Xor<0xAA, Zeroize>, mode: CStrLiteral, b"no nul");
+/// ```
+#[cfg(feature = "macros")]
+pub use const_secret_macros::secret;
+
+/// Decodes a key from a hex environment variable set at build time.
+///
+/// Expands to a `[u8; N]` expression, so it can initialize a `const`. `N` is inferred
+/// from the context, and the variable must hold exactly `2 * N` hex digits, or the
+/// build fails. Pair it with `build_helpers::emit_key` in a build script, or set the
+/// variable by hand.
+///
+/// A single-byte key is decoded into a `[u8; 1]` first; indexing the macro's result
+/// directly leaves `N` uninferred.
+///
+/// ```rust
+/// use const_secret::{ByteArray, Encrypted, drop_strategy::Zeroize, key_from_env, xor::Xor};
+///
+/// const RC4_KEY: [u8; 16] = key_from_env!("RC4_KEY");
+/// const XOR_KEY: [u8; 1] = key_from_env!("XOR_KEY");
+///
+/// const SECRET: Encrypted<Xor<{ XOR_KEY[0] }, Zeroize>, ByteArray, 2> =
+///     Encrypted::<Xor<{ XOR_KEY[0] }, Zeroize>, ByteArray, 2>::new([1, 2]);
+/// # assert_eq!(RC4_KEY.len(), 16);
+/// # assert_eq!(&*SECRET, &[1, 2]);
+/// ```
+#[macro_export]
+macro_rules! key_from_env {
+    ($name:literal $(,)?) => {
+        $crate::__private::decode_hex(::core::env!($name))
+    };
+}
+
+#[cfg(test)]
+#[allow(clippy::explicit_auto_deref)]
+mod tests {
+    use crate::drop_strategy::{DropStrategy, Residue};
+
+    secret!(XOR_ZEROIZE, xor = 0xAA, zeroize, str, b"hello");
+    secret!(XOR_REENCRYPT, xor = 0x5A, reencrypt, bytes, b"\x00\x01\x02\x03");
+    secret!(XOR_NOOP, xor = 0xCC, noop, str, b"noop");
+    secret!(pub(crate) RC4_ZEROIZE, rc4 = b"mykey", zeroize, str, b"rc4 secret");
+    secret!(RC4_REENCRYPT, rc4 = b"k-e-y", reencrypt, bytes, b"\xFF\xEE");
+    secret!(RC4_NOOP, rc4 = b"another key", noop, str, b"x");
+    secret!(XOR_SLICE, xor = 0x11, zeroize, slice, b"\x04\x05");
+    secret!(RC4_CSTR, rc4 = b"mykey", zeroize, cstr, b"token\0");
+
+    fn residue<A: crate::Algorithm, M, const N: usize>(_: &crate::Encrypted<A, M, N>) -> Residue {
+        <A::Drop as DropStrategy>::RESIDUE
+    }
+
+    #[test]
+    fn test_key_from_env() {
+        // Set in `.cargo/config.toml`.
+        const RC4_KEY: [u8; 16] = key_from_env!("RC4_KEY");
+        const XOR_KEY: [u8; 1] = key_from_env!("XOR_KEY");
+
+        assert_eq!(RC4_KEY[..4], [0x00, 0x11, 0x22, 0x33]);
+        assert_eq!(XOR_KEY, [0x5c]);
+        let secret = crate::Encrypted::<
+            crate::xor::Xor<{ XOR_KEY[0] }, crate::drop_strategy::Zeroize>,
+            crate::ByteArray,
+            2,
+        >::new([1, 2]);
+        assert_eq!(&*secret, &[1, 2]);
+    }
+
+    #[test]
+    fn test_xor_secrets() {
+        let zeroize = XOR_ZEROIZE;
+        assert_eq!(&*zeroize, "hello");
+        assert_eq!(residue(&zeroize), Residue::Scrubbed);
+
+        let reencrypt = XOR_REENCRYPT;
+        assert_eq!(&*reencrypt, &[0, 1, 2, 3]);
+        assert_eq!(residue(&reencrypt), Residue::Ciphertext);
+
+        let noop = XOR_NOOP;
+        assert_eq!(&*noop, "noop");
+        assert_eq!(residue(&noop), Residue::Plaintext);
+    }
+
+    #[test]
+    fn test_rc4_secrets() {
+        let zeroize = RC4_ZEROIZE;
+        assert_eq!(&*zeroize, "rc4 secret");
+        assert_eq!(residue(&zeroize), Residue::Scrubbed);
+
+        let reencrypt = RC4_REENCRYPT;
+        assert_eq!(&*reencrypt, &[0xFF, 0xEE]);
+        assert_eq!(residue(&reencrypt), Residue::Ciphertext);
+
+        let noop = RC4_NOOP;
+        assert_eq!(&*noop, "x");
+        assert_eq!(residue(&noop), Residue::Plaintext);
+    }
+
+    #[test]
+    fn test_slice_mode() {
+        let slice = XOR_SLICE;
+        let bytes: &[u8] = &*slice;
+        assert_eq!(bytes, &[4, 5]);
+    }
+
+    #[test]
+    fn test_cstr_mode() {
+        let cstr = RC4_CSTR;
+        assert_eq!(&*cstr, c"token");
+    }
+
+    #[test]
+    #[cfg(feature = "macros")]
+    fn test_proc_macro() {
+        use crate::{
+            ByteArray, CStrLiteral, Encrypted, StringLiteral, drop_strategy::Zeroize, macros,
+            rc4::Rc4, xor::Xor,
+        };
+
+        #[allow(clippy::declare_interior_mutable_const)]
+        const TEXT: Encrypted<Xor<0xAA, Zeroize>, StringLiteral, 5> =
+            macros::secret!(algorithm: Xor<0xAA, Zeroize>, mode: StringLiteral, "hello");
+        let text = TEXT;
+        assert_eq!(&*text, "hello");
+
+        let bytes = macros::secret!(algorithm: Xor<0x11>, mode: ByteArray, b"\x01\x02");
+        assert_eq!(&*bytes, &[1, 2]);
+
+        let cstr = macros::secret!(
+            algorithm: Rc4<5, Zeroize<[u8; 5]>>,
+            mode: CStrLiteral,
+            c"token",
+            *b"mykey",
+        );
+        assert_eq!(&*cstr, c"token");
+    }
+
+    #[test]
+    fn test_matches_explicit_declaration() {
+        use crate::{Encrypted, StringLiteral, drop_strategy::Zeroize, xor::Xor};
+
+        let explicit = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        let from_macro: Encrypted<Xor<0xAA, Zeroize>, StringLiteral, 5> = XOR_ZEROIZE;
+        assert_eq!(&*explicit, &*from_macro);
+    }
+}
@@ -0,0 +1,81 @@
+//! Ready-made secret types for common kinds of values.
+//!
+//! Picking an algorithm, a drop strategy and a mode for every secret is a choice most
+//! users make by copying an example. These aliases make that choice once, for the
+//! values secrets usually are:
+//!
+//! | Alias | Algorithm | Mode | Why |
+//! |-------|-----------|------|-----|
+//! | [`EncryptedPassword`] | RC4, 16-byte key | [`StringLiteral`] | Accessed rarely, so the slower, longer-keyed cipher costs nothing |
+//! | [`EncryptedApiKey`] | XOR, key in the type | [`StringLiteral`] | Read on every request; XOR decrypts fastest and stores no key |
+//! | [`EncryptedHmacKey`] | RC4, 16-byte key | [`ByteArray`] | Raw key bytes, not text |
+//!
+//! All of them wipe the plaintext with [`Zeroize`] on drop. None of them is
+//! encryption in the cryptographic sense: the key is in the binary next to the
+//! ciphertext, so they keep secrets out of `strings` output and casual memory dumps,
+//! not away from someone reverse-engineering the program. Use
+//! [`chacha20`](crate::chacha20) when the ciphertext itself should resist analysis.
+//!
+//! # Example
+//!
+//! ```rust
+//! use const_secret::presets::{EncryptedApiKey, EncryptedHmacKey, EncryptedPassword};
+//!
+//! const DB_PASSWORD: EncryptedPassword<6> =
+//!     EncryptedPassword::<6>::new(*b"hunter", *b"0123456789abcdef");
+//! const API_KEY: EncryptedApiKey<0x5C, 7> = EncryptedApiKey::<0x5C, 7>::new(*b"sk-1234");
+//! const HMAC_KEY: EncryptedHmacKey<4> =
+//!     EncryptedHmacKey::<4>::new([0xDE, 0xAD, 0xBE, 0xEF], *b"fedcba9876543210");
+//!
+//! fn main() {
+//!     assert_eq!(&*DB_PASSWORD, "hunter");
+//!     assert_eq!(&*API_KEY, "sk-1234");
+//!     assert_eq!(&*HMAC_KEY, &[0xDE, 0xAD, 0xBE, 0xEF]);
+//! }
+//! ```
+
+use crate::{ByteArray, Encrypted, StringLiteral, drop_strategy::Zeroize, rc4::Rc4, xor::Xor};
+
+/// A password of `N` bytes, sealed with RC4 under a 16-byte key.
+///
+/// Passwords are read once, to log in or open a connection, so the cost of RC4's key
+/// schedule does not matter, and a 16-byte key leaves no single-byte pattern in the
+/// ciphertext. Construct with `EncryptedPassword::<N>::new(plaintext, key)`.
+pub type EncryptedPassword<const N: usize> =
+    Encrypted<Rc4<16, Zeroize<[u8; 16]>>, StringLiteral, N>;
+
+/// An API key or token of `N` bytes, sealed with XOR under `KEY`.
+///
+/// Tokens are often read on every request. XOR is the cheapest algorithm to decrypt
+/// and keeps its key in the type, so the secret is one byte larger than its
+/// plaintext. `KEY` is a parameter rather than a fixed default: a key shared by every
+/// user of this crate would be the first one tried. Construct with
+/// `EncryptedApiKey::<KEY, N>::new(plaintext)`.
+pub type EncryptedApiKey<const KEY: u8, const N: usize> =
+    Encrypted<Xor<KEY, Zeroize>, StringLiteral, N>;
+
+/// An HMAC or other binary key of `N` bytes, sealed with RC4 under a 16-byte key.
+///
+/// Dereferences to `[u8; N]`, so it can be passed straight to a MAC constructor.
+/// Construct with `EncryptedHmacKey::<N>::new(bytes, key)`.
+pub type EncryptedHmacKey<const N: usize> = Encrypted<Rc4<16, Zeroize<[u8; 16]>>, ByteArray, N>;
+
+#[cfg(test)]
+#[allow(clippy::explicit_auto_deref)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_round_trip() {
+        let password = EncryptedPassword::<6>::new(*b"hunter", *b"0123456789abcdef");
+        assert_ne!(password.encrypted_bytes(), *b"hunter");
+        assert_eq!(&*password, "hunter");
+
+        let api_key = EncryptedApiKey::<0x5C, 7>::new(*b"sk-1234");
+        assert_eq!(size_of_val(&api_key), 8);
+        assert_eq!(&*api_key, "sk-1234");
+
+        let hmac_key = EncryptedHmacKey::<4>::new([1, 2, 3, 4], *b"fedcba9876543210");
+        assert_eq!(&*hmac_key, &[1, 2, 3, 4]);
+    }
+}
@@ -1,7 +1,7 @@
 //! RC4 stream cipher algorithm implementation.
 //!
 //! This module provides the RC4 (Rivest Cipher 4) stream cipher implementation.
-//! RC4 is a widely-used stream cipher that uses a variable-length key (1-256 bytes)
+//! RC4 is a widely-used stream cipher that uses a variable-length key (5-256 bytes)
 //! to generate a pseudorandom keystream which is XOR'd with the plaintext.
 //!
 //! # Security Note
@@ -22,6 +22,8 @@
 //! # Types
 //!
 //! - [`Rc4<KEY_LEN, D>`](Rc4): The main algorithm type with const generic key length
+//! - [`Rc4Large<KEY_LEN, D>`](Rc4Large): [`Rc4`] under another name, documenting its cost
+//!   for large buffers
 //! - [`ReEncrypt<KEY_LEN>`](ReEncrypt): A drop strategy that re-encrypts data on drop
 //!
 //! # Example
@@ -52,76 +54,213 @@
 //! }
 //! ```
 
-use core::{
-    cell::UnsafeCell,
-    marker::PhantomData,
-    ops::Deref,
-    sync::atomic::{AtomicU8, Ordering},
-};
+use core::{cell::UnsafeCell, marker::PhantomData, sync::atomic::AtomicU8};
 
 use crate::{
-    Algorithm, ByteArray, Encrypted, STATE_DECRYPTED, STATE_DECRYPTING, STATE_UNENCRYPTED,
-    StringLiteral,
-    drop_strategy::{DropStrategy, Zeroize},
+    Algorithm, Encrypted, Mode, STATE_KEY_MISSING, STATE_UNENCRYPTED, StreamDecrypt,
+    check_plaintext, decode_hex,
+    drop_strategy::{DropStrategy, Residue, Zeroize},
+    wipe,
 };
 
+/// Runs the RC4 Key Scheduling Algorithm (KSA) and returns the permuted S-box.
+///
+/// Keys shorter than 5 bytes (40 bits) are rejected at compile time, since they can
+/// be brute-forced in moments.
+const fn key_schedule<const KEY_LEN: usize>(key: &[u8; KEY_LEN]) -> [u8; 256] {
+    const { assert!(KEY_LEN >= 5, "RC4 key must be at least 5 bytes (40 bits) long") };
+
+    // We use a fixed 256-byte S-box for simplicity
+    let mut s = [0u8; 256];
+    let mut j: u8 = 0;
+
+    // Initialize S-box
+    let mut i = 0usize;
+    while i < 256 {
+        s[i] = i as u8;
+        i += 1;
+    }
+
+    // KSA: Permute S-box based on key
+    let mut i = 0usize;
+    while i < 256 {
+        let key_byte = key[i % KEY_LEN];
+        j = j.wrapping_add(s[i]).wrapping_add(key_byte);
+        // Swap s[i] and s[j]
+        let temp = s[i];
+        s[i] = s[j as usize];
+        s[j as usize] = temp;
+        i += 1;
+    }
+
+    s
+}
+
+/// Applies the RC4 keystream derived from `key` to `data` in place.
+///
+/// RC4 XORs the keystream into the buffer, so the same routine encrypts and decrypts.
+/// It is a `const fn` for the compile-time constructors, which cannot call
+/// [`wipe`]: the S-box is left on the stack when this runs at runtime. During
+/// compile-time evaluation it never reaches a runtime stack. Runtime paths use
+/// [`apply_keystream_windowed`], which wipes it.
+pub(crate) const fn apply_keystream<const KEY_LEN: usize>(data: &mut [u8], key: &[u8; KEY_LEN]) {
+    let mut s = key_schedule(key);
+
+    // PRGA: Generate keystream and XOR it into the buffer in place
+    let mut i: u8 = 0;
+    let mut j: u8 = 0;
+    let mut idx = 0usize;
+    while idx < data.len() {
+        i = i.wrapping_add(1);
+        j = j.wrapping_add(s[i as usize]);
+        // Swap s[i] and s[j]
+        let temp = s[i as usize];
+        s[i as usize] = s[j as usize];
+        s[j as usize] = temp;
+        // Generate keystream byte and XOR with buffer
+        let k = s[(s[i as usize].wrapping_add(s[j as usize])) as usize];
+        data[idx] ^= k;
+        idx += 1;
+    }
+}
+
+/// Size of the keystream window used by [`apply_keystream_windowed`].
+const WINDOW: usize = 256;
+
+/// Applies the same keystream as [`apply_keystream`], one [`WINDOW`] at a time.
+///
+/// The PRGA fills a small keystream buffer that stays in L1 cache, then the buffer is
+/// XOR'd into the data in a separate pass the compiler can vectorize. The keystream
+/// window, the S-box and the `i`/`j` indices are zeroized before returning, so no key
+/// schedule material is left on the stack.
+fn apply_keystream_windowed<const KEY_LEN: usize>(data: &mut [u8], key: &[u8; KEY_LEN]) {
+    let mut s = key_schedule(key);
+    let mut window = [0u8; WINDOW];
+    let mut i: u8 = 0;
+    let mut j: u8 = 0;
+
+    for chunk in data.chunks_mut(WINDOW) {
+        for k in &mut window[..chunk.len()] {
+            i = i.wrapping_add(1);
+            j = j.wrapping_add(s[i as usize]);
+            s.swap(i as usize, j as usize);
+            *k = s[(s[i as usize].wrapping_add(s[j as usize])) as usize];
+        }
+        for (byte, k) in chunk.iter_mut().zip(&window) {
+            *byte ^= k;
+        }
+    }
+
+    wipe(&mut window);
+    wipe(&mut s);
+    // SAFETY: `i` and `j` are valid, aligned locals.
+    unsafe {
+        core::ptr::write_volatile(&raw mut i, 0);
+        core::ptr::write_volatile(&raw mut j, 0);
+    }
+}
+
+/// RC4 state for byte-at-a-time decryption: the S-box followed by `i` and `j`.
+type StreamState = [u8; 258];
+
+/// Runs the KSA and returns the state for the first keystream byte.
+fn stream_start<const KEY_LEN: usize>(key: &[u8; KEY_LEN]) -> StreamState {
+    let mut state = [0u8; 258];
+    let mut s = key_schedule(key);
+    state[..256].copy_from_slice(&s);
+    wipe(&mut s);
+    state
+}
+
+/// Runs one PRGA step and XORs the keystream byte into `byte`.
+fn stream_decrypt_byte(state: &mut StreamState, byte: u8) -> u8 {
+    let (s, ij) = state.split_at_mut(256);
+    let i = ij[0].wrapping_add(1);
+    let j = ij[1].wrapping_add(s[i as usize]);
+    s.swap(i as usize, j as usize);
+    ij[0] = i;
+    ij[1] = j;
+    byte ^ s[(s[i as usize].wrapping_add(s[j as usize])) as usize]
+}
+
 /// Re-encrypts the buffer using RC4 on drop.
 /// This ensures the plaintext never remains in memory after the value is dropped.
 pub struct ReEncrypt<const KEY_LEN: usize>;
 
 impl<const KEY_LEN: usize> DropStrategy for ReEncrypt<KEY_LEN> {
     type Extra = [u8; KEY_LEN];
+    const RESIDUE: Residue = Residue::Ciphertext;
 
     fn drop(data: &mut [u8], key: &[u8; KEY_LEN]) {
         // Re-run RC4 to re-encrypt the buffer
-        let mut s = [0u8; 256];
-        let mut j: u8 = 0;
-
-        // Initialize S-box
-        let mut i = 0usize;
-        while i < 256 {
-            s[i] = i as u8;
-            i += 1;
-        }
-
-        // KSA
-        let mut i = 0usize;
-        while i < 256 {
-            j = j.wrapping_add(s[i]).wrapping_add(key[i % KEY_LEN]);
-            s.swap(i, j as usize);
-            i += 1;
-        }
-
-        // PRGA: Re-encrypt
-        let mut i: u8 = 0;
-        j = 0;
-        let mut idx = 0usize;
-        let n = data.len();
-        while idx < n {
-            i = i.wrapping_add(1);
-            j = j.wrapping_add(s[i as usize]);
-            s.swap(i as usize, j as usize);
-            let k = s[(s[i as usize].wrapping_add(s[j as usize])) as usize];
-            data[idx] ^= k;
-            idx += 1;
-        }
+        apply_keystream_windowed(data, key);
     }
 }
 
 /// An algorithm that performs RC4 encryption and decryption.
 /// This algorithm is generic over drop strategy.
 ///
-/// RC4 is a stream cipher that uses a variable-length key (1-256 bytes).
+/// RC4 is a stream cipher that uses a variable-length key (5-256 bytes).
 /// The key is stored alongside the encrypted data and is used to reproduce
 /// the keystream for decryption at runtime.
+///
+/// A key shorter than 5 bytes is rejected at compile time:
+///
+/// ```compile_fail
+/// use const_secret::{ByteArray, Encrypted, drop_strategy::Zeroize, rc4::Rc4};
+///
+/// let _ = Encrypted::<Rc4<3, Zeroize<[u8; 3]>>, ByteArray, 4>::new(*b"data", *b"key");
+/// ```
 pub struct Rc4<const KEY_LEN: usize, D: DropStrategy = Zeroize>(PhantomData<D>);
 
+impl<const KEY_LEN: usize> Rc4<KEY_LEN> {
+    /// Returns the ciphertext that `Encrypted::new` stores for `plaintext` under `key`,
+    /// whatever the drop strategy.
+    ///
+    /// Lets a test check that a secret really was encrypted at compile time:
+    ///
+    /// ```rust
+    /// use const_secret::{ByteArray, Encrypted, drop_strategy::Zeroize, rc4::Rc4};
+    ///
+    /// const EXPECTED: [u8; 4] = Rc4::<5>::expected_ciphertext(*b"data", *b"mykey");
+    ///
+    /// let secret = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 4>::new(*b"data", *b"mykey");
+    /// assert_eq!(secret.encrypted_bytes(), EXPECTED);
+    /// ```
+    #[must_use]
+    pub const fn expected_ciphertext<const N: usize>(
+        mut plaintext: [u8; N],
+        key: [u8; KEY_LEN],
+    ) -> [u8; N] {
+        apply_keystream(&mut plaintext, &key);
+        plaintext
+    }
+}
+
 impl<const KEY_LEN: usize, D: DropStrategy<Extra = [u8; KEY_LEN]>> Algorithm for Rc4<KEY_LEN, D> {
     type Drop = D;
     type Extra = [u8; KEY_LEN];
+
+    fn encrypt(data: &mut [u8], key: &[u8; KEY_LEN]) {
+        apply_keystream_windowed(data, key);
+    }
 }
 
-impl<const KEY_LEN: usize, D: DropStrategy<Extra = [u8; KEY_LEN]>, M, const N: usize>
+impl<const KEY_LEN: usize, D: DropStrategy<Extra = [u8; KEY_LEN]>> StreamDecrypt
+    for Rc4<KEY_LEN, D>
+{
+    type State = StreamState;
+
+    fn start(key: &[u8; KEY_LEN]) -> StreamState {
+        stream_start(key)
+    }
+
+    fn decrypt_byte(state: &mut StreamState, _index: usize, byte: u8) -> u8 {
+        stream_decrypt_byte(state, byte)
+    }
+}
+
+impl<const KEY_LEN: usize, D: DropStrategy<Extra = [u8; KEY_LEN]>, M: Mode, const N: usize>
     Encrypted<Rc4<KEY_LEN, D>, M, N>
 {
     /// Creates a new encrypted buffer using RC4.
@@ -134,47 +273,13 @@ impl<const KEY_LEN: usize, D: DropStrategy<Extra = [u8; KEY_LEN]>, M, const N: u
     /// 1. Runs the Key Scheduling Algorithm (KSA) to initialize the S-box
     /// 2. Runs the Pseudo-Random Generation Algorithm (PRGA) to generate keystream
     /// 3. XORs the keystream with the plaintext
+    ///
+    /// A `const fn` cannot zeroize, so when `new` is called at runtime rather than in a
+    /// `const` or `static` initializer, the S-box is left on the stack. Decryption
+    /// and re-encryption at runtime wipe it.
     pub const fn new(mut buffer: [u8; N], key: [u8; KEY_LEN]) -> Self {
-        // RC4 Key Scheduling Algorithm (KSA) and PRGA combined
-        // We use a fixed 256-byte S-box for simplicity
-        let mut s = [0u8; 256];
-        let mut j: u8 = 0;
-
-        // Initialize S-box
-        let mut i = 0usize;
-        while i < 256 {
-            s[i] = i as u8;
-            i += 1;
-        }
-
-        // KSA: Permute S-box based on key
-        let mut i = 0usize;
-        while i < 256 {
-            let key_byte = key[i % KEY_LEN];
-            j = j.wrapping_add(s[i]).wrapping_add(key_byte);
-            // Swap s[i] and s[j]
-            let temp = s[i];
-            s[i] = s[j as usize];
-            s[j as usize] = temp;
-            i += 1;
-        }
-
-        // PRGA: Generate keystream and encrypt buffer in place
-        let mut i: u8 = 0;
-        j = 0;
-        let mut idx = 0usize;
-        while idx < N {
-            i = i.wrapping_add(1);
-            j = j.wrapping_add(s[i as usize]);
-            // Swap s[i] and s[j]
-            let temp = s[i as usize];
-            s[i as usize] = s[j as usize];
-            s[j as usize] = temp;
-            // Generate keystream byte and XOR with buffer
-            let k = s[(s[i as usize].wrapping_add(s[j as usize])) as usize];
-            buffer[idx] ^= k;
-            idx += 1;
-        }
+        check_plaintext::<M, N>(&buffer);
+        apply_keystream(&mut buffer, &key);
 
         Encrypted {
             buffer: UnsafeCell::new(buffer),
@@ -183,170 +288,69 @@ impl<const KEY_LEN: usize, D: DropStrategy<Extra = [u8; KEY_LEN]>, M, const N: u
             _phantom: PhantomData,
         }
     }
-}
-
-impl<const KEY_LEN: usize, D: DropStrategy<Extra = [u8; KEY_LEN]>, const N: usize> Deref
-    for Encrypted<Rc4<KEY_LEN, D>, ByteArray, N>
-{
-    type Target = [u8; N];
-
-    fn deref(&self) -> &Self::Target {
-        // Fast path: already decrypted
-        if self.decryption_state.load(Ordering::Acquire) == STATE_DECRYPTED {
-            // SAFETY: `buffer` is initialized and lives as long as `self`.
-            return unsafe { &*self.buffer.get() };
-        }
-
-        // Try to acquire the decryption lock by transitioning from UNENCRYPTED to DECRYPTING
-        match self.decryption_state.compare_exchange(
-            STATE_UNENCRYPTED,
-            STATE_DECRYPTING,
-            Ordering::AcqRel,
-            Ordering::Acquire,
-        ) {
-            Ok(_) => {
-                // SAFETY: `buffer` is always initialized and points to valid `[u8; N]`.
-                // We won the race, perform decryption with exclusive mutable access.
-                let data = unsafe { &mut *self.buffer.get() };
-                // Reconstruct RC4 state from stored key and decrypt
-                let key = &self.extra;
-                let mut s = [0u8; 256];
-                let mut j: u8 = 0;
-
-                // Initialize S-box
-                let mut i = 0usize;
-                while i < 256 {
-                    s[i] = i as u8;
-                    i += 1;
-                }
-
-                // KSA
-                let mut i = 0usize;
-                while i < 256 {
-                    j = j.wrapping_add(s[i]).wrapping_add(key[i % KEY_LEN]);
-                    s.swap(i, j as usize);
-                    i += 1;
-                }
-
-                // PRGA: Decrypt
-                let mut i: u8 = 0;
-                j = 0;
-                let mut idx = 0usize;
-                while idx < N {
-                    i = i.wrapping_add(1);
-                    j = j.wrapping_add(s[i as usize]);
-                    s.swap(i as usize, j as usize);
-                    let k = s[(s[i as usize].wrapping_add(s[j as usize])) as usize];
-                    data[idx] ^= k;
-                    idx += 1;
-                }
-
-                // Decryption complete - release lock by transitioning to DECRYPTED
-                // Use Release ordering to ensure all decryption writes are visible to other threads
-                self.decryption_state.store(STATE_DECRYPTED, Ordering::Release);
-            }
-            Err(_) => {
-                // Lost the race - another thread is decrypting
-                // Spin-wait until decryption completes
-                while self.decryption_state.load(Ordering::Acquire) != STATE_DECRYPTED {
-                    core::hint::spin_loop();
-                }
-            }
-        }
 
-        // SAFETY: `buffer` is initialized and lives as long as `self`.
-        // Decryption is complete (either by us or another thread), so it's safe
-        // to return a shared reference.
-        unsafe { &*self.buffer.get() }
+    /// Creates a new encrypted buffer from a hex string of exactly `2 * N` digits.
+    ///
+    /// Upper- and lowercase digits are accepted. In a `const` initializer, a wrong
+    /// length or a non-hex character fails the build. As with [`new`](Self::new), the
+    /// decoded bytes of a [`StringLiteral`](crate::StringLiteral) secret must be valid
+    /// UTF-8.
+    pub const fn from_hex(hex: &str, key: [u8; KEY_LEN]) -> Self {
+        Self::new(decode_hex(hex), key)
     }
-}
 
-impl<const KEY_LEN: usize, D: DropStrategy<Extra = [u8; KEY_LEN]>, const N: usize> Deref
-    for Encrypted<Rc4<KEY_LEN, D>, StringLiteral, N>
-{
-    type Target = str;
-
-    fn deref(&self) -> &Self::Target {
-        // Fast path: already decrypted
-        if self.decryption_state.load(Ordering::Acquire) == STATE_DECRYPTED {
-            // SAFETY: `buffer` is initialized and lives as long as `self`.
-            let bytes = unsafe { &*self.buffer.get() };
-            // SAFETY: Since the original input was a valid UTF-8 string literal, XOR
-            // with RC4 keystream preserves the length, and RC4 is a bijection,
-            // so the resulting bytes will still form a valid UTF-8 string.
-            return unsafe { core::str::from_utf8_unchecked(bytes) };
-        }
-
-        // Try to acquire the decryption lock by transitioning from UNENCRYPTED to DECRYPTING
-        match self.decryption_state.compare_exchange(
-            STATE_UNENCRYPTED,
-            STATE_DECRYPTING,
-            Ordering::AcqRel,
-            Ordering::Acquire,
-        ) {
-            Ok(_) => {
-                // SAFETY: `buffer` is always initialized and points to valid `[u8; N]`.
-                // We won the race, perform decryption with exclusive mutable access.
-                let data = unsafe { &mut *self.buffer.get() };
-                // Reconstruct RC4 state from stored key and decrypt
-                let key = &self.extra;
-                let mut s = [0u8; 256];
-                let mut j: u8 = 0;
-
-                // Initialize S-box
-                let mut i = 0usize;
-                while i < 256 {
-                    s[i] = i as u8;
-                    i += 1;
-                }
-
-                // KSA
-                let mut i = 0usize;
-                while i < 256 {
-                    j = j.wrapping_add(s[i]).wrapping_add(key[i % KEY_LEN]);
-                    s.swap(i, j as usize);
-                    i += 1;
-                }
-
-                // PRGA: Decrypt
-                let mut i: u8 = 0;
-                j = 0;
-                let mut idx = 0usize;
-                while idx < N {
-                    i = i.wrapping_add(1);
-                    j = j.wrapping_add(s[i as usize]);
-                    s.swap(i as usize, j as usize);
-                    let k = s[(s[i as usize].wrapping_add(s[j as usize])) as usize];
-                    data[idx] ^= k;
-                    idx += 1;
-                }
+    /// Creates a new encrypted buffer using RC4, without storing the key.
+    ///
+    /// The plaintext is encrypted at compile time exactly as with [`new`](Self::new),
+    /// but `key` is discarded afterwards, so it does not end up in the binary next to
+    /// the ciphertext. Supply it at runtime with [`Encrypted::set_key`] before the
+    /// first dereference; until then, dereferencing panics and
+    /// [`try_deref`](Encrypted::try_deref) returns `None`.
+    pub const fn new_with_deferred_key(mut buffer: [u8; N], key: [u8; KEY_LEN]) -> Self {
+        check_plaintext::<M, N>(&buffer);
+        apply_keystream(&mut buffer, &key);
 
-                // Decryption complete - release lock by transitioning to DECRYPTED
-                // Use Release ordering to ensure all decryption writes are visible to other threads
-                self.decryption_state.store(STATE_DECRYPTED, Ordering::Release);
-            }
-            Err(_) => {
-                // Lost the race - another thread is decrypting
-                // Spin-wait until decryption completes
-                while self.decryption_state.load(Ordering::Acquire) != STATE_DECRYPTED {
-                    core::hint::spin_loop();
-                }
-            }
+        Encrypted {
+            buffer: UnsafeCell::new(buffer),
+            decryption_state: AtomicU8::new(STATE_KEY_MISSING),
+            extra: [0; KEY_LEN],
+            _phantom: PhantomData,
         }
-
-        // SAFETY: `buffer` is initialized and lives as long as `self`.
-        // Decryption is complete (either by us or another thread), so it's safe
-        // to return a shared reference.
-        let bytes = unsafe { &*self.buffer.get() };
-
-        // SAFETY: Since the original input was a valid UTF-8 string literal, XOR
-        // with RC4 keystream preserves the length, and RC4 is a bijection,
-        // so the resulting bytes will still form a valid UTF-8 string.
-        unsafe { core::str::from_utf8_unchecked(bytes) }
     }
 }
 
+/// [`Rc4`] under the name to reach for with large buffers (kilobytes to megabytes).
+///
+/// This is the same type as [`Rc4`]: its runtime path already generates the keystream
+/// into a 256-byte window and applies it in a separate pass, which is what large
+/// buffers need. The alias exists to carry the notes below.
+///
+/// # Large buffers
+///
+/// RC4's `i`/`j` indices are `u8` by specification, so any `N` is correct. What grows
+/// with `N` is cost:
+///
+/// - **Compile time**: `new` runs RC4 in the const evaluator, which is orders of
+///   magnitude slower than native code. Const-evaluating megabyte blobs can add
+///   minutes to a build and may trip the `long_running_const_eval` lint. For large
+///   assets, prefer sealing at runtime with
+///   [`SealingBuilder`](crate::builder::SealingBuilder).
+/// - **First decryption**: the first dereference decrypts the whole buffer while other
+///   threads wait, in time linear in `N`. RC4 keystream generation is inherently
+///   serial. Measured at 244 µs per 64 KiB (about 255 MiB/s) on one vCPU of an
+///   x86-64 Intel Xeon virtual machine, built with rustc 1.95 in release mode. Measure
+///   your target with
+///   `cargo bench --bench rc4_single_threaded -- rc4_key16_first_decrypt_size_64k`.
+/// - **Stack usage**: `Encrypted` stores the buffer inline, so a large secret declared
+///   as a local lives on the stack. Put it in a `static` or a `Box` instead.
+pub type Rc4Large<const KEY_LEN: usize, D = Zeroize> = Rc4<KEY_LEN, D>;
+
 #[cfg(test)]
+#[allow(
+    clippy::borrow_interior_mutable_const,
+    clippy::declare_interior_mutable_const,
+    clippy::explicit_auto_deref
+)]
 mod tests {
     use super::*;
     use crate::{
@@ -547,8 +551,209 @@ mod tests {
             handle.join().unwrap();
         }
 
-        // After all threads finish and the Arc is dropped, the data should be re-encrypted
-        // (We can't easily test the re-encryption result here, but the test verifies
-        // that ReEncrypt compiles and works with the type system)
+        // `reset` runs the drop strategy without dropping, so the residue is observable.
+        let mut secret = Arc::try_unwrap(shared).ok().unwrap();
+        assert!(secret.is_decrypted());
+        secret.reset();
+        assert!(!secret.is_decrypted());
+        assert_eq!(unsafe { *secret.buffer.get() }, unsafe { *SHARED.buffer.get() });
+        assert_eq!(&*secret, "hello", "a re-encrypted secret decrypts again");
+    }
+
+    #[test]
+    fn test_rc4_zeroize_reset_wipes() {
+        let mut secret = CONST_ENCRYPTED;
+        assert_eq!(&*secret, b"hello");
+        secret.reset();
+        assert!(secret.is_wiped());
+        assert_eq!(unsafe { *secret.buffer.get() }, [0; 5]);
+        assert_eq!(secret.try_deref(), None);
+    }
+
+    #[test]
+    fn test_windowed_keystream_matches_bytewise() {
+        // Lengths around window boundaries, including a partial last window.
+        for len in [0, 1, 255, 256, 257, 511, 512, 1000] {
+            let mut bytewise = vec![0x5Au8; len];
+            let mut windowed = bytewise.clone();
+            apply_keystream(&mut bytewise, &RC4_KEY2);
+            apply_keystream_windowed(&mut windowed, &RC4_KEY2);
+            assert_eq!(bytewise, windowed, "length {len}");
+        }
+    }
+
+    #[test]
+    fn test_rc4_large_round_trip_100k() {
+        const N: usize = 100_000;
+        let plain: Vec<u8> = (0..N).map(|i| (i % 251) as u8).collect();
+        let mut buffer = [0u8; N];
+        buffer.copy_from_slice(&plain);
+
+        let secret =
+            alloc::boxed::Box::new(
+                Encrypted::<Rc4Large<16, Zeroize<[u8; 16]>>, ByteArray, N>::new(buffer, RC4_KEY2),
+            );
+        let keystream = reference_keystream(&RC4_KEY2, N);
+        let raw = unsafe { &*secret.buffer.get() };
+        assert!(raw.iter().zip(&plain).map(|(c, p)| c ^ p).eq(keystream));
+
+        assert_eq!(&secret[..], &plain[..]);
+    }
+
+    /// A textbook RC4 keystream with `usize` indices reduced mod 256, to check the
+    /// `u8` wrap-around in [`apply_keystream`] against.
+    fn reference_keystream(key: &[u8], len: usize) -> Vec<u8> {
+        let mut s: Vec<usize> = (0..256).collect();
+        let mut j = 0;
+        for i in 0..256 {
+            j = (j + s[i] + usize::from(key[i % key.len()])) % 256;
+            s.swap(i, j);
+        }
+        let (mut i, mut j) = (0, 0);
+        (0..len)
+            .map(|_| {
+                i = (i + 1) % 256;
+                j = (j + s[i]) % 256;
+                s.swap(i, j);
+                s[(s[i] + s[j]) % 256] as u8
+            })
+            .collect()
+    }
+
+    /// Checks the ciphertext against the reference keystream and round-trips `N` bytes
+    /// in both modes.
+    fn check_large_round_trip<const N: usize>() {
+        let plain: [u8; N] = core::array::from_fn(|i| b'a' + (i % 26) as u8);
+        let keystream = reference_keystream(&RC4_KEY2, N);
+
+        let bytes = Encrypted::<Rc4<16, Zeroize<[u8; 16]>>, ByteArray, N>::new(plain, RC4_KEY2);
+        let raw = unsafe { *bytes.buffer.get() };
+        assert!(raw.iter().zip(&plain).map(|(c, p)| c ^ p).eq(keystream), "N = {N}");
+        assert_eq!(&*bytes, &plain, "N = {N}");
+
+        let text = Encrypted::<Rc4<16, Zeroize<[u8; 16]>>, StringLiteral, N>::new(plain, RC4_KEY2);
+        assert_eq!(unsafe { *text.buffer.get() }, raw, "N = {N}");
+        assert_eq!(text.as_bytes(), &plain[..], "N = {N}");
+    }
+
+    #[test]
+    fn test_rc4_round_trip_past_256_bytes() {
+        check_large_round_trip::<256>();
+        check_large_round_trip::<257>();
+        check_large_round_trip::<512>();
+        check_large_round_trip::<1000>();
+        check_large_round_trip::<1024>();
+    }
+
+    #[test]
+    fn test_rc4_decrypted_bytes_matches_deref() {
+        let plain: [u8; 600] = core::array::from_fn(|i| (i % 251) as u8);
+        let secret = Encrypted::<Rc4<16, Zeroize<[u8; 16]>>, ByteArray, 600>::new(plain, RC4_KEY2);
+        assert!(secret.decrypted_bytes().eq(plain));
+        assert!(!secret.is_decrypted());
+    }
+
+    #[test]
+    fn test_rc4_with_decrypted_concurrent_with_deref() {
+        let shared = Arc::new(CONST_ENCRYPTED);
+        let mut handles: Vec<thread::JoinHandle<()>> = vec![];
+
+        for i in 0..10 {
+            let shared_clone = Arc::clone(&shared);
+            let handle = thread::spawn(move || {
+                if i % 2 == 0 {
+                    shared_clone.with_decrypted(|plain| assert_eq!(plain, b"hello"));
+                } else {
+                    let decrypted: &[u8; 5] = &*shared_clone;
+                    assert_eq!(decrypted, b"hello");
+                }
+            });
+            handles.push(handle);
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!(shared.is_decrypted());
+    }
+
+    #[test]
+    fn test_rc4_with_decrypted_keeps_sealed() {
+        let encrypted = CONST_ENCRYPTED_16;
+        encrypted.with_decrypted(|plain| assert_eq!(plain, b"longdata"));
+        assert!(!encrypted.is_decrypted());
+        let raw = unsafe { &*encrypted.buffer.get() };
+        assert_ne!(raw, b"longdata");
+    }
+
+    const DEFERRED: Encrypted<Rc4<5, ReEncrypt<5>>, StringLiteral, 5> =
+        Encrypted::<Rc4<5, ReEncrypt<5>>, StringLiteral, 5>::new_with_deferred_key(
+            *b"hello", RC4_KEY,
+        );
+
+    #[test]
+    fn test_deferred_key() {
+        let mut encrypted = DEFERRED;
+        assert_eq!(encrypted.extra, [0; 5], "the key must not be stored");
+        assert!(encrypted.try_deref().is_none());
+
+        let eager = CONST_ENCRYPTED;
+        assert_eq!(unsafe { *encrypted.buffer.get() }, unsafe { *eager.buffer.get() });
+
+        encrypted.set_key(RC4_KEY);
+        assert_eq!(&*encrypted, "hello");
+
+        // ReEncrypt uses the supplied key.
+        encrypted.reset();
+        assert!(!encrypted.is_decrypted());
+        assert_eq!(&*encrypted, "hello");
+    }
+
+    #[test]
+    #[should_panic(expected = "secret key was not set")]
+    fn test_deferred_key_deref_before_set_panics() {
+        let encrypted = DEFERRED;
+        let _ = &*encrypted;
+    }
+
+    #[test]
+    fn test_wrong_deferred_key_is_rejected() {
+        use std::panic::{AssertUnwindSafe, catch_unwind};
+
+        let mut encrypted = DEFERRED;
+        let result = catch_unwind(AssertUnwindSafe(|| encrypted.set_key(*b"wrong")));
+        assert!(result.is_err(), "a key that yields invalid UTF-8 must be rejected");
+        assert_eq!(encrypted.extra, [0; 5], "a rejected key must not be stored");
+
+        // Dereferencing never hands out the garbage a wrong key would produce.
+        assert!(encrypted.try_deref().is_none());
+        let result = catch_unwind(AssertUnwindSafe(|| std::string::String::from(&*encrypted)));
+        assert!(result.is_err(), "dereferencing without a valid key must panic");
+
+        encrypted.set_key(RC4_KEY);
+        assert_eq!(&*encrypted, "hello");
+        assert_eq!(encrypted.try_as_str(), Ok("hello"));
+    }
+
+    #[test]
+    #[should_panic(expected = "secret key was already set")]
+    fn test_set_key_twice_panics() {
+        let mut encrypted = DEFERRED;
+        encrypted.set_key(RC4_KEY);
+        encrypted.set_key(RC4_KEY);
+    }
+
+    const FROM_HEX: Encrypted<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 4> =
+        Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 4>::from_hex("DEADbeef", RC4_KEY);
+
+    #[test]
+    fn test_rc4_from_hex() {
+        let encrypted = FROM_HEX;
+        let expected = Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 4>::new(
+            [0xde, 0xad, 0xbe, 0xef],
+            RC4_KEY,
+        );
+        assert_eq!(unsafe { *encrypted.buffer.get() }, unsafe { *expected.buffer.get() });
+        assert_eq!(&*encrypted, &[0xde, 0xad, 0xbe, 0xef]);
     }
 }
@@ -0,0 +1,143 @@
+//! Secrets whose ciphertext can live in genuinely read-only memory.
+//!
+//! [`Encrypted`] decrypts in place: the first dereference writes the plaintext back
+//! into its buffer through an [`UnsafeCell`](core::cell::UnsafeCell). That is sound
+//! wherever the compiler places the value itself:
+//!
+//! - `static` items containing an `UnsafeCell` are emitted into writable memory
+//!   (`.data`/`.bss`), never `.rodata`.
+//! - `const` items are copied into every use site. The compiler refuses to promote
+//!   values with interior mutability to `'static` read-only allocations, so those
+//!   copies live on the stack.
+//!
+//! It is *not* sound when the placement is forced from outside, for example a
+//! `static` pinned into a read-only section with `#[link_section]`, or a value that a
+//! loader maps from flash without write access. The first dereference would then
+//! fault.
+//!
+//! [`ReadOnly`] covers those cases. It has no interior mutability, so the ciphertext
+//! can be placed in read-only memory, and it never writes to its own storage:
+//! [`ReadOnly::with_decrypted`] decrypts into a stack scratch buffer that is zeroized
+//! as soon as the closure returns.
+//!
+//! # Example
+//!
+//! ```rust
+//! use const_secret::{
+//!     Encrypted, StringLiteral,
+//!     drop_strategy::Zeroize,
+//!     read_only::ReadOnly,
+//!     xor::Xor,
+//! };
+//!
+//! static SECRET: ReadOnly<Xor<0xAA, Zeroize>, StringLiteral, 5> =
+//!     Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello").into_read_only();
+//!
+//! fn main() {
+//!     SECRET.with_decrypted(|plain| assert_eq!(plain, "hello"));
+//! }
+//! ```
+
+use core::{marker::PhantomData, mem::ManuallyDrop, ptr, sync::atomic::AtomicU8};
+
+use crate::{Algorithm, Encrypted, Mode, STATE_UNENCRYPTED, Scratch};
+
+/// Encrypted data that is never decrypted in place.
+///
+/// Created from a sealed [`Encrypted`] with [`Encrypted::into_read_only`]. Because it
+/// holds no plaintext at any point, it does not need a drop strategy.
+pub struct ReadOnly<A: Algorithm, M, const N: usize> {
+    /// The ciphertext. Never written after construction.
+    buffer: [u8; N],
+    /// Algorithm-specific extra data (e.g., the encryption key for RC4).
+    extra: A::Extra,
+    /// Phantom marker to carry the algorithm and mode type information.
+    _phantom: PhantomData<(A, M)>,
+}
+
+impl<A: Algorithm, M, const N: usize> Encrypted<A, M, N> {
+    /// Converts a sealed secret into a [`ReadOnly`] one that decrypts out-of-band.
+    ///
+    /// This is a `const fn`, so it can be chained onto the algorithm constructor to
+    /// initialize a `static` or `const`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret has already been decrypted, since its buffer would then
+    /// hold plaintext rather than ciphertext.
+    pub const fn into_read_only(self) -> ReadOnly<A, M, N> {
+        let this = ManuallyDrop::new(self);
+        let this: *const Self = (&raw const this).cast();
+
+        // SAFETY: `this` points to a live `Encrypted` wrapped in `ManuallyDrop`, so its
+        // destructor never runs and every field is moved out at most once.
+        let state = unsafe { ptr::read(&raw const (*this).decryption_state) };
+        assert!(
+            AtomicU8::into_inner(state) == STATE_UNENCRYPTED,
+            "only a sealed secret can be converted into a read-only secret"
+        );
+
+        // SAFETY: as above.
+        unsafe {
+            ReadOnly {
+                buffer: ptr::read((*this).buffer.get()),
+                extra: ptr::read(&raw const (*this).extra),
+                _phantom: PhantomData,
+            }
+        }
+    }
+}
+
+impl<A: Algorithm, M: Mode, const N: usize> ReadOnly<A, M, N> {
+    /// Decrypts into a stack scratch buffer and passes it to `f`.
+    ///
+    /// `f` receives `&str` for [`StringLiteral`](crate::StringLiteral) and `&[u8; N]`
+    /// for [`ByteArray`](crate::ByteArray). The scratch buffer is zeroized when `f`
+    /// returns (or unwinds). The stored ciphertext is never modified.
+    pub fn with_decrypted<R>(&self, f: impl FnOnce(&M::Target<N>) -> R) -> R {
+        let mut scratch = Scratch(self.buffer);
+        A::decrypt(&mut scratch.0, &self.extra);
+        // SAFETY: the scratch holds the decrypted plaintext of a secret of mode `M`.
+        f(unsafe { M::view(&scratch.0) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ByteArray, StringLiteral, drop_strategy::Zeroize, rc4::Rc4, xor::Xor};
+
+    // Pin the secret into a read-only section: any write to it would fault.
+    #[cfg_attr(target_os = "linux", unsafe(link_section = ".rodata.const_secret_test"))]
+    static READ_ONLY_SECRET: ReadOnly<Xor<0xAA, Zeroize>, StringLiteral, 5> =
+        Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello").into_read_only();
+
+    static READ_ONLY_RC4: ReadOnly<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 4> =
+        Encrypted::<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 4>::new([1, 2, 3, 4], *b"mykey")
+            .into_read_only();
+
+    #[test]
+    fn test_decrypts_from_read_only_section() {
+        READ_ONLY_SECRET.with_decrypted(|plain| assert_eq!(plain, "hello"));
+        // A second access decrypts again from the untouched ciphertext.
+        READ_ONLY_SECRET.with_decrypted(|plain| assert_eq!(plain, "hello"));
+
+        let expected = [b'h' ^ 0xAA, b'e' ^ 0xAA, b'l' ^ 0xAA, b'l' ^ 0xAA, b'o' ^ 0xAA];
+        assert_eq!(READ_ONLY_SECRET.buffer, expected, "ciphertext must stay untouched");
+    }
+
+    #[test]
+    fn test_rc4_read_only() {
+        let sum: u32 =
+            READ_ONLY_RC4.with_decrypted(|plain| plain.iter().map(|&b| u32::from(b)).sum());
+        assert_eq!(sum, 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "only a sealed secret")]
+    fn test_into_read_only_rejects_decrypted() {
+        let secret = Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 2>::new([1, 2]);
+        let _ = &*secret;
+        let _ = secret.into_read_only();
+    }
+}
@@ -0,0 +1,348 @@
+//! `Salsa20` stream cipher algorithm implementation.
+//!
+//! This module provides the original `Salsa20/20` stream cipher as specified in
+//! [the Salsa20 specification](https://cr.yp.to/snuffle/spec.pdf), with a 64-bit nonce
+//! and a 64-bit block counter. Its keystream is identical to the one used by `NaCl`'s
+//! `crypto_stream_salsa20`, so secrets can be checked against systems built on it.
+//! For new designs prefer [`chacha20`](crate::chacha20). As with every algorithm in
+//! this crate, the key ships inside the binary, so this is obfuscation rather than
+//! protection against a determined reverse engineer.
+//!
+//! # Algorithm
+//!
+//! `Salsa20` expands a 256-bit key, a 64-bit nonce and a 64-bit block counter into
+//! 64-byte keystream blocks using 20 rounds (10 double rounds) of add-rotate-xor
+//! quarter rounds. The keystream is XOR'd with the plaintext, so the same operation
+//! encrypts and decrypts. The block counter starts at 0.
+//!
+//! # Types
+//!
+//! - [`Salsa20<D>`](Salsa20): The main algorithm type, generic over drop strategy
+//! - [`ReEncrypt`]: A drop strategy that re-encrypts data on drop
+//!
+//! # Example
+//!
+//! ```rust
+//! use const_secret::{
+//!     Encrypted, StringLiteral,
+//!     drop_strategy::Zeroize,
+//!     salsa20::{Key, Nonce, ReEncrypt, Salsa20},
+//! };
+//!
+//! const KEY: [u8; 32] = *b"an example very very secret key.";
+//! const NONCE: [u8; 8] = *b"nonce!!!";
+//!
+//! // Zeroize on drop (default)
+//! const SECRET: Encrypted<Salsa20<Zeroize<(Key, Nonce)>>, StringLiteral, 5> =
+//!     Encrypted::<Salsa20<Zeroize<(Key, Nonce)>>, StringLiteral, 5>::new(*b"hello", KEY, NONCE);
+//!
+//! // Re-encrypt on drop
+//! const SECRET2: Encrypted<Salsa20<ReEncrypt>, StringLiteral, 6> =
+//!     Encrypted::<Salsa20<ReEncrypt>, StringLiteral, 6>::new(*b"secret", KEY, NONCE);
+//!
+//! fn main() {
+//!     let s1: &str = &*SECRET;
+//!     assert_eq!(s1, "hello");
+//!
+//!     let s2: &str = &*SECRET2;
+//!     assert_eq!(s2, "secret");
+//! }
+//! ```
+
+use core::{cell::UnsafeCell, marker::PhantomData, sync::atomic::AtomicU8};
+
+use crate::{
+    Algorithm, Encrypted, Mode, STATE_UNENCRYPTED, check_plaintext,
+    drop_strategy::{DropStrategy, Residue, Zeroize},
+};
+
+/// A `Salsa20` key.
+pub type Key = [u8; 32];
+/// A `Salsa20` nonce.
+pub type Nonce = [u8; 8];
+
+/// The constant words `"expand 32-byte k"`.
+const SIGMA: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+
+/// Reads a little-endian `u32` from `bytes` at `offset`.
+const fn load_le(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+}
+
+/// The `Salsa20` quarter round, `quarterround(y0, y1, y2, y3)` in the specification.
+///
+/// Exposed for checking against the test vectors in section 3 of the specification.
+#[must_use]
+pub const fn quarter_round(state: [u32; 4]) -> [u32; 4] {
+    let [mut a, mut b, mut c, mut d] = state;
+    b ^= a.wrapping_add(d).rotate_left(7);
+    c ^= b.wrapping_add(a).rotate_left(9);
+    d ^= c.wrapping_add(b).rotate_left(13);
+    a ^= d.wrapping_add(c).rotate_left(18);
+    [a, b, c, d]
+}
+
+/// Applies [`quarter_round`] to words `a`, `b`, `c` and `d` of `state`.
+const fn quarter_round_at(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    let words = quarter_round([state[a], state[b], state[c], state[d]]);
+    state[a] = words[0];
+    state[b] = words[1];
+    state[c] = words[2];
+    state[d] = words[3];
+}
+
+/// The `Salsa20` block function: one 64-byte keystream block for `counter`.
+const fn block(key: &Key, nonce: &Nonce, counter: u64) -> [u8; 64] {
+    // The constants sit on the diagonal, the key around them.
+    let mut initial = [0u32; 16];
+    initial[0] = SIGMA[0];
+    initial[5] = SIGMA[1];
+    initial[10] = SIGMA[2];
+    initial[15] = SIGMA[3];
+    let mut i = 0;
+    while i < 4 {
+        initial[1 + i] = load_le(key, i * 4);
+        initial[11 + i] = load_le(key, 16 + i * 4);
+        i += 1;
+    }
+    initial[6] = load_le(nonce, 0);
+    initial[7] = load_le(nonce, 4);
+    // The counter is split into its two 32-bit halves, low word first.
+    initial[8] = counter as u32;
+    initial[9] = (counter >> 32) as u32;
+
+    // 20 rounds: 10 iterations of a column round followed by a row round
+    let mut state = initial;
+    let mut round = 0;
+    while round < 10 {
+        quarter_round_at(&mut state, 0, 4, 8, 12);
+        quarter_round_at(&mut state, 5, 9, 13, 1);
+        quarter_round_at(&mut state, 10, 14, 2, 6);
+        quarter_round_at(&mut state, 15, 3, 7, 11);
+        quarter_round_at(&mut state, 0, 1, 2, 3);
+        quarter_round_at(&mut state, 5, 6, 7, 4);
+        quarter_round_at(&mut state, 10, 11, 8, 9);
+        quarter_round_at(&mut state, 15, 12, 13, 14);
+        round += 1;
+    }
+
+    // Add the initial state and serialize little-endian
+    let mut out = [0u8; 64];
+    let mut i = 0;
+    while i < 16 {
+        let word = state[i].wrapping_add(initial[i]).to_le_bytes();
+        out[i * 4] = word[0];
+        out[i * 4 + 1] = word[1];
+        out[i * 4 + 2] = word[2];
+        out[i * 4 + 3] = word[3];
+        i += 1;
+    }
+    out
+}
+
+/// Applies the `Salsa20` keystream for `key` and `nonce` to `data` in place.
+///
+/// `Salsa20` XORs the keystream into the buffer, so the same routine encrypts and
+/// decrypts. The final block is truncated when `data.len()` is not a multiple of 64.
+pub(crate) const fn apply_keystream(data: &mut [u8], key: &Key, nonce: &Nonce) {
+    let mut counter = 0u64;
+    let mut offset = 0usize;
+    while offset < data.len() {
+        let keystream = block(key, nonce, counter);
+        let mut i = 0;
+        while i < 64 && offset + i < data.len() {
+            data[offset + i] ^= keystream[i];
+            i += 1;
+        }
+        offset += 64;
+        counter += 1;
+    }
+}
+
+/// Re-encrypts the buffer using `Salsa20` on drop.
+/// This ensures the plaintext never remains in memory after the value is dropped.
+pub struct ReEncrypt;
+
+impl DropStrategy for ReEncrypt {
+    type Extra = (Key, Nonce);
+    const RESIDUE: Residue = Residue::Ciphertext;
+
+    fn drop(data: &mut [u8], extra: &(Key, Nonce)) {
+        // Re-run Salsa20 to re-encrypt the buffer
+        apply_keystream(data, &extra.0, &extra.1);
+    }
+}
+
+/// An algorithm that performs `Salsa20` encryption and decryption.
+/// This algorithm is generic over drop strategy.
+///
+/// The 32-byte key and 8-byte nonce are stored alongside the encrypted data and
+/// are used to reproduce the keystream for decryption at runtime.
+pub struct Salsa20<D: DropStrategy = Zeroize<(Key, Nonce)>>(PhantomData<D>);
+
+impl<D: DropStrategy<Extra = (Key, Nonce)>> Algorithm for Salsa20<D> {
+    type Drop = D;
+    type Extra = (Key, Nonce);
+
+    fn encrypt(data: &mut [u8], extra: &(Key, Nonce)) {
+        apply_keystream(data, &extra.0, &extra.1);
+    }
+}
+
+impl<D: DropStrategy<Extra = (Key, Nonce)>, M: Mode, const N: usize> Encrypted<Salsa20<D>, M, N> {
+    /// Creates a new encrypted buffer using `Salsa20`.
+    ///
+    /// # Arguments
+    /// * `buffer` - The plaintext data to encrypt (must be an array of length N)
+    /// * `key` - The 256-bit `Salsa20` key
+    /// * `nonce` - The 64-bit `Salsa20` nonce
+    ///
+    /// This function performs `Salsa20` encryption at compile time, generating one
+    /// 64-byte keystream block per 64 bytes of plaintext and XOR'ing it in.
+    pub const fn new(mut buffer: [u8; N], key: Key, nonce: Nonce) -> Self {
+        check_plaintext::<M, N>(&buffer);
+        apply_keystream(&mut buffer, &key, &nonce);
+
+        Encrypted {
+            buffer: UnsafeCell::new(buffer),
+            decryption_state: AtomicU8::new(STATE_UNENCRYPTED),
+            extra: (key, nonce),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::borrow_interior_mutable_const,
+    clippy::declare_interior_mutable_const,
+    clippy::explicit_auto_deref
+)]
+mod tests {
+    use super::*;
+    use crate::{ByteArray, StringLiteral, drop_strategy::Zeroize};
+
+    use alloc::vec::Vec;
+
+    /// ECRYPT `Salsa20/20` 256-bit test vectors, set 1, vector 0: key `80 00 .. 00`,
+    /// IV `00 .. 00`. The first 64 bytes of keystream.
+    const ECRYPT_KEY: Key = {
+        let mut key = [0u8; 32];
+        key[0] = 0x80;
+        key
+    };
+    const ECRYPT_NONCE: Nonce = [0; 8];
+    const ECRYPT_KEYSTREAM: [u8; 64] = [
+        0xe3, 0xbe, 0x8f, 0xdd, 0x8b, 0xec, 0xa2, 0xe3, 0xea, 0x8e, 0xf9, 0x47, 0x5b, 0x29, 0xa6,
+        0xe7, 0x00, 0x39, 0x51, 0xe1, 0x09, 0x7a, 0x5c, 0x38, 0xd2, 0x3b, 0x7a, 0x5f, 0xad, 0x9f,
+        0x68, 0x44, 0xb2, 0x2c, 0x97, 0x55, 0x9e, 0x27, 0x23, 0xc7, 0xcb, 0xbd, 0x3f, 0xe4, 0xfc,
+        0x8d, 0x9a, 0x07, 0x44, 0x65, 0x2a, 0x83, 0xe7, 0x2a, 0x9c, 0x46, 0x18, 0x76, 0xaf, 0x4d,
+        0x7e, 0xf1, 0xa1, 0x17,
+    ];
+
+    /// A multi-block message, cross-checked against the `RustCrypto` `salsa20` crate.
+    const KEY: Key = {
+        let mut key = [0u8; 32];
+        let mut i = 0;
+        while i < 32 {
+            key[i] = i as u8;
+            i += 1;
+        }
+        key
+    };
+    const NONCE: Nonce = *b"nacl-iv!";
+    const PLAINTEXT: [u8; 123] = *b"The quick brown fox jumps over the lazy dog, then keeps \
+        running past block boundaries until it reaches the end of message!!";
+    const CIPHERTEXT: [u8; 123] = [
+        0x3e, 0x6f, 0xe0, 0x46, 0xd6, 0x66, 0xcc, 0x8a, 0x68, 0x25, 0x45, 0xae, 0x54, 0xc7, 0x88,
+        0x66, 0xac, 0xae, 0xe5, 0xb0, 0x2a, 0x25, 0xd3, 0x07, 0x27, 0x97, 0x01, 0x3c, 0xb5, 0x23,
+        0xbf, 0x6f, 0x4e, 0x8c, 0x55, 0x14, 0x1e, 0x29, 0xbe, 0xcb, 0xda, 0xa8, 0x97, 0x0e, 0xac,
+        0xdb, 0x06, 0x6d, 0xc3, 0x4a, 0x8b, 0xea, 0xbb, 0x1d, 0x13, 0xd0, 0x92, 0x3e, 0xb0, 0x98,
+        0x38, 0x07, 0xc7, 0x93, 0x15, 0xc6, 0x3d, 0x86, 0x08, 0xdd, 0x34, 0xc9, 0xd7, 0xb8, 0x9d,
+        0xc7, 0x03, 0x77, 0xfd, 0xbc, 0xd5, 0xc3, 0x3c, 0xb9, 0x48, 0x53, 0xcd, 0x94, 0xd2, 0x8c,
+        0x26, 0xf6, 0xc3, 0x7a, 0x2f, 0x21, 0xa8, 0xad, 0x37, 0x0c, 0x86, 0x86, 0x2d, 0x17, 0xb0,
+        0xca, 0x0f, 0x3b, 0xc8, 0xd0, 0x3d, 0x3a, 0xd3, 0x68, 0xd8, 0xbe, 0x19, 0x4c, 0x54, 0xe6,
+        0x4e, 0x2c, 0xa7,
+    ];
+
+    type ZeroizeSalsa = Salsa20<Zeroize<(Key, Nonce)>>;
+
+    const CONST_ENCRYPTED: Encrypted<ZeroizeSalsa, ByteArray, 123> =
+        Encrypted::<ZeroizeSalsa, ByteArray, 123>::new(PLAINTEXT, KEY, NONCE);
+
+    const CONST_ENCRYPTED_STR: Encrypted<ZeroizeSalsa, StringLiteral, 5> =
+        Encrypted::<ZeroizeSalsa, StringLiteral, 5>::new(*b"hello", KEY, NONCE);
+
+    #[test]
+    fn test_quarter_round_spec_vector() {
+        // Salsa20 specification, section 3
+        let mut state = [0u32; 16];
+        state[0] = 0x0000_0001;
+        quarter_round_at(&mut state, 0, 1, 2, 3);
+        assert_eq!(&state[..4], &[0x0800_8145, 0x0000_0080, 0x0001_0200, 0x2050_0000]);
+
+        assert_eq!(quarter_round([0; 4]), [0; 4]);
+        assert_eq!(
+            quarter_round([0, 1, 0, 0]),
+            [0x8800_0100, 0x0000_0001, 0x0000_0200, 0x0040_2000]
+        );
+        assert_eq!(
+            quarter_round([0, 0, 0, 1]),
+            [0x0004_8044, 0x0000_0080, 0x0001_0000, 0x2010_0001]
+        );
+        assert_eq!(
+            quarter_round([0xe7e8_c006, 0xc4f9_417d, 0x6479_b4b2, 0x68c6_7137]),
+            [0xe876_d72b, 0x9361_dfd5, 0xf146_0244, 0x9485_41a3]
+        );
+    }
+
+    #[test]
+    fn test_keystream_matches_ecrypt() {
+        assert_eq!(block(&ECRYPT_KEY, &ECRYPT_NONCE, 0), ECRYPT_KEYSTREAM);
+    }
+
+    #[test]
+    fn test_ciphertext_matches_reference() {
+        let encrypted = CONST_ENCRYPTED;
+        let raw = unsafe { &*encrypted.buffer.get() };
+        assert_eq!(raw, &CIPHERTEXT);
+    }
+
+    #[test]
+    fn test_salsa20_bytearray_deref_decrypts() {
+        let encrypted = CONST_ENCRYPTED;
+        let decrypted: &[u8; 123] = &*encrypted;
+        assert_eq!(decrypted, &PLAINTEXT);
+    }
+
+    #[test]
+    fn test_salsa20_string_deref_decrypts() {
+        let encrypted = CONST_ENCRYPTED_STR;
+        let raw = unsafe { &*encrypted.buffer.get() };
+        assert_ne!(raw, b"hello", "buffer must NOT be plaintext before deref");
+
+        let decrypted: &str = &*encrypted;
+        assert_eq!(decrypted, "hello");
+    }
+
+    #[test]
+    fn test_round_trip_partial_blocks() {
+        for len in [0, 1, 63, 64, 65, 127, 128, 129, 200] {
+            let plain: Vec<u8> = (0..len).map(|i| (i * 7) as u8).collect();
+            let mut data = plain.clone();
+            apply_keystream(&mut data, &KEY, &NONCE);
+            if len > 0 {
+                assert_ne!(data, plain, "length {len}");
+            }
+            apply_keystream(&mut data, &KEY, &NONCE);
+            assert_eq!(data, plain, "length {len}");
+        }
+    }
+
+    #[test]
+    fn test_salsa20_reencrypt_drop() {
+        let mut data = PLAINTEXT;
+        ReEncrypt::drop(&mut data, &(KEY, NONCE));
+        assert_eq!(data, CIPHERTEXT);
+    }
+}
@@ -0,0 +1,250 @@
+//! Persisting secrets with `serde`, without ever writing the plaintext.
+//!
+//! Serializing an [`Encrypted`] writes its ciphertext as bytes. If the secret has
+//! already been decrypted, the plaintext is encrypted again into the output, as with
+//! [`Encrypted::encrypted_bytes`]. Deserializing rebuilds a sealed secret, so it is
+//! encrypted at rest in memory just like a compile-time one, and decrypts on first
+//! access.
+//!
+//! The algorithm's [`Extra`](Algorithm::Extra) data, such as the RC4 key, is never
+//! serialized. Algorithms without any, such as XOR, implement `Deserialize` directly.
+//! For the others, the key is supplied out-of-band with [`WithKey`].
+//!
+//! A deserialized ciphertext is decrypted once into a scratch buffer to check that it
+//! is valid for the mode (UTF-8 for [`StringLiteral`](crate::StringLiteral), a C
+//! string for [`CStrLiteral`](crate::CStrLiteral)). A wrong key or tampered input is
+//! rejected there, instead of producing a `&str` that is not UTF-8.
+//!
+//! # Example
+//!
+//! ```rust
+//! use const_secret::{
+//!     Encrypted, StringLiteral,
+//!     drop_strategy::Zeroize,
+//!     rc4::Rc4,
+//!     serde_support::WithKey,
+//!     xor::Xor,
+//! };
+//! use serde::de::DeserializeSeed;
+//!
+//! type XorSecret = Encrypted<Xor<0xAA, Zeroize>, StringLiteral, 5>;
+//! let json = serde_json::to_string(&XorSecret::new(*b"hello")).unwrap();
+//! let restored: XorSecret = serde_json::from_str(&json).unwrap();
+//! assert_eq!(&*restored, "hello");
+//!
+//! type Rc4Secret = Encrypted<Rc4<5, Zeroize<[u8; 5]>>, StringLiteral, 5>;
+//! let json = serde_json::to_string(&Rc4Secret::new(*b"hello", *b"mykey")).unwrap();
+//! let mut deserializer = serde_json::Deserializer::from_str(&json);
+//! let restored: Rc4Secret = WithKey::new(*b"mykey")
+//!     .deserialize(&mut deserializer)
+//!     .unwrap();
+//! assert_eq!(&*restored, "hello");
+//! ```
+
+use core::{cell::UnsafeCell, fmt, marker::PhantomData, sync::atomic::AtomicU8};
+
+use ::serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{self, DeserializeSeed, SeqAccess, Visitor},
+    ser,
+};
+
+use crate::{
+    Algorithm, Encrypted, Mode, STATE_UNENCRYPTED, STATE_WIPED, Scratch, is_valid_plaintext,
+};
+
+/// Writes the ciphertext as bytes.
+///
+/// Fails for a wiped or poisoned secret, which has no ciphertext left.
+impl<A: Algorithm, M, const N: usize> Serialize for Encrypted<A, M, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (ciphertext, state) = self.sealed_copy();
+        if state == STATE_WIPED {
+            return Err(ser::Error::custom("secret was wiped or poisoned"));
+        }
+        serializer.serialize_bytes(&ciphertext)
+    }
+}
+
+/// Reads the ciphertext of a secret whose algorithm has no extra data.
+impl<'de, A, M: Mode, const N: usize> Deserialize<'de> for Encrypted<A, M, N>
+where
+    A: Algorithm<Extra = ()>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        WithKey::new(()).deserialize(deserializer)
+    }
+}
+
+/// Deserializes an [`Encrypted`] whose key is supplied by the caller.
+///
+/// A [`DeserializeSeed`], for algorithms such as RC4 whose [`Extra`](Algorithm::Extra)
+/// data is managed separately from the ciphertext.
+///
+/// Deserializing panics if the algorithm rejects the key (see
+/// [`Algorithm::check_extra`]). The key comes from the caller, not the input, so this
+/// is a bug in the caller rather than bad data.
+pub struct WithKey<A: Algorithm, M, const N: usize> {
+    /// The key the deserialized secret is sealed under.
+    key: A::Extra,
+    /// Phantom marker to carry the algorithm and mode type information.
+    _phantom: PhantomData<(A, M)>,
+}
+
+impl<A: Algorithm, M, const N: usize> WithKey<A, M, N> {
+    /// Deserializes ciphertext that was encrypted with `key`.
+    pub const fn new(key: A::Extra) -> Self {
+        Self {
+            key,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<A: Algorithm, M, const N: usize> fmt::Debug for WithKey<A, M, N> {
+    /// Formats the seed without revealing the key.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WithKey").finish_non_exhaustive()
+    }
+}
+
+impl<'de, A: Algorithm, M: Mode, const N: usize> DeserializeSeed<'de> for WithKey<A, M, N> {
+    type Value = Encrypted<A, M, N>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        A::check_extra(&self.key);
+        let ciphertext = deserializer.deserialize_bytes(CiphertextVisitor::<N>)?;
+
+        if M::UTF8 || M::NUL_TERMINATED {
+            let mut scratch = Scratch(ciphertext);
+            A::decrypt(&mut scratch.0, &self.key);
+            if !is_valid_plaintext::<M>(&scratch.0) {
+                return Err(de::Error::custom(
+                    "ciphertext does not decrypt to a valid plaintext for this mode",
+                ));
+            }
+        }
+
+        Ok(Encrypted {
+            buffer: UnsafeCell::new(ciphertext),
+            decryption_state: AtomicU8::new(STATE_UNENCRYPTED),
+            extra: self.key,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+/// Accepts exactly `N` bytes, as a byte string or a sequence.
+struct CiphertextVisitor<const N: usize>;
+
+impl<'de, const N: usize> Visitor<'de> for CiphertextVisitor<N> {
+    type Value = [u8; N];
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{N} bytes of ciphertext")
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<[u8; N], E> {
+        bytes.try_into().map_err(|_| E::invalid_length(bytes.len(), &self))
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<[u8; N], S::Error> {
+        let mut ciphertext = [0u8; N];
+        for (len, byte) in ciphertext.iter_mut().enumerate() {
+            *byte = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(len, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(de::Error::invalid_length(N + 1, &self));
+        }
+        Ok(ciphertext)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::explicit_auto_deref)]
+mod tests {
+    use super::*;
+    use crate::{
+        ByteArray, CStrLiteral, StringLiteral,
+        drop_strategy::Zeroize,
+        rc4::Rc4,
+        xor::{Xor, XorRuntime},
+    };
+
+    type XorString = Encrypted<Xor<0xAA, Zeroize>, StringLiteral, 5>;
+    type Rc4Bytes = Encrypted<Rc4<5, Zeroize<[u8; 5]>>, ByteArray, 4>;
+
+    #[test]
+    fn test_xor_round_trip() {
+        let secret = XorString::new(*b"hello");
+        let json = serde_json::to_string(&secret).unwrap();
+        assert!(!json.contains("104"), "the plaintext must not be written");
+
+        let restored: XorString = serde_json::from_str(&json).unwrap();
+        assert!(!restored.is_decrypted());
+        assert_eq!(&*restored, "hello");
+    }
+
+    #[test]
+    fn test_decrypted_secret_serializes_ciphertext() {
+        let secret = XorString::new(*b"hello");
+        let sealed = serde_json::to_string(&secret).unwrap();
+        assert_eq!(&*secret, "hello");
+        assert_eq!(serde_json::to_string(&secret).unwrap(), sealed);
+    }
+
+    #[test]
+    fn test_embedded_in_a_larger_value() {
+        let config = (8080u16, XorString::new(*b"hello"));
+        assert_eq!(&*config.1, "hello");
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(!json.contains("104"), "the plaintext must not be written");
+
+        let (port, secret): (u16, XorString) = serde_json::from_str(&json).unwrap();
+        assert_eq!(port, 8080);
+        assert!(!secret.is_decrypted());
+        assert_eq!(&*secret, "hello");
+    }
+
+    #[test]
+    fn test_rc4_with_key() {
+        let secret = Rc4Bytes::new(*b"data", *b"mykey");
+        let json = serde_json::to_string(&secret).unwrap();
+
+        let mut deserializer = serde_json::Deserializer::from_str(&json);
+        let restored = WithKey::<_, ByteArray, 4>::new(*b"mykey").deserialize(&mut deserializer);
+        let restored: Rc4Bytes = restored.unwrap();
+        assert_eq!(&*restored, b"data");
+    }
+
+    #[test]
+    #[should_panic(expected = "XorRuntime key of 0x00")]
+    fn test_rejected_key_panics() {
+        let json = serde_json::to_string(&[1u8, 2, 3]).unwrap();
+        let mut deserializer = serde_json::Deserializer::from_str(&json);
+        let _ = WithKey::<XorRuntime, ByteArray, 3>::new(0).deserialize(&mut deserializer);
+    }
+
+    #[test]
+    fn test_wrong_length_is_rejected() {
+        assert!(serde_json::from_str::<XorString>("[1,2,3]").is_err());
+        assert!(serde_json::from_str::<XorString>("[1,2,3,4,5,6]").is_err());
+    }
+
+    #[test]
+    fn test_invalid_plaintext_is_rejected() {
+        // 0x55 decrypts to 0xFF, which is not UTF-8.
+        assert!(serde_json::from_str::<XorString>("[85,85,85,85,85]").is_err());
+
+        let missing_nul = "[194,207,198,198,197]";
+        let cstr = serde_json::from_str::<Encrypted<Xor<0xAA, Zeroize>, CStrLiteral, 5>>;
+        assert!(cstr(missing_nul).is_err());
+    }
+
+    #[test]
+    fn test_wiped_secret_does_not_serialize() {
+        let mut secret = XorString::new(*b"hello");
+        secret.zeroize_now();
+        assert!(serde_json::to_string(&secret).is_err());
+    }
+}
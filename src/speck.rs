@@ -0,0 +1,286 @@
+//! SPECK128/256 in CTR mode.
+//!
+//! This module provides the SPECK128/256 lightweight block cipher
+//! ([Beaulieu et al., 2013](https://eprint.iacr.org/2013/404)) in counter mode.
+//! SPECK uses only 64-bit additions, rotations and XORs, with no lookup tables, so
+//! it is small in flash and constant-time on microcontrollers where the RC4 S-box or
+//! the AES tables are too large. CTR mode turns the block cipher into a stream
+//! cipher, so it works with any buffer length `N` without padding and preserves byte
+//! values for [`StringLiteral`](crate::StringLiteral) round-trips.
+//!
+//! # Security Note
+//!
+//! The key is stored alongside the ciphertext in the binary, so this is still
+//! obfuscation: it resists analysis of the ciphertext, not a reverse engineer who
+//! finds the key.
+//!
+//! # Algorithm
+//!
+//! 1. **Key schedule**: the 32-byte key is read as four little-endian 64-bit words
+//!    and expanded into 34 round keys
+//! 2. **Keystream**: a 128-bit little-endian counter, starting at zero, is encrypted
+//!    with 34 SPECK rounds, then incremented
+//! 3. The keystream is XOR'd with the plaintext
+//!
+//! # Types
+//!
+//! - [`Speck<D>`](Speck): The main algorithm type, generic over drop strategy
+//! - [`ReEncrypt`]: A drop strategy that re-encrypts data on drop
+//!
+//! # Example
+//!
+//! ```rust
+//! use const_secret::{
+//!     Encrypted, StringLiteral,
+//!     drop_strategy::Zeroize,
+//!     speck::{Key, ReEncrypt, Speck},
+//! };
+//!
+//! const KEY: Key = *b"a thirty-two byte key for SPECK!";
+//!
+//! // Zeroize on drop (default)
+//! const SECRET: Encrypted<Speck<Zeroize<Key>>, StringLiteral, 5> =
+//!     Encrypted::<Speck<Zeroize<Key>>, StringLiteral, 5>::new(*b"hello", KEY);
+//!
+//! // Re-encrypt on drop
+//! const SECRET2: Encrypted<Speck<ReEncrypt>, StringLiteral, 6> =
+//!     Encrypted::<Speck<ReEncrypt>, StringLiteral, 6>::new(*b"secret", KEY);
+//!
+//! fn main() {
+//!     assert_eq!(&*SECRET, "hello");
+//!     assert_eq!(&*SECRET2, "secret");
+//! }
+//! ```
+
+use core::{cell::UnsafeCell, marker::PhantomData, sync::atomic::AtomicU8};
+
+use crate::{
+    Algorithm, Encrypted, Mode, STATE_UNENCRYPTED, check_plaintext,
+    drop_strategy::{DropStrategy, Residue, Zeroize},
+};
+
+/// A SPECK128/256 key.
+pub type Key = [u8; 32];
+
+/// Number of rounds for SPECK128/256.
+const ROUNDS: usize = 34;
+
+/// Reads the little-endian 64-bit word at `offset`.
+const fn load_u64(bytes: &[u8], offset: usize) -> u64 {
+    let mut word = [0u8; 8];
+    let mut i = 0;
+    while i < 8 {
+        word[i] = bytes[offset + i];
+        i += 1;
+    }
+    u64::from_le_bytes(word)
+}
+
+/// One SPECK round: mixes the round key `k` into the words `x` and `y`.
+const fn round(x: &mut u64, y: &mut u64, k: u64) {
+    *x = x.rotate_right(8).wrapping_add(*y) ^ k;
+    *y = y.rotate_left(3) ^ *x;
+}
+
+/// Expands the key into the 34 round keys.
+///
+/// The key words are `k0, l0, l1, l2` in byte order; the key schedule reuses the
+/// round function, with the round number as the round key.
+const fn expand_key(key: &Key) -> [u64; ROUNDS] {
+    let mut round_keys = [0u64; ROUNDS];
+    let mut k = load_u64(key, 0);
+    let mut l = [load_u64(key, 8), load_u64(key, 16), load_u64(key, 24)];
+    let mut i = 0;
+    while i < ROUNDS {
+        round_keys[i] = k;
+        round(&mut l[i % 3], &mut k, i as u64);
+        i += 1;
+    }
+    round_keys
+}
+
+/// Encrypts one 16-byte block: `y` is bytes 0..8 and `x` bytes 8..16, little-endian.
+const fn encrypt_block(block: &[u8; 16], round_keys: &[u64; ROUNDS]) -> [u8; 16] {
+    let mut y = load_u64(block, 0);
+    let mut x = load_u64(block, 8);
+    let mut i = 0;
+    while i < ROUNDS {
+        round(&mut x, &mut y, round_keys[i]);
+        i += 1;
+    }
+
+    let mut out = [0u8; 16];
+    let (y, x) = (y.to_le_bytes(), x.to_le_bytes());
+    let mut i = 0;
+    while i < 8 {
+        out[i] = y[i];
+        out[8 + i] = x[i];
+        i += 1;
+    }
+    out
+}
+
+/// Applies the SPECK128/256-CTR keystream for `key` to `data` in place.
+///
+/// CTR mode XORs the keystream into the buffer, so the same routine encrypts and
+/// decrypts. The final block is truncated when `data.len()` is not a multiple of 16.
+pub(crate) const fn apply_keystream(data: &mut [u8], key: &Key) {
+    let round_keys = expand_key(key);
+    let mut counter: u128 = 0;
+    let mut offset = 0usize;
+    while offset < data.len() {
+        let keystream = encrypt_block(&counter.to_le_bytes(), &round_keys);
+        let mut i = 0;
+        while i < 16 && offset + i < data.len() {
+            data[offset + i] ^= keystream[i];
+            i += 1;
+        }
+        offset += 16;
+        counter = counter.wrapping_add(1);
+    }
+}
+
+/// Re-encrypts the buffer using SPECK128/256-CTR on drop.
+/// This ensures the plaintext never remains in memory after the value is dropped.
+pub struct ReEncrypt;
+
+impl DropStrategy for ReEncrypt {
+    type Extra = Key;
+    const RESIDUE: Residue = Residue::Ciphertext;
+
+    fn drop(data: &mut [u8], key: &Key) {
+        // Re-run SPECK128/256-CTR to re-encrypt the buffer
+        apply_keystream(data, key);
+    }
+}
+
+/// An algorithm that performs SPECK128/256-CTR encryption and decryption.
+/// This algorithm is generic over drop strategy.
+///
+/// The 32-byte key is stored alongside the encrypted data and is used to reproduce
+/// the keystream for decryption at runtime.
+pub struct Speck<D: DropStrategy = Zeroize<Key>>(PhantomData<D>);
+
+impl<D: DropStrategy<Extra = Key>> Algorithm for Speck<D> {
+    type Drop = D;
+    type Extra = Key;
+
+    fn encrypt(data: &mut [u8], key: &Key) {
+        apply_keystream(data, key);
+    }
+}
+
+impl<D: DropStrategy<Extra = Key>, M: Mode, const N: usize> Encrypted<Speck<D>, M, N> {
+    /// Creates a new encrypted buffer using SPECK128/256-CTR.
+    ///
+    /// # Arguments
+    /// * `buffer` - The plaintext data to encrypt (must be an array of length N)
+    /// * `key` - The SPECK128/256 key
+    ///
+    /// This function performs SPECK128/256-CTR encryption at compile time:
+    /// 1. Expands the key into the round keys
+    /// 2. Encrypts successive counter blocks to generate the keystream
+    /// 3. XORs the keystream with the plaintext
+    pub const fn new(mut buffer: [u8; N], key: Key) -> Self {
+        check_plaintext::<M, N>(&buffer);
+        apply_keystream(&mut buffer, &key);
+
+        Encrypted {
+            buffer: UnsafeCell::new(buffer),
+            decryption_state: AtomicU8::new(STATE_UNENCRYPTED),
+            extra: key,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::borrow_interior_mutable_const,
+    clippy::declare_interior_mutable_const,
+    clippy::explicit_auto_deref
+)]
+mod tests {
+    use super::*;
+    use crate::{ByteArray, StringLiteral, builder::SealingBuilder, drop_strategy::Zeroize};
+
+    use alloc::vec::Vec;
+
+    /// The key from the SPECK128/256 test vector: bytes `00 01 .. 1f`.
+    const SPEC_KEY: Key = {
+        let mut key = [0u8; 32];
+        let mut i = 0;
+        while i < 32 {
+            key[i] = i as u8;
+            i += 1;
+        }
+        key
+    };
+
+    type ZeroizeSpeck = Speck<Zeroize<Key>>;
+
+    const CONST_ENCRYPTED_STR: Encrypted<ZeroizeSpeck, StringLiteral, 5> =
+        Encrypted::<ZeroizeSpeck, StringLiteral, 5>::new(*b"hello", SPEC_KEY);
+
+    #[test]
+    fn test_block_matches_spec_vector() {
+        // Beaulieu et al., appendix C: plaintext "pooner. In those", with the words
+        // (x, y) = (0x65736f6874206e49, 0x202e72656e6f6f70) stored y first.
+        let plaintext = *b"pooner. In those";
+        let expected = [
+            0x43, 0x8f, 0x18, 0x9c, 0x8d, 0xb4, 0xee, 0x4e, 0x3e, 0xf5, 0xc0, 0x05, 0x04, 0x01,
+            0x09, 0x41,
+        ];
+        assert_eq!(encrypt_block(&plaintext, &expand_key(&SPEC_KEY)), expected);
+    }
+
+    #[test]
+    fn test_speck_string_deref_decrypts() {
+        let encrypted = CONST_ENCRYPTED_STR;
+        let raw = unsafe { &*encrypted.buffer.get() };
+        assert_ne!(raw, b"hello", "buffer must NOT be plaintext before deref");
+        assert_eq!(&*encrypted, "hello");
+    }
+
+    #[test]
+    fn test_first_block_is_encrypted_zero_counter() {
+        let encrypted = Encrypted::<ZeroizeSpeck, ByteArray, 16>::new([0; 16], SPEC_KEY);
+        let raw = unsafe { *encrypted.buffer.get() };
+        assert_eq!(raw, encrypt_block(&[0; 16], &expand_key(&SPEC_KEY)));
+    }
+
+    #[test]
+    fn test_round_trip_partial_blocks() {
+        for len in [0, 1, 15, 16, 17, 33, 100] {
+            let plain: Vec<u8> = (0..len).map(|i| (i * 13) as u8).collect();
+            let mut data = plain.clone();
+            apply_keystream(&mut data, &SPEC_KEY);
+            apply_keystream(&mut data, &SPEC_KEY);
+            assert_eq!(data, plain, "length {len}");
+        }
+    }
+
+    #[test]
+    fn test_speck_reencrypt_drop() {
+        let mut encrypted = Encrypted::<Speck<ReEncrypt>, ByteArray, 20>::new([7; 20], SPEC_KEY);
+        let sealed = unsafe { *encrypted.buffer.get() };
+        assert_eq!(&*encrypted, &[7; 20]);
+
+        encrypted.reset();
+        assert_eq!(unsafe { *encrypted.buffer.get() }, sealed);
+    }
+
+    #[test]
+    fn test_runtime_sealing_matches_const_new() {
+        let mut builder: SealingBuilder<ZeroizeSpeck, ByteArray, 20> =
+            Encrypted::with_capacity_sealed();
+        builder.push_segment(b"crosses a ").unwrap();
+        builder.push_segment(b"block end!").unwrap();
+        let sealed = builder.finish(SPEC_KEY).unwrap();
+
+        let expected =
+            Encrypted::<ZeroizeSpeck, ByteArray, 20>::new(*b"crosses a block end!", SPEC_KEY);
+        assert_eq!(unsafe { *sealed.buffer.get() }, unsafe { *expected.buffer.get() });
+        assert_eq!(&*sealed, b"crosses a block end!");
+    }
+}
@@ -0,0 +1,259 @@
+//! Waiting for another thread to finish decrypting.
+//!
+//! When several threads dereference the same secret at once, one of them decrypts and
+//! the others wait. They spin with [`core::hint::spin_loop`] for up to [`SPIN_LIMIT`]
+//! iterations and then stop spinning, so that a decrypting thread which was preempted
+//! gets the CPU back. On a single core, or under a cooperative scheduler, spinning
+//! alone would never let it finish.
+//!
+//! With the `std` feature, waiting threads then block on a condition variable until
+//! the decryption lock is released, so a slow decryption does not keep a core busy.
+//! Without it, how to yield depends on the platform, so it is configurable with
+//! [`set_yield_hook`]; without a hook, waiting threads keep spinning. An installed
+//! hook is used instead of blocking under `std` too.
+//!
+//! The `parking_lot` feature blocks on `parking_lot`'s mutex and condition variable
+//! instead of the standard library's, which park threads more cheaply under contention.
+//!
+//! # Example
+//!
+//! ```rust
+//! fn yield_to_scheduler() {
+//!     // e.g. `embassy_futures::yield_now`, `vTaskDelay(0)` or `sched_yield()`
+//! }
+//!
+//! const_secret::spin::set_yield_hook(Some(yield_to_scheduler));
+//! ```
+
+use core::{
+    ptr,
+    sync::atomic::{AtomicPtr, AtomicU8, Ordering},
+};
+
+/// How many times a waiting thread spins before it blocks or yields.
+pub const SPIN_LIMIT: u32 = 64;
+
+/// The hook installed by [`set_yield_hook`], or null.
+static YIELD_HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Sets the function waiting threads call to yield once they have spun
+/// [`SPIN_LIMIT`] times, and returns the hook it replaces. `None` restores the
+/// default, which blocks under `std`.
+///
+/// The hook is global and may be called from any thread that dereferences a secret,
+/// so it must be cheap and must not dereference a secret itself.
+pub fn set_yield_hook(hook: Option<fn()>) -> Option<fn()> {
+    let hook = hook.map_or(ptr::null_mut(), |hook| hook as *mut ());
+    let previous = YIELD_HOOK.swap(hook, Ordering::AcqRel);
+    // SAFETY: the only non-null values stored are `fn()` pointers cast above.
+    (!previous.is_null()).then(|| unsafe { core::mem::transmute::<*mut (), fn()>(previous) })
+}
+
+/// Gives up the CPU while `state` is locked, using the configured hook or the default
+/// for the platform.
+fn yield_now(state: &AtomicU8) {
+    let hook = YIELD_HOOK.load(Ordering::Acquire);
+    if !hook.is_null() {
+        // SAFETY: the only non-null values stored are `fn()` pointers cast in
+        // `set_yield_hook`.
+        let hook = unsafe { core::mem::transmute::<*mut (), fn()>(hook) };
+        hook();
+        return;
+    }
+
+    #[cfg(feature = "std")]
+    parking::wait_while_locked(state);
+
+    #[cfg(not(feature = "std"))]
+    {
+        let _ = state;
+        core::hint::spin_loop();
+    }
+}
+
+/// Releases the decryption lock of a secret by storing `new` into its `state`, and
+/// wakes the threads blocked on it.
+pub(crate) fn unlock(state: &AtomicU8, new: u8) {
+    state.store(new, Ordering::Release);
+
+    #[cfg(feature = "std")]
+    parking::wake_all();
+}
+
+/// Blocking waits, shared by every secret.
+///
+/// A secret stays a bare `AtomicU8` so that it can be built in a `const`, so all
+/// waiters block on one global condition variable. Decryption is rare, so a wakeup
+/// meant for another secret only costs a recheck.
+#[cfg(feature = "std")]
+mod parking {
+    use core::{
+        sync::atomic::{AtomicU8, AtomicUsize, Ordering, fence},
+        time::Duration,
+    };
+    #[cfg(feature = "parking_lot")]
+    use parking_lot::{Condvar, Mutex, MutexGuard};
+    #[cfg(not(feature = "parking_lot"))]
+    use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+
+    use crate::STATE_DECRYPTING;
+
+    static LOCK: Mutex<()> = Mutex::new(());
+    static RELEASED: Condvar = Condvar::new();
+    /// The number of threads between checking a state and being woken.
+    static WAITERS: AtomicUsize = AtomicUsize::new(0);
+
+    /// A wait never lasts longer than this, in case the state was changed without
+    /// going through [`unlock`](super::unlock).
+    const TIMEOUT: Duration = Duration::from_millis(10);
+
+    /// Takes [`LOCK`]. Nothing panics while holding it, so poisoning is ignored.
+    #[cfg(not(feature = "parking_lot"))]
+    fn lock() -> MutexGuard<'static, ()> {
+        LOCK.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Takes [`LOCK`].
+    #[cfg(feature = "parking_lot")]
+    fn lock() -> MutexGuard<'static, ()> {
+        LOCK.lock()
+    }
+
+    /// Releases `guard` until [`RELEASED`] is notified or [`TIMEOUT`] passes.
+    #[cfg(not(feature = "parking_lot"))]
+    fn wait(guard: MutexGuard<'static, ()>) -> MutexGuard<'static, ()> {
+        RELEASED.wait_timeout(guard, TIMEOUT).unwrap_or_else(PoisonError::into_inner).0
+    }
+
+    /// Releases `guard` until [`RELEASED`] is notified or [`TIMEOUT`] passes.
+    #[cfg(feature = "parking_lot")]
+    fn wait(mut guard: MutexGuard<'static, ()>) -> MutexGuard<'static, ()> {
+        RELEASED.wait_for(&mut guard, TIMEOUT);
+        guard
+    }
+
+    /// Blocks until a lock is released, if `state` is still locked.
+    pub(super) fn wait_while_locked(state: &AtomicU8) {
+        let guard = lock();
+        WAITERS.fetch_add(1, Ordering::Relaxed);
+        // Pairs with the fence in `wake_all`: either the waker sees this waiter, or
+        // this waiter sees the released state.
+        fence(Ordering::SeqCst);
+        if state.load(Ordering::Acquire) == STATE_DECRYPTING {
+            drop(wait(guard));
+        } else {
+            drop(guard);
+        }
+        WAITERS.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Wakes every blocked waiter, after a lock was released.
+    pub(super) fn wake_all() {
+        fence(Ordering::SeqCst);
+        if WAITERS.load(Ordering::Relaxed) != 0 {
+            // Taking the lock waits for a waiter that saw the old state to block.
+            drop(lock());
+            RELEASED.notify_all();
+        }
+    }
+}
+
+/// Counts the spins of one wait for the decryption lock.
+pub(crate) struct Backoff {
+    spins: u32,
+}
+
+impl Backoff {
+    pub(crate) const fn new() -> Self {
+        Self {
+            spins: 0,
+        }
+    }
+
+    /// Waits a little before the lock on `state` is checked again.
+    pub(crate) fn snooze(&mut self, state: &AtomicU8) {
+        if self.spins < SPIN_LIMIT {
+            self.spins += 1;
+            core::hint::spin_loop();
+        } else {
+            yield_now(state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ByteArray, Encrypted, STATE_DECRYPTING, STATE_UNENCRYPTED, xor::Xor};
+    use core::sync::atomic::{AtomicBool, AtomicUsize};
+
+    // The hook is global, so a single test installs it and checks everything. Other
+    // tests may yield through it too, so counts are only ever compared as lower bounds,
+    // and the hook only touches statics. Tests in this module that must not run into
+    // the hook take `HOOK_LOCK`.
+    static HOOK_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    static YIELDS: AtomicUsize = AtomicUsize::new(0);
+    /// A secret that the test forces into DECRYPTING, for the hook to release.
+    static STUCK: Encrypted<Xor<0xAA>, ByteArray, 3> =
+        Encrypted::<Xor<0xAA>, ByteArray, 3>::new([1, 2, 3]);
+    /// Set while `STUCK` waits for the hook, so it is released exactly once.
+    static RELEASE: AtomicBool = AtomicBool::new(false);
+
+    fn counting_hook() {
+        YIELDS.fetch_add(1, Ordering::SeqCst);
+        if RELEASE.swap(false, Ordering::SeqCst) {
+            STUCK.decryption_state.store(STATE_UNENCRYPTED, Ordering::Release);
+        }
+    }
+
+    #[test]
+    fn test_yields_after_spin_limit() {
+        let _lock = HOOK_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let previous = set_yield_hook(Some(counting_hook));
+
+        let before = YIELDS.load(Ordering::SeqCst);
+        let state = AtomicU8::new(STATE_DECRYPTING);
+        let mut backoff = Backoff::new();
+        for _ in 0..=SPIN_LIMIT {
+            backoff.snooze(&state);
+        }
+        assert!(YIELDS.load(Ordering::SeqCst) > before);
+
+        // A secret stuck in DECRYPTING is only released by the hook, so this
+        // dereference would spin forever if the hook were never called.
+        STUCK.decryption_state.store(STATE_DECRYPTING, Ordering::Release);
+        RELEASE.store(true, Ordering::SeqCst);
+        assert_eq!(&*STUCK, &[1, 2, 3]);
+        assert!(!RELEASE.load(Ordering::SeqCst));
+
+        assert!(set_yield_hook(previous).is_some(), "the replaced hook is returned");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_slow_decryption_blocks_waiters() {
+        use std::{thread, time::Duration};
+
+        let _lock = HOOK_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let state = AtomicU8::new(STATE_DECRYPTING);
+
+        let checks = thread::scope(|scope| {
+            scope.spawn(|| {
+                thread::sleep(Duration::from_millis(200));
+                unlock(&state, STATE_UNENCRYPTED);
+            });
+
+            let mut checks = 0u32;
+            let mut backoff = Backoff::new();
+            while state.load(Ordering::Acquire) == STATE_DECRYPTING {
+                checks += 1;
+                backoff.snooze(&state);
+            }
+            checks
+        });
+
+        // Yielding would recheck the state tens of thousands of times in 200 ms.
+        // Blocking rechecks it once per timeout at most, plus spurious wakeups.
+        assert!(checks < SPIN_LIMIT + 100, "waiter rechecked {checks} times");
+    }
+}
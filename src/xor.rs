@@ -10,10 +10,24 @@
 //! byte of the plaintext. The same operation is used for both encryption
 //! and decryption (XOR is its own inverse).
 //!
+//! A single-byte key leaves an obvious repeating mask in the ciphertext. The
+//! [`XorN`] algorithm instead cycles through a `[u8; KEY_LEN]` key
+//! (`buffer[i] ^= key[i % KEY_LEN]`), which stops trivial single-byte key recovery
+//! while staying lighter than RC4. In between, [`Xor2`] alternates two const
+//! generic keys over even and odd positions, breaking the single-mask pattern with
+//! nothing stored at runtime.
+//!
 //! # Types
 //!
 //! - [`Xor<KEY, D>`]: The main algorithm type with const generic key and drop strategy
 //! - [`ReEncrypt<KEY>`]: A drop strategy that re-encrypts data on drop
+//! - [`XorN<KEY_LEN, D>`]: Repeating multi-byte key stored alongside the buffer
+//! - [`ReEncryptN<KEY_LEN>`]: A drop strategy that re-encrypts [`XorN`] data on drop
+//! - [`XorMulti<KEY_LEN, D>`]: An alias of [`XorN`]
+//! - [`Xor2<KEY_EVEN, KEY_ODD, D>`]: Separate const generic keys for even and odd bytes
+//! - [`ReEncrypt2<KEY_EVEN, KEY_ODD>`]: A drop strategy that re-encrypts [`Xor2`] data on drop
+//! - [`XorRuntime<D>`]: Single-byte key chosen at runtime and stored alongside the buffer
+//! - [`ReEncryptRuntime`]: A drop strategy that re-encrypts [`XorRuntime`] data on drop
 //!
 //! # Example
 //!
@@ -41,27 +55,22 @@
 //! }
 //! ```
 
-use core::{
-    cell::UnsafeCell,
-    marker::PhantomData,
-    ops::Deref,
-    sync::atomic::{AtomicU8, Ordering},
-};
+use core::{cell::UnsafeCell, marker::PhantomData, sync::atomic::AtomicU8};
 
 use crate::{
-    Algorithm, ByteArray, Encrypted, STATE_DECRYPTED, STATE_DECRYPTING, STATE_UNENCRYPTED,
-    StringLiteral,
-    drop_strategy::{DropStrategy, Zeroize},
+    Algorithm, Encrypted, Mode, STATE_UNENCRYPTED, Scratch, StreamDecrypt, check_plaintext,
+    decode_hex,
+    drop_strategy::{DropStrategy, Residue, Zeroize},
+    is_valid_plaintext,
 };
 
 pub struct ReEncrypt<const KEY: u8>;
 
 impl<const KEY: u8> DropStrategy for ReEncrypt<KEY> {
     type Extra = ();
+    const RESIDUE: Residue = Residue::Ciphertext;
     fn drop(data: &mut [u8], _extra: &()) {
-        for byte in data {
-            *byte ^= KEY;
-        }
+        xor_in_place(data, KEY);
     }
 }
 
@@ -69,13 +78,124 @@ impl<const KEY: u8> DropStrategy for ReEncrypt<KEY> {
 /// This algorithm is generic over drop strategy.
 pub struct Xor<const KEY: u8, D: DropStrategy = Zeroize>(PhantomData<D>);
 
+impl<const KEY: u8> Xor<KEY> {
+    /// Returns the ciphertext that `Encrypted::new` stores for `plaintext` under `KEY`,
+    /// whatever the drop strategy.
+    ///
+    /// Lets a test check that a secret really was encrypted at compile time:
+    ///
+    /// ```rust
+    /// use const_secret::{Encrypted, StringLiteral, drop_strategy::Zeroize, xor::Xor};
+    ///
+    /// const SECRET: Encrypted<Xor<0xAA, Zeroize>, StringLiteral, 5> =
+    ///     Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+    /// const EXPECTED: [u8; 5] = Xor::<0xAA>::expected_ciphertext(*b"hello");
+    ///
+    /// let secret = SECRET;
+    /// assert_eq!(secret.encrypted_bytes(), EXPECTED);
+    /// assert_ne!(EXPECTED, *b"hello");
+    /// ```
+    #[must_use]
+    pub const fn expected_ciphertext<const N: usize>(mut plaintext: [u8; N]) -> [u8; N] {
+        check_key::<KEY>();
+        let mut i = 0;
+        while i < N {
+            plaintext[i] ^= KEY;
+            i += 1;
+        }
+        plaintext
+    }
+}
+
 impl<const KEY: u8, D: DropStrategy<Extra = ()>> Algorithm for Xor<KEY, D> {
     type Drop = D;
     type Extra = ();
+
+    fn encrypt(data: &mut [u8], _extra: &()) {
+        check_key::<KEY>();
+        xor_in_place(data, KEY);
+    }
+}
+
+impl<const KEY: u8, D: DropStrategy<Extra = ()>> StreamDecrypt for Xor<KEY, D> {
+    type State = [u8; 0];
+
+    fn start(_extra: &()) -> [u8; 0] {
+        []
+    }
+
+    fn decrypt_byte(_state: &mut [u8; 0], _index: usize, byte: u8) -> u8 {
+        byte ^ KEY
+    }
+
+    fn skip(_state: &mut [u8; 0], _count: usize) {}
+}
+
+/// Rejects the key `0x00`, which leaves the plaintext unchanged, at compile time.
+const fn check_key<const KEY: u8>() {
+    const { assert!(KEY != 0x00, "XOR key of 0x00 is a no-op and provides no obfuscation") };
+}
+
+/// Buffers at least this long take the SIMD path, when it is enabled and supported.
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+const SIMD_THRESHOLD: usize = 64;
+
+/// XORs every byte of `data` with `key` at runtime.
+///
+/// With the `simd` feature on `x86_64`, long buffers are processed 32 bytes at a time
+/// with AVX2 if the CPU supports it. Otherwise this is a scalar loop, which the
+/// compiler is free to vectorize with the instructions it may assume at build time.
+fn xor_in_place(data: &mut [u8], key: u8) {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if data.len() >= SIMD_THRESHOLD && std::is_x86_feature_detected!("avx2") {
+        // SAFETY: AVX2 support was just detected.
+        unsafe { simd::xor_avx2(data, key) };
+        return;
+    }
+
+    for byte in data {
+        *byte ^= key;
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod simd {
+    use core::arch::x86_64::{
+        __m256i, _mm256_loadu_si256, _mm256_set1_epi8, _mm256_storeu_si256, _mm256_xor_si256,
+    };
+
+    /// XORs `data` with `key` 32 bytes at a time, then the tail byte by byte.
+    #[target_feature(enable = "avx2")]
+    pub(super) fn xor_avx2(data: &mut [u8], key: u8) {
+        let mask = _mm256_set1_epi8(i8::from_ne_bytes([key]));
+        let mut chunks = data.chunks_exact_mut(32);
+        for chunk in &mut chunks {
+            let lane = chunk.as_mut_ptr().cast::<__m256i>();
+            // SAFETY: `chunk` is 32 bytes, and the unaligned load and store need no
+            // particular alignment.
+            unsafe { _mm256_storeu_si256(lane, _mm256_xor_si256(_mm256_loadu_si256(lane), mask)) };
+        }
+        for byte in chunks.into_remainder() {
+            *byte ^= key;
+        }
+    }
 }
 
-impl<const KEY: u8, D: DropStrategy<Extra = ()>, M, const N: usize> Encrypted<Xor<KEY, D>, M, N> {
+impl<const KEY: u8, D: DropStrategy<Extra = ()>, M: Mode, const N: usize>
+    Encrypted<Xor<KEY, D>, M, N>
+{
+    /// Creates a new encrypted buffer by XOR-ing every byte with `KEY`.
+    ///
+    /// A key of `0x00` would leave the plaintext in the binary, so it fails the build:
+    ///
+    /// ```compile_fail
+    /// use const_secret::{ByteArray, Encrypted, drop_strategy::Zeroize, xor::Xor};
+    ///
+    /// let _ = Encrypted::<Xor<0x00, Zeroize>, ByteArray, 3>::new(*b"abc");
+    /// ```
     pub const fn new(mut buffer: [u8; N]) -> Self {
+        check_key::<KEY>();
+        check_plaintext::<M, N>(&buffer);
         // We use a while loop because const contexts do not allow for-loops.
         let mut i = 0;
         while i < N {
@@ -90,108 +210,360 @@ impl<const KEY: u8, D: DropStrategy<Extra = ()>, M, const N: usize> Encrypted<Xo
             _phantom: PhantomData,
         }
     }
+
+    /// Creates a new encrypted buffer from a hex string of exactly `2 * N` digits.
+    ///
+    /// Upper- and lowercase digits are accepted. In a `const` initializer, a wrong
+    /// length or a non-hex character fails the build:
+    ///
+    /// ```compile_fail
+    /// use const_secret::{ByteArray, Encrypted, drop_strategy::Zeroize, xor::Xor};
+    ///
+    /// const ODD: Encrypted<Xor<0xAA, Zeroize>, ByteArray, 2> =
+    ///     Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 2>::from_hex("abc");
+    /// # let _ = &*ODD;
+    /// ```
+    ///
+    /// As with [`new`](Self::new), the decoded bytes of a
+    /// [`StringLiteral`](crate::StringLiteral) secret must be valid UTF-8.
+    pub const fn from_hex(hex: &str) -> Self {
+        Self::new(decode_hex(hex))
+    }
 }
 
-impl<const KEY: u8, D: DropStrategy<Extra = ()>, const N: usize> Deref
-    for Encrypted<Xor<KEY, D>, ByteArray, N>
-{
-    type Target = [u8; N];
+/// XORs `data` in place with `key`, repeating the key as needed.
+const fn apply_repeating_key<const KEY_LEN: usize>(data: &mut [u8], key: &[u8; KEY_LEN]) {
+    const { assert!(KEY_LEN > 0, "XorN key must not be empty") };
 
-    fn deref(&self) -> &Self::Target {
-        // Fast path: already decrypted
-        if self.decryption_state.load(Ordering::Acquire) == STATE_DECRYPTED {
-            // SAFETY: `buffer` is initialized and lives as long as `self`.
-            return unsafe { &*self.buffer.get() };
+    // We use a while loop because const contexts do not allow for-loops.
+    let mut i = 0;
+    while i < data.len() {
+        data[i] ^= key[i % KEY_LEN];
+        i += 1;
+    }
+}
+
+/// Rejects keys that leave a single-byte pattern (or none) in the ciphertext: all
+/// zeros, or the same byte repeated. Fails the build when called in a `const`.
+const fn check_repeating_key<const KEY_LEN: usize>(key: &[u8; KEY_LEN]) {
+    let mut distinct = false;
+    let mut i = 1;
+    while i < KEY_LEN {
+        if key[i] != key[0] {
+            distinct = true;
         }
+        i += 1;
+    }
+    assert!(
+        distinct || KEY_LEN == 0 || key[0] != 0,
+        "XorN key of all zeros is a no-op and provides no obfuscation"
+    );
+    assert!(
+        distinct || KEY_LEN < 2,
+        "XorN key repeats a single byte; use Xor, or a key with distinct bytes"
+    );
+}
 
-        // Try to acquire the decryption lock by transitioning from UNENCRYPTED to DECRYPTING
-        match self.decryption_state.compare_exchange(
-            STATE_UNENCRYPTED,
-            STATE_DECRYPTING,
-            Ordering::AcqRel,
-            Ordering::Acquire,
-        ) {
-            Ok(_) => {
-                // SAFETY: `buffer` is always initialized and points to valid `[u8; N]`.
-                // We won the race, perform decryption with exclusive mutable access.
-                let data = unsafe { &mut *self.buffer.get() };
-                for byte in data.iter_mut() {
-                    *byte ^= KEY;
-                }
+/// Re-encrypts [`XorN`] data with its repeating key on drop.
+pub struct ReEncryptN<const KEY_LEN: usize>;
 
-                // Decryption complete - release lock by transitioning to DECRYPTED
-                // Use Release ordering to ensure all decryption writes are visible to other threads
-                self.decryption_state.store(STATE_DECRYPTED, Ordering::Release);
-            }
-            Err(_) => {
-                // Lost the race - another thread is decrypting
-                // Spin-wait until decryption completes
-                while self.decryption_state.load(Ordering::Acquire) != STATE_DECRYPTED {
-                    core::hint::spin_loop();
-                }
-            }
+impl<const KEY_LEN: usize> DropStrategy for ReEncryptN<KEY_LEN> {
+    type Extra = [u8; KEY_LEN];
+    const RESIDUE: Residue = Residue::Ciphertext;
+
+    fn drop(data: &mut [u8], key: &[u8; KEY_LEN]) {
+        apply_repeating_key(data, key);
+    }
+}
+
+/// An algorithm that performs repeating-key XOR encryption and decryption.
+/// This algorithm is generic over drop strategy.
+///
+/// The key is a `[u8; KEY_LEN]` stored alongside the encrypted data, and byte `i` of
+/// the buffer is XOR'd with `key[i % KEY_LEN]`.
+///
+/// An empty key (`KEY_LEN == 0`) is rejected at compile time:
+///
+/// ```compile_fail
+/// use const_secret::{ByteArray, Encrypted, drop_strategy::Zeroize, xor::XorN};
+///
+/// let _ = Encrypted::<XorN<0, Zeroize<[u8; 0]>>, ByteArray, 4>::new(*b"data", []);
+/// ```
+pub struct XorN<const KEY_LEN: usize, D: DropStrategy = Zeroize<[u8; KEY_LEN]>>(PhantomData<D>);
+
+/// Alias of [`XorN`], for code that refers to repeating-key XOR by this name.
+pub type XorMulti<const KEY_LEN: usize, D = Zeroize<[u8; KEY_LEN]>> = XorN<KEY_LEN, D>;
+
+impl<const KEY_LEN: usize, D: DropStrategy<Extra = [u8; KEY_LEN]>> Algorithm for XorN<KEY_LEN, D> {
+    type Drop = D;
+    type Extra = [u8; KEY_LEN];
+
+    fn encrypt(data: &mut [u8], key: &[u8; KEY_LEN]) {
+        apply_repeating_key(data, key);
+    }
+
+    fn check_extra(key: &[u8; KEY_LEN]) {
+        check_repeating_key(key);
+    }
+}
+
+impl<const KEY_LEN: usize, D: DropStrategy<Extra = [u8; KEY_LEN]>> StreamDecrypt
+    for XorN<KEY_LEN, D>
+{
+    type State = [u8; KEY_LEN];
+
+    fn start(key: &[u8; KEY_LEN]) -> [u8; KEY_LEN] {
+        *key
+    }
+
+    fn decrypt_byte(key: &mut [u8; KEY_LEN], index: usize, byte: u8) -> u8 {
+        byte ^ key[index % KEY_LEN]
+    }
+
+    fn skip(_key: &mut [u8; KEY_LEN], _count: usize) {}
+}
+
+impl<const KEY_LEN: usize, D: DropStrategy<Extra = [u8; KEY_LEN]>, M: Mode, const N: usize>
+    Encrypted<XorN<KEY_LEN, D>, M, N>
+{
+    /// Creates a new encrypted buffer using repeating-key XOR.
+    ///
+    /// # Arguments
+    /// * `buffer` - The plaintext data to encrypt (must be an array of length N)
+    /// * `key` - The XOR key, cycled over the buffer
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is all zeros or repeats a single byte, which fails the build
+    /// in a `const` initializer.
+    pub const fn new(mut buffer: [u8; N], key: [u8; KEY_LEN]) -> Self {
+        check_repeating_key(&key);
+        check_plaintext::<M, N>(&buffer);
+        apply_repeating_key(&mut buffer, &key);
+
+        Encrypted {
+            buffer: UnsafeCell::new(buffer),
+            decryption_state: AtomicU8::new(STATE_UNENCRYPTED),
+            extra: key,
+            _phantom: PhantomData,
         }
+    }
+}
+
+/// XORs even-indexed bytes of `data` with `KEY_EVEN` and odd-indexed bytes with
+/// `KEY_ODD`.
+const fn apply_two_byte_key<const KEY_EVEN: u8, const KEY_ODD: u8>(data: &mut [u8]) {
+    let mut i = 0;
+    while i < data.len() {
+        data[i] ^= if i.is_multiple_of(2) {
+            KEY_EVEN
+        } else {
+            KEY_ODD
+        };
+        i += 1;
+    }
+}
+
+/// Rejects key pairs that leave bytes unchanged or a single-byte mask, at compile time.
+const fn check_two_byte_key<const KEY_EVEN: u8, const KEY_ODD: u8>() {
+    const {
+        assert!(
+            KEY_EVEN != 0x00 && KEY_ODD != 0x00,
+            "XOR key of 0x00 leaves every other byte as plaintext"
+        );
+        assert!(KEY_EVEN != KEY_ODD, "equal even and odd keys are a single-byte XOR; use `Xor`");
+    };
+}
+
+/// Re-encrypts [`Xor2`] data with its even and odd keys on drop.
+pub struct ReEncrypt2<const KEY_EVEN: u8, const KEY_ODD: u8>;
+
+impl<const KEY_EVEN: u8, const KEY_ODD: u8> DropStrategy for ReEncrypt2<KEY_EVEN, KEY_ODD> {
+    type Extra = ();
+    const RESIDUE: Residue = Residue::Ciphertext;
 
-        // SAFETY: `buffer` is initialized and lives as long as `self`.
-        // Decryption is complete (either by us or another thread), so it's safe
-        // to return a shared reference.
-        unsafe { &*self.buffer.get() }
+    fn drop(data: &mut [u8], _extra: &()) {
+        apply_two_byte_key::<KEY_EVEN, KEY_ODD>(data);
     }
 }
 
-impl<const KEY: u8, D: DropStrategy<Extra = ()>, const N: usize> Deref
-    for Encrypted<Xor<KEY, D>, StringLiteral, N>
+/// An algorithm that XORs even-indexed bytes with `KEY_EVEN` and odd-indexed bytes
+/// with `KEY_ODD`.
+/// This algorithm is generic over drop strategy.
+///
+/// Like [`Xor`], the keys are const generics and nothing is stored alongside the
+/// buffer, but the ciphertext no longer shows the same mask on every byte. A zero
+/// key, or two equal keys, fail the build:
+///
+/// ```compile_fail
+/// use const_secret::{ByteArray, Encrypted, drop_strategy::Zeroize, xor::Xor2};
+///
+/// let _ = Encrypted::<Xor2<0xAA, 0xAA, Zeroize>, ByteArray, 3>::new(*b"abc");
+/// ```
+pub struct Xor2<const KEY_EVEN: u8, const KEY_ODD: u8, D: DropStrategy = Zeroize>(PhantomData<D>);
+
+impl<const KEY_EVEN: u8, const KEY_ODD: u8, D: DropStrategy<Extra = ()>> Algorithm
+    for Xor2<KEY_EVEN, KEY_ODD, D>
 {
-    type Target = str;
-
-    fn deref(&self) -> &Self::Target {
-        // Fast path: already decrypted
-        if self.decryption_state.load(Ordering::Acquire) == STATE_DECRYPTED {
-            // SAFETY: `buffer` is initialized and lives as long as `self`.
-            let bytes = unsafe { &*self.buffer.get() };
-            // SAFETY: Since the original input was a valid UTF-8 string literal, XOR with a single byte key will not produce invalid UTF-8. The length is also preserved, so the resulting bytes will still form a valid UTF-8 string.
-            return unsafe { core::str::from_utf8_unchecked(bytes) };
+    type Drop = D;
+    type Extra = ();
+
+    fn encrypt(data: &mut [u8], _extra: &()) {
+        check_two_byte_key::<KEY_EVEN, KEY_ODD>();
+        apply_two_byte_key::<KEY_EVEN, KEY_ODD>(data);
+    }
+}
+
+impl<const KEY_EVEN: u8, const KEY_ODD: u8, D: DropStrategy<Extra = ()>> StreamDecrypt
+    for Xor2<KEY_EVEN, KEY_ODD, D>
+{
+    type State = [u8; 0];
+
+    fn start(_extra: &()) -> [u8; 0] {
+        []
+    }
+
+    fn decrypt_byte(_state: &mut [u8; 0], index: usize, byte: u8) -> u8 {
+        byte ^ if index.is_multiple_of(2) {
+            KEY_EVEN
+        } else {
+            KEY_ODD
         }
+    }
 
-        // Try to acquire the decryption lock by transitioning from UNENCRYPTED to DECRYPTING
-        match self.decryption_state.compare_exchange(
-            STATE_UNENCRYPTED,
-            STATE_DECRYPTING,
-            Ordering::AcqRel,
-            Ordering::Acquire,
-        ) {
-            Ok(_) => {
-                // SAFETY: `buffer` is always initialized and points to valid `[u8; N]`.
-                // We won the race, perform decryption with exclusive mutable access.
-                let data = unsafe { &mut *self.buffer.get() };
-                for byte in data.iter_mut() {
-                    *byte ^= KEY;
-                }
+    fn skip(_state: &mut [u8; 0], _count: usize) {}
+}
 
-                // Decryption complete - release lock by transitioning to DECRYPTED
-                // Use Release ordering to ensure all decryption writes are visible to other threads
-                self.decryption_state.store(STATE_DECRYPTED, Ordering::Release);
-            }
-            Err(_) => {
-                // Lost the race - another thread is decrypting
-                // Spin-wait until decryption completes
-                while self.decryption_state.load(Ordering::Acquire) != STATE_DECRYPTED {
-                    core::hint::spin_loop();
-                }
-            }
+impl<const KEY_EVEN: u8, const KEY_ODD: u8, D: DropStrategy<Extra = ()>, M: Mode, const N: usize>
+    Encrypted<Xor2<KEY_EVEN, KEY_ODD, D>, M, N>
+{
+    /// Creates a new encrypted buffer by XOR-ing even-indexed bytes with `KEY_EVEN`
+    /// and odd-indexed bytes with `KEY_ODD`.
+    pub const fn new(mut buffer: [u8; N]) -> Self {
+        check_two_byte_key::<KEY_EVEN, KEY_ODD>();
+        check_plaintext::<M, N>(&buffer);
+        apply_two_byte_key::<KEY_EVEN, KEY_ODD>(&mut buffer);
+
+        Encrypted {
+            buffer: UnsafeCell::new(buffer),
+            decryption_state: AtomicU8::new(STATE_UNENCRYPTED),
+            extra: (),
+            _phantom: PhantomData,
         }
+    }
+}
 
-        // SAFETY: `buffer` is initialized and lives as long as `self`.
-        // Decryption is complete (either by us or another thread), so it's safe
-        // to return a shared reference.
-        let bytes = unsafe { &*self.buffer.get() };
+/// Re-encrypts [`XorRuntime`] data with its key on drop.
+pub struct ReEncryptRuntime;
 
-        // SAFETY: Since the original input was a valid UTF-8 string literal, XOR with a single byte key will not produce invalid UTF-8. The length is also preserved, so the resulting bytes will still form a valid UTF-8 string.
-        unsafe { core::str::from_utf8_unchecked(bytes) }
+impl DropStrategy for ReEncryptRuntime {
+    type Extra = u8;
+    const RESIDUE: Residue = Residue::Ciphertext;
+
+    fn drop(data: &mut [u8], key: &u8) {
+        xor_in_place(data, *key);
+    }
+}
+
+/// An algorithm that performs single-byte XOR with a key chosen at runtime.
+/// This algorithm is generic over drop strategy.
+///
+/// Works like [`Xor`], but the key is a `u8` stored alongside the encrypted data
+/// instead of a const generic, so it can be derived at startup (e.g. from a hardware
+/// ID) and never appears in the binary. Secrets are sealed at runtime with `new`, or
+/// adopted from ciphertext produced elsewhere with [`Encrypted::from_ciphertext`].
+pub struct XorRuntime<D: DropStrategy = Zeroize<u8>>(PhantomData<D>);
+
+impl<D: DropStrategy<Extra = u8>> Algorithm for XorRuntime<D> {
+    type Drop = D;
+    type Extra = u8;
+
+    fn encrypt(data: &mut [u8], key: &u8) {
+        xor_in_place(data, *key);
+    }
+
+    fn check_extra(key: &u8) {
+        check_runtime_key(*key);
+    }
+}
+
+/// Rejects the key 0, which would store the plaintext as is.
+fn check_runtime_key(key: u8) {
+    assert!(key != 0x00, "XorRuntime key of 0x00 is a no-op and provides no obfuscation");
+}
+
+impl<D: DropStrategy<Extra = u8>> StreamDecrypt for XorRuntime<D> {
+    type State = [u8; 1];
+
+    fn start(key: &u8) -> [u8; 1] {
+        [*key]
+    }
+
+    fn decrypt_byte(key: &mut [u8; 1], _index: usize, byte: u8) -> u8 {
+        byte ^ key[0]
+    }
+
+    fn skip(_key: &mut [u8; 1], _count: usize) {}
+}
+
+impl<D: DropStrategy<Extra = u8>, M: Mode, const N: usize> Encrypted<XorRuntime<D>, M, N> {
+    /// Creates a new encrypted buffer by XOR-ing the plaintext with `key`.
+    ///
+    /// Unlike [`Xor`]'s constructor this runs at runtime, so the plaintext is in the
+    /// binary unless it too is computed at runtime; combine it with
+    /// [`from_ciphertext`](Self::from_ciphertext) to ship only ciphertext.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is 0, or under the same conditions as [`Xor`]'s constructor.
+    pub fn new(mut buffer: [u8; N], key: u8) -> Self {
+        check_runtime_key(key);
+        check_plaintext::<M, N>(&buffer);
+        xor_in_place(&mut buffer, key);
+
+        Encrypted {
+            buffer: UnsafeCell::new(buffer),
+            decryption_state: AtomicU8::new(STATE_UNENCRYPTED),
+            extra: key,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Adopts `ciphertext` that was XOR-ed with `key` ahead of time, e.g. by a build
+    /// script, so the plaintext never appears in the binary.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is 0, or if the ciphertext does not decrypt to a valid
+    /// plaintext for the mode (UTF-8 for [`StringLiteral`](crate::StringLiteral), a C
+    /// string for [`CStrLiteral`](crate::CStrLiteral)), which usually means the key is
+    /// wrong.
+    pub fn from_ciphertext(ciphertext: [u8; N], key: u8) -> Self {
+        check_runtime_key(key);
+        if M::UTF8 || M::NUL_TERMINATED {
+            let mut scratch = Scratch(ciphertext);
+            xor_in_place(&mut scratch.0, key);
+            assert!(
+                is_valid_plaintext::<M>(&scratch.0),
+                "ciphertext does not decrypt to a valid plaintext for this mode"
+            );
+        }
+
+        Encrypted {
+            buffer: UnsafeCell::new(ciphertext),
+            decryption_state: AtomicU8::new(STATE_UNENCRYPTED),
+            extra: key,
+            _phantom: PhantomData,
+        }
     }
 }
 
 #[cfg(test)]
+#[allow(
+    clippy::borrow_interior_mutable_const,
+    clippy::declare_interior_mutable_const,
+    clippy::explicit_auto_deref
+)]
 mod tests {
     use super::*;
     use crate::{
@@ -233,9 +605,6 @@ mod tests {
     const CONST_ENCRYPTED_ZEROS: Encrypted<Xor<0xAA, Zeroize>, ByteArray, 4> =
         Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 4>::new([0, 0, 0, 0]);
 
-    const CONST_ENCRYPTED_NOOP_KEY: Encrypted<Xor<0x00, Zeroize>, ByteArray, 3> =
-        Encrypted::<Xor<0x00, Zeroize>, ByteArray, 3>::new(*b"abc");
-
     #[test]
     fn test_new_in_const_context() {
         let plain: &[u8; 5] = &*CONST_ENCRYPTED;
@@ -295,18 +664,6 @@ mod tests {
         assert_eq!(plain, &[0, 0, 0, 0]);
     }
 
-    #[test]
-    fn test_bytearray_deref_key_zero_is_identity() {
-        // A key of 0x00 means XOR is a no-op; buffer equals plaintext.
-        let pre_deref = CONST_ENCRYPTED_NOOP_KEY;
-        let raw = unsafe { &*pre_deref.buffer.get() };
-        assert_eq!(raw, b"abc", "key 0x00 should leave buffer unchanged");
-
-        let encrypted = CONST_ENCRYPTED_NOOP_KEY;
-        let plain: &[u8; 3] = &*encrypted;
-        assert_eq!(plain, b"abc");
-    }
-
     #[test]
     fn test_bytearray_multiple_derefs_are_idempotent() {
         let encrypted = CONST_ENCRYPTED;
@@ -456,4 +813,230 @@ mod tests {
             handle.join().unwrap();
         }
     }
+
+    #[test]
+    fn test_zeroize_now_wipes_buffer() {
+        let mut secret = Encrypted::<Xor<0xAA, ReEncrypt<0xAA>>, ByteArray, 4>::new([1, 2, 3, 4]);
+        assert_eq!(&*secret, &[1, 2, 3, 4]);
+
+        secret.zeroize_now();
+        let raw = unsafe { &*secret.buffer.get() };
+        assert_eq!(raw, &[0u8; 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "secret was wiped")]
+    fn test_deref_after_zeroize_now_panics() {
+        let mut secret = Encrypted::<Xor<0xAA, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        secret.zeroize_now();
+        let _: &str = &*secret;
+    }
+
+    const XORN_KEY: [u8; 4] = *b"key!";
+
+    const CONST_ENCRYPTED_N: Encrypted<XorN<4, Zeroize<[u8; 4]>>, ByteArray, 10> =
+        Encrypted::<XorN<4, Zeroize<[u8; 4]>>, ByteArray, 10>::new(*b"0123456789", XORN_KEY);
+
+    #[test]
+    fn test_xorn_cycles_key() {
+        let encrypted = CONST_ENCRYPTED_N;
+        let raw = unsafe { &*encrypted.buffer.get() };
+        for (i, byte) in raw.iter().enumerate() {
+            assert_eq!(*byte, b"0123456789"[i] ^ XORN_KEY[i % 4], "byte {i}");
+        }
+
+        let decrypted: &[u8; 10] = &*encrypted;
+        assert_eq!(decrypted, b"0123456789");
+    }
+
+    #[test]
+    fn test_xorn_string_deref_decrypts() {
+        let encrypted = Encrypted::<XorN<3, ReEncryptN<3>>, StringLiteral, 7>::new(
+            *b"hunter2",
+            [0x13, 0x37, 0x42],
+        );
+        let raw = unsafe { &*encrypted.buffer.get() };
+        assert_ne!(raw, b"hunter2", "buffer must NOT be plaintext before deref");
+
+        let decrypted: &str = &*encrypted;
+        assert_eq!(decrypted, "hunter2");
+    }
+
+    #[test]
+    fn test_xorn_reencrypt_drop() {
+        let mut data = *b"0123456789";
+        ReEncryptN::<4>::drop(&mut data, &XORN_KEY);
+        let encrypted = CONST_ENCRYPTED_N;
+        assert_eq!(&data, unsafe { &*encrypted.buffer.get() });
+    }
+
+    #[test]
+    fn test_xor_multi_alias() {
+        let data = *b"sixteen-byte-msg";
+        let encrypted =
+            Encrypted::<XorMulti<4, Zeroize<[u8; 4]>>, ByteArray, 16>::new(data, *b"key!");
+        let raw = unsafe { &*encrypted.buffer.get() };
+        // The 4-byte mask repeats every 4 bytes rather than every byte.
+        assert_eq!(raw[0] ^ data[0], raw[4] ^ data[4]);
+        assert_ne!(raw[0] ^ data[0], raw[1] ^ data[1]);
+
+        let decrypted: &[u8; 16] = &*encrypted;
+        assert_eq!(decrypted, &data);
+    }
+
+    const CONST_ENCRYPTED_2: Encrypted<Xor2<0xAA, 0x55, Zeroize>, StringLiteral, 5> =
+        Encrypted::<Xor2<0xAA, 0x55, Zeroize>, StringLiteral, 5>::new(*b"hello");
+
+    #[test]
+    fn test_xor2_alternates_keys() {
+        let encrypted = CONST_ENCRYPTED_2;
+        let raw = unsafe { *encrypted.buffer.get() };
+        for (i, byte) in raw.iter().enumerate() {
+            let key = if i.is_multiple_of(2) {
+                0xAA
+            } else {
+                0x55
+            };
+            assert_eq!(*byte, b"hello"[i] ^ key, "byte {i}");
+        }
+
+        let decrypted: &str = &*encrypted;
+        assert_eq!(decrypted, "hello");
+        assert_eq!(encrypted.decrypted_bytes().collect::<Vec<_>>(), b"hello");
+
+        let bytes = Encrypted::<Xor2<0x01, 0x02, Zeroize>, ByteArray, 3>::new([7, 8, 9]);
+        assert_eq!(unsafe { *bytes.buffer.get() }, [6, 10, 8]);
+        assert_eq!(&*bytes, &[7, 8, 9]);
+    }
+
+    #[test]
+    fn test_xor2_reencrypt_drop() {
+        let mut encrypted =
+            Encrypted::<Xor2<0x13, 0x37, ReEncrypt2<0x13, 0x37>>, ByteArray, 5>::new(*b"abcde");
+        let sealed = unsafe { *encrypted.buffer.get() };
+        assert_eq!(&*encrypted, b"abcde");
+
+        encrypted.reset();
+        assert_eq!(unsafe { *encrypted.buffer.get() }, sealed);
+        assert_eq!(&*encrypted, b"abcde");
+    }
+
+    const FROM_HEX: Encrypted<Xor<0xAA, Zeroize>, ByteArray, 4> =
+        Encrypted::<Xor<0xAA, Zeroize>, ByteArray, 4>::from_hex("deadbeef");
+
+    #[test]
+    fn test_runtime_xor_matches_const_new() {
+        // Crosses the SIMD threshold, with tails of every length after the last lane.
+        let plain: [u8; 200] = core::array::from_fn(|i| i as u8);
+        for len in [0, 1, 31, 32, 63, 64, 65, 95, 96, 127, 128, 200] {
+            let mut data = plain;
+            <Xor<0x5A> as Algorithm>::encrypt(&mut data[..len], &());
+            for (i, byte) in data.iter().enumerate() {
+                let expected = if i < len {
+                    plain[i] ^ 0x5A
+                } else {
+                    plain[i]
+                };
+                assert_eq!(*byte, expected, "len {len}, byte {i}");
+            }
+        }
+
+        let mut secret = Encrypted::<Xor<0x5A, ReEncrypt<0x5A>>, ByteArray, 200>::new(plain);
+        let sealed = unsafe { *secret.buffer.get() };
+        assert_eq!(&*secret, &plain);
+        secret.reset();
+        assert_eq!(unsafe { *secret.buffer.get() }, sealed);
+    }
+
+    #[test]
+    #[should_panic(expected = "XorN key repeats a single byte")]
+    fn test_xorn_rejects_repeated_byte_key() {
+        let _ = Encrypted::<XorN<3, Zeroize<[u8; 3]>>, ByteArray, 2>::new([1, 2], [5, 5, 5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "XorN key of all zeros is a no-op")]
+    fn test_xorn_rejects_zero_key() {
+        let _ = Encrypted::<XorN<1, Zeroize<[u8; 1]>>, ByteArray, 2>::new([1, 2], [0]);
+    }
+
+    #[test]
+    fn test_xor_runtime_key() {
+        let key = u8::try_from(std::env::args().count()).unwrap().wrapping_add(0x40);
+        let secret = Encrypted::<XorRuntime, StringLiteral, 5>::new(*b"hello", key);
+        assert_eq!(unsafe { *secret.buffer.get() }, b"hello".map(|byte| byte ^ key));
+        assert_eq!(&*secret, "hello");
+
+        let mut reencrypt =
+            Encrypted::<XorRuntime<ReEncryptRuntime>, ByteArray, 3>::new([1, 2, 3], 0x5A);
+        let sealed = unsafe { *reencrypt.buffer.get() };
+        assert_eq!(&*reencrypt, &[1, 2, 3]);
+        reencrypt.reset();
+        assert_eq!(unsafe { *reencrypt.buffer.get() }, sealed);
+
+        reencrypt.rotate_key(0x33);
+        assert_eq!(unsafe { *reencrypt.buffer.get() }, [1 ^ 0x33, 2 ^ 0x33, 3 ^ 0x33]);
+        assert_eq!(&*reencrypt, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_runtime_keys_are_checked() {
+        use std::panic::{AssertUnwindSafe, catch_unwind};
+
+        let mut multi = Encrypted::<XorN<2, ReEncryptN<2>>, ByteArray, 3>::new([1, 2, 3], [9, 8]);
+        for bad_key in [[0, 0], [7, 7]] {
+            let result = catch_unwind(AssertUnwindSafe(|| multi.rotate_key(bad_key)));
+            assert!(result.is_err(), "rotate_key must reject {bad_key:?}");
+        }
+        assert_eq!(multi.extra, [9, 8], "a rejected key must not be installed");
+        assert_eq!(&*multi, &[1, 2, 3]);
+
+        let mut runtime = Encrypted::<XorRuntime, ByteArray, 2>::new([1, 2], 0x5A);
+        assert!(catch_unwind(AssertUnwindSafe(|| runtime.rotate_key(0))).is_err());
+        assert_eq!(&*runtime, &[1, 2]);
+
+        let new = catch_unwind(|| Encrypted::<XorRuntime, ByteArray, 2>::new([1, 2], 0));
+        assert!(new.is_err());
+        let adopted =
+            catch_unwind(|| Encrypted::<XorRuntime, ByteArray, 2>::from_ciphertext([1, 2], 0));
+        assert!(adopted.is_err());
+    }
+
+    #[test]
+    fn test_decrypted_bytes_multi_byte_and_runtime_keys() {
+        let multi = Encrypted::<XorN<3, Zeroize<[u8; 3]>>, ByteArray, 7>::new(
+            [1, 2, 3, 4, 5, 6, 7],
+            [0x10, 0x20, 0x30],
+        );
+        assert!(multi.decrypted_bytes().eq([1, 2, 3, 4, 5, 6, 7]));
+
+        let runtime = Encrypted::<XorRuntime, StringLiteral, 5>::new(*b"hello", 0x42);
+        assert!(runtime.decrypted_bytes().eq(*b"hello"));
+        assert!(!runtime.is_decrypted());
+    }
+
+    #[test]
+    fn test_xor_runtime_from_ciphertext() {
+        let ciphertext = b"hello".map(|byte| byte ^ 0x21);
+        let secret = Encrypted::<XorRuntime, StringLiteral, 5>::from_ciphertext(ciphertext, 0x21);
+        assert_eq!(&*secret, "hello");
+
+        let bytes = Encrypted::<XorRuntime, ByteArray, 2>::from_ciphertext([0xFF, 0xFE], 1);
+        assert_eq!(&*bytes, &[0xFE, 0xFF]);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not decrypt to a valid plaintext")]
+    fn test_xor_runtime_from_ciphertext_wrong_key_panics() {
+        let ciphertext = b"hi".map(|byte| byte ^ 0x21);
+        let _ = Encrypted::<XorRuntime, StringLiteral, 2>::from_ciphertext(ciphertext, 0xA1);
+    }
+
+    #[test]
+    fn test_xor_from_hex() {
+        let encrypted = FROM_HEX;
+        let raw = unsafe { *encrypted.buffer.get() };
+        assert_eq!(raw, [0xde ^ 0xAA, 0xad ^ 0xAA, 0xbe ^ 0xAA, 0xef ^ 0xAA]);
+        assert_eq!(&*encrypted, &[0xde, 0xad, 0xbe, 0xef]);
+    }
 }
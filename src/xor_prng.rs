@@ -0,0 +1,183 @@
+//! XOR with a per-byte keystream generated from a compile-time seed.
+//!
+//! This module provides an algorithm that XORs the plaintext with the output of a small
+//! pseudorandom generator. Like [`Xor`](crate::xor::Xor) it is not cryptographically
+//! secure, only an obfuscation, but every byte gets its own mask.
+//!
+//! # Algorithm
+//!
+//! [`XorPrng`] seeds a xorshift64 generator from `SEED` (scrambled with splitmix64, so
+//! that nearby seeds give unrelated streams) and XORs each 8 bytes of the buffer with
+//! the next generator output. Decryption runs the same generator again. A single-byte
+//! XOR key has only 256 possibilities and repeats across the whole ciphertext; here
+//! the mask does not repeat, yet the only key material is the 8-byte seed, which is
+//! part of the type rather than stored next to the buffer.
+//!
+//! # Types
+//!
+//! - [`XorPrng<SEED, D>`]: The algorithm type with const generic seed and drop strategy
+//! - [`ReEncrypt<SEED>`]: A drop strategy that re-encrypts data on drop
+//!
+//! # Example
+//!
+//! ```rust
+//! use const_secret::{
+//!     Encrypted, StringLiteral,
+//!     drop_strategy::Zeroize,
+//!     xor_prng::{ReEncrypt, XorPrng},
+//! };
+//!
+//! const SEED: u64 = 0x1234_5678_9abc_def0;
+//!
+//! const SECRET: Encrypted<XorPrng<SEED, Zeroize>, StringLiteral, 5> =
+//!     Encrypted::<XorPrng<SEED, Zeroize>, StringLiteral, 5>::new(*b"hello");
+//!
+//! const SECRET2: Encrypted<XorPrng<SEED, ReEncrypt<SEED>>, StringLiteral, 6> =
+//!     Encrypted::<XorPrng<SEED, ReEncrypt<SEED>>, StringLiteral, 6>::new(*b"secret");
+//!
+//! fn main() {
+//!     assert_eq!(&*SECRET, "hello");
+//!     assert_eq!(&*SECRET2, "secret");
+//! }
+//! ```
+
+use core::{cell::UnsafeCell, marker::PhantomData, sync::atomic::AtomicU8};
+
+use crate::{
+    Algorithm, Encrypted, Mode, STATE_UNENCRYPTED, check_plaintext,
+    drop_strategy::{DropStrategy, Residue, Zeroize},
+};
+
+/// Re-encrypts [`XorPrng`] data on drop, restoring the ciphertext.
+pub struct ReEncrypt<const SEED: u64>;
+
+impl<const SEED: u64> DropStrategy for ReEncrypt<SEED> {
+    type Extra = ();
+    const RESIDUE: Residue = Residue::Ciphertext;
+
+    fn drop(data: &mut [u8], _extra: &()) {
+        apply_keystream(data, SEED);
+    }
+}
+
+/// The xorshift64 generator behind [`XorPrng`] and
+/// [`RandomFill`](crate::drop_strategy::RandomFill).
+pub(crate) struct Xorshift64(u64);
+
+impl Xorshift64 {
+    /// Seeds the generator from `seed`, scrambled with splitmix64 so that nearby seeds
+    /// start far apart. The state is never zero, where xorshift would get stuck.
+    pub(crate) const fn new(seed: u64) -> Self {
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        Self((z ^ (z >> 31)) | 1)
+    }
+
+    /// Advances the generator and returns the next 8 bytes of output.
+    pub(crate) const fn next_bytes(&mut self) -> [u8; 8] {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0.to_le_bytes()
+    }
+}
+
+/// XORs `data` with the keystream generated from `seed`.
+const fn apply_keystream(data: &mut [u8], seed: u64) {
+    let mut generator = Xorshift64::new(seed);
+    let mut mask = [0u8; 8];
+    // We use a while loop because const contexts do not allow for-loops.
+    let mut i = 0;
+    while i < data.len() {
+        if i % 8 == 0 {
+            mask = generator.next_bytes();
+        }
+        data[i] ^= mask[i % 8];
+        i += 1;
+    }
+}
+
+/// An algorithm that XORs each byte with a keystream generated from `SEED`.
+/// This algorithm is generic over drop strategy.
+pub struct XorPrng<const SEED: u64, D: DropStrategy = Zeroize>(PhantomData<D>);
+
+impl<const SEED: u64, D: DropStrategy<Extra = ()>> Algorithm for XorPrng<SEED, D> {
+    type Drop = D;
+    type Extra = ();
+
+    fn encrypt(data: &mut [u8], _extra: &()) {
+        apply_keystream(data, SEED);
+    }
+}
+
+impl<const SEED: u64, D: DropStrategy<Extra = ()>, M: Mode, const N: usize>
+    Encrypted<XorPrng<SEED, D>, M, N>
+{
+    /// Creates a new encrypted buffer by XOR-ing it with the keystream from `SEED`.
+    pub const fn new(mut buffer: [u8; N]) -> Self {
+        check_plaintext::<M, N>(&buffer);
+        apply_keystream(&mut buffer, SEED);
+
+        Encrypted {
+            buffer: UnsafeCell::new(buffer),
+            decryption_state: AtomicU8::new(STATE_UNENCRYPTED),
+            extra: (),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::explicit_auto_deref)]
+mod tests {
+    use super::*;
+    use crate::{ByteArray, StringLiteral, builder::SealingBuilder};
+
+    #[test]
+    fn test_round_trip() {
+        let encrypted = Encrypted::<XorPrng<42, Zeroize>, StringLiteral, 5>::new(*b"hello");
+        assert_ne!(unsafe { *encrypted.buffer.get() }, *b"hello");
+        assert_eq!(&*encrypted, "hello");
+    }
+
+    #[test]
+    fn test_mask_differs_per_byte() {
+        let encrypted = Encrypted::<XorPrng<7, Zeroize>, ByteArray, 32>::new([0; 32]);
+        let mask = unsafe { *encrypted.buffer.get() };
+        assert!(mask.windows(2).any(|pair| pair[0] != pair[1]));
+        assert_ne!(mask[..8], mask[8..16], "the keystream must not repeat every word");
+    }
+
+    #[test]
+    fn test_seeds_give_different_ciphertext() {
+        let one = Encrypted::<XorPrng<1, Zeroize>, ByteArray, 16>::new([0; 16]);
+        let two = Encrypted::<XorPrng<2, Zeroize>, ByteArray, 16>::new([0; 16]);
+        assert_ne!(unsafe { *one.buffer.get() }, unsafe { *two.buffer.get() });
+    }
+
+    #[test]
+    fn test_reencrypt_restores_ciphertext() {
+        let mut encrypted =
+            Encrypted::<XorPrng<99, ReEncrypt<99>>, StringLiteral, 11>::new(*b"hello world");
+        let sealed = unsafe { *encrypted.buffer.get() };
+        assert_eq!(&*encrypted, "hello world");
+
+        encrypted.reset();
+        assert_eq!(unsafe { *encrypted.buffer.get() }, sealed);
+        assert_eq!(&*encrypted, "hello world");
+    }
+
+    #[test]
+    fn test_runtime_sealing_matches_const_new() {
+        let mut builder: SealingBuilder<XorPrng<5, Zeroize>, ByteArray, 12> =
+            Encrypted::with_capacity_sealed();
+        builder.push_segment(b"split ").unwrap();
+        builder.push_segment(b"across").unwrap();
+        let sealed = builder.finish(()).unwrap();
+
+        let expected = Encrypted::<XorPrng<5, Zeroize>, ByteArray, 12>::new(*b"split across");
+        assert_eq!(unsafe { *sealed.buffer.get() }, unsafe { *expected.buffer.get() });
+        assert_eq!(&*sealed, b"split across");
+    }
+}